@@ -0,0 +1,13 @@
+fn main() {
+    // Regenerate the C header only when the C API is enabled, so ordinary builds need no cbindgen
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR");
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).expect("cbindgen.toml is valid");
+        cbindgen::generate_with_config(&crate_dir, config)
+            .expect("failed to generate the C header")
+            .write_to_file(format!("{}/include/asciigen.h", crate_dir));
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
@@ -0,0 +1,99 @@
+#ifndef ASCIIGEN_H
+#define ASCIIGEN_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Opaque handle to a genetic algorithm run, created by `asciigen_session_new`
+typedef struct AsciigenSession AsciigenSession;
+
+// Settings for `asciigen_session_new`; start from `asciigen_config_default()` and override fields
+typedef struct AsciigenConfig {
+  // Width of the art in characters; the height follows the image's aspect ratio
+  uint32_t width;
+  // Population size (20-1000)
+  uint32_t population;
+  // Threads used for fitness evaluation
+  uint32_t threads;
+  // Font size in pixels used for fitness rendering (4-64)
+  float font_size;
+  // True to optimize for dark characters on a white background
+  bool white_background;
+  // True to invert the source image
+  bool invert_source;
+  // True to use `seed` for a reproducible run; otherwise a random seed is chosen
+  bool use_seed;
+  uint64_t seed;
+  // NUL-terminated UTF-8 character set, or NULL for the built-in set
+  const char *charset;
+} AsciigenConfig;
+
+// Returns the default settings: 40 characters wide, population 80, 4 threads, 12 pixel font, built-in set
+struct AsciigenConfig asciigen_config_default(void);
+
+// Decodes an encoded image (PNG, JPEG, GIF, ...) of `image_len` bytes and prepares a run
+// Returns NULL on failure; `asciigen_last_error()` then describes the problem
+// The returned session must be released with `asciigen_session_free`
+//
+// # Safety
+// `image_data` must point to `image_len` readable bytes and `config` must be NULL or a valid pointer whose
+// `charset` is NULL or a NUL-terminated string
+struct AsciigenSession *asciigen_session_new(const uint8_t *image_data,
+                                             size_t image_len,
+                                             const struct AsciigenConfig *config);
+
+// Releases a session; NULL is ignored
+//
+// # Safety
+// `session` must be NULL or a pointer returned by `asciigen_session_new` that has not been freed
+void asciigen_session_free(struct AsciigenSession *session);
+
+// Runs the given number of generations and returns the best fitness afterwards (0.0 to 1.0)
+//
+// # Safety
+// `session` must be a live pointer returned by `asciigen_session_new`
+double asciigen_session_step(struct AsciigenSession *session, uint32_t generations);
+
+// Returns the best fitness so far (0.0 to 1.0)
+//
+// # Safety
+// `session` must be a live pointer returned by `asciigen_session_new`
+double asciigen_session_fitness(const struct AsciigenSession *session);
+
+// Returns the number of generations run so far
+//
+// # Safety
+// `session` must be a live pointer returned by `asciigen_session_new`
+uint32_t asciigen_session_generation(const struct AsciigenSession *session);
+
+// Writes the art size in characters to `width` and `height`; either may be NULL
+//
+// # Safety
+// `session` must be a live pointer returned by `asciigen_session_new`; `width` and `height` must be NULL or writable
+void asciigen_session_dimensions(const struct AsciigenSession *session,
+                                 uint32_t *width,
+                                 uint32_t *height);
+
+// Returns the best art so far as a NUL-terminated UTF-8 string, rows separated by '\n'
+// The caller owns the string and must release it with `asciigen_string_free`
+//
+// # Safety
+// `session` must be a live pointer returned by `asciigen_session_new`
+char *asciigen_session_best_art(const struct AsciigenSession *session);
+
+// Releases a string returned by this library; NULL is ignored
+//
+// # Safety
+// `s` must be NULL or a string returned by `asciigen_session_best_art` that has not been freed
+void asciigen_string_free(char *s);
+
+// Returns a description of the last error on the calling thread, or NULL if there was none
+// The string is owned by the library and stays valid until the next failing call on the same thread
+const char *asciigen_last_error(void);
+
+#endif  /* ASCIIGEN_H */
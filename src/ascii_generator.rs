@@ -1,45 +1,74 @@
 use image::{ImageBuffer, Luma};
 use rusttype::{Font, Scale, point};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use crate::charset::{Charset, BRAILLE_BLANK};
+
+/// Font size in pixels used for fitness rendering unless overridden
+pub const DEFAULT_FONT_SIZE: f32 = 12.0;
+
+/// Intensity of the lines separating cells in `glyph_atlas`, distinct from both backgrounds
+const ATLAS_GRID_INTENSITY: u8 = 128;
 
 /// Generator for ASCII art that converts characters to image buffers and manages character rendering
+/// Character codes passed in and out are indices into the active `Charset`
 pub struct AsciiGenerator {
     font: Font<'static>,
     scale: Scale,
     char_width: u32,
     char_height: u32,
-    char_cache: HashMap<u8, ImageBuffer<Luma<u8>, Vec<u8>>>,
+    char_cache: Vec<ImageBuffer<Luma<u8>, Vec<u8>>>,
+    charset: Charset,
+    density_ramp: Vec<u8>,
+}
+
+impl Default for AsciiGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AsciiGenerator {
-    /// Creates a new ASCII generator with a monospace font at 12pt
+    /// Creates a new ASCII generator with a monospace font at the default size using the default character set
     pub fn new() -> Self {
-        let font = Self::load_font();
-
-        let scale = Scale::uniform(12.0);
-
-        // Calculate character dimensions for monospace font
-        let glyph = font.glyph('M').scaled(scale);
-        let h_metrics = glyph.h_metrics();
-        let char_width = h_metrics.advance_width.ceil() as u32;
-        let char_height = (scale.y * 1.2).ceil() as u32; // Add line spacing
-
-        println!("Font scale: {}, advance_width: {}, calculated char_width: {}, char_height: {}",
-                 scale.y, h_metrics.advance_width, char_width, char_height);
+        Self::with_charset(Charset::default())
+    }
 
+    /// Creates a new ASCII generator restricted to the given character set
+    pub fn with_charset(charset: Charset) -> Self {
         let mut generator = Self {
-            font,
-            scale,
-            char_width,
-            char_height,
-            char_cache: HashMap::new(),
+            font: Self::load_font(),
+            scale: Scale::uniform(DEFAULT_FONT_SIZE),
+            char_width: 0,
+            char_height: 0,
+            char_cache: Vec::new(),
+            charset,
+            density_ramp: Vec::new(),
         };
 
-        // Pre-cache all ASCII characters from 0x20 to 0x7F
-        generator.build_char_cache();
+        generator.set_font_size(DEFAULT_FONT_SIZE);
         generator
     }
 
+    /// Changes the font size used to render glyphs for fitness evaluation
+    /// Larger glyphs capture more detail per cell; smaller ones make large grids cheaper to evaluate
+    pub fn set_font_size(&mut self, font_size: f32) {
+        self.scale = Scale::uniform(font_size);
+
+        // Calculate character dimensions for monospace font
+        let glyph = self.font.glyph('M').scaled(self.scale);
+        self.char_width = (glyph.h_metrics().advance_width.ceil() as u32).max(1);
+        self.char_height = ((self.scale.y * 1.2).ceil() as u32).max(1); // Add line spacing
+
+        // Pre-cache every character of the active set
+        self.build_char_cache();
+        self.density_ramp = self.rank_by_coverage();
+    }
+
+    /// Returns the font size in pixels used for fitness rendering
+    pub fn font_size(&self) -> f32 {
+        self.scale.y
+    }
+
     /// Loads the font, with fallback for testing
     fn load_font() -> Font<'static> {
         // Use embedded font data
@@ -48,16 +77,30 @@ impl AsciiGenerator {
             .expect("Failed to load embedded font")
     }
 
-    /// Pre-renders all 7-bit ASCII characters starting at 0x20 and caches them
+    /// Pre-renders every character of the active set and caches them by code
     fn build_char_cache(&mut self) {
-        for ascii_code in 0x20..=0x7F {
-            let char_img = self.render_char(ascii_code as char);
-            self.char_cache.insert(ascii_code, char_img);
-        }
+        self.char_cache = self.charset.chars()
+            .iter()
+            .map(|&ch| self.render_char(ch))
+            .collect();
+    }
+
+    /// Returns the cached rendering of a character, light on dark and one cell in size
+    pub fn glyph(&self, char_code: u8) -> Option<&ImageBuffer<Luma<u8>, Vec<u8>>> {
+        self.char_cache.get(char_code as usize)
+    }
+
+    /// Returns the character set the generators are allowed to place
+    pub fn charset(&self) -> &Charset {
+        &self.charset
     }
 
     /// Renders a single character to a grayscale image buffer
     fn render_char(&self, ch: char) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        if let Some(glyph) = render_procedural_glyph(ch, self.char_width, self.char_height) {
+            return glyph;
+        }
+
         let mut img = ImageBuffer::new(self.char_width, self.char_height);
 
         // Fill with black background (default)
@@ -89,15 +132,30 @@ impl AsciiGenerator {
 
     /// Generates an ASCII art image buffer with optional white background
     pub fn generate_ascii_image_with_background(&self, chars: &[u8], width: u32, height: u32, white_background: bool) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let mut result = ImageBuffer::new(0, 0);
+        self.render_into(chars, width, height, white_background, &mut result);
+        result
+    }
+
+    /// Renders ASCII art into an existing buffer, reallocating only when the required dimensions change
+    /// Lets hot loops reuse one buffer instead of allocating a full-size image per render
+    pub fn render_into(
+        &self,
+        chars: &[u8],
+        width: u32,
+        height: u32,
+        white_background: bool,
+        buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>,
+    ) {
         let img_width = width * self.char_width;
         let img_height = height * self.char_height;
-        let mut result = ImageBuffer::new(img_width, img_height);
+        if buffer.dimensions() != (img_width, img_height) {
+            *buffer = ImageBuffer::new(img_width, img_height);
+        }
 
         // Fill with background color
         let bg_color = if white_background { 255u8 } else { 0u8 };
-        for pixel in result.pixels_mut() {
-            *pixel = Luma([bg_color]);
-        }
+        buffer.fill(bg_color);
 
         for (i, &char_code) in chars.iter().enumerate() {
             let x = (i as u32) % width;
@@ -107,37 +165,41 @@ impl AsciiGenerator {
                 break;
             }
 
-            if let Some(char_img) = self.char_cache.get(&char_code) {
-                if white_background {
-                    // Invert the cached character image for white background
-                    let mut inverted_img = char_img.clone();
-                    for pixel in inverted_img.pixels_mut() {
-                        pixel[0] = 255 - pixel[0]; // Invert pixel intensity
-                    }
-                    self.copy_char_to_image(&mut result, &inverted_img, x * self.char_width, y * self.char_height);
-                } else {
-                    self.copy_char_to_image(&mut result, char_img, x * self.char_width, y * self.char_height);
-                }
+            if let Some(char_img) = self.char_cache.get(char_code as usize) {
+                self.copy_char_to_image(buffer, char_img, x * self.char_width, y * self.char_height, white_background);
             }
         }
-
-        result
     }
 
-    /// Copies a character image to a specific position in the target image
+    /// Copies a character image to a specific position in the target image, inverting it for white backgrounds
+    /// The glyph is clipped to the target bounds; its own dimensions define the cell size
     fn copy_char_to_image(
         &self,
         target: &mut ImageBuffer<Luma<u8>, Vec<u8>>,
         char_img: &ImageBuffer<Luma<u8>, Vec<u8>>,
         start_x: u32,
         start_y: u32,
+        white_background: bool,
     ) {
-        for y in 0..self.char_height {
-            for x in 0..self.char_width {
-                if start_x + x < target.width() && start_y + y < target.height() {
-                    let pixel = char_img.get_pixel(x, y);
-                    target.put_pixel(start_x + x, start_y + y, *pixel);
+        let stride = target.width() as usize;
+        let copy_width = char_img.width().min(target.width().saturating_sub(start_x)) as usize;
+        let copy_height = char_img.height().min(target.height().saturating_sub(start_y));
+        let glyph = char_img.as_raw();
+        let pixels: &mut [u8] = target;
+
+        for y in 0..copy_height {
+            let src_start = (y * char_img.width()) as usize;
+            let src = &glyph[src_start..src_start + copy_width];
+            let dst_start = (start_y + y) as usize * stride + start_x as usize;
+            let dst = &mut pixels[dst_start..dst_start + copy_width];
+
+            if white_background {
+                // Invert pixel intensity for black characters on white
+                for (d, &v) in dst.iter_mut().zip(src) {
+                    *d = 255 - v;
                 }
+            } else {
+                dst.copy_from_slice(src);
             }
         }
     }
@@ -147,22 +209,73 @@ impl AsciiGenerator {
         let mut result = String::new();
 
         for (i, &char_code) in individual.chars.iter().enumerate() {
-            if i > 0 && (i as u32) % width == 0 {
+            if i > 0 && (i as u32).is_multiple_of(width) {
                 result.push('\n');
             }
-            result.push(char_code as char);
+            result.push(self.charset.char_at(char_code));
         }
 
         result
     }
 
+    /// Returns the fraction of a character cell covered by ink (0.0 = empty, 1.0 = solid)
+    pub fn glyph_coverage(&self, char_code: u8) -> f64 {
+        match self.char_cache.get(char_code as usize) {
+            Some(char_img) => {
+                let ink: u64 = char_img.as_raw().iter().map(|&v| v as u64).sum();
+                ink as f64 / (char_img.as_raw().len() as f64 * 255.0)
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Returns the codes of the active character set ordered from lightest to densest glyph coverage
+    pub fn density_ramp(&self) -> &[u8] {
+        &self.density_ramp
+    }
+
+    /// Sorts the active set's codes by measured glyph coverage, lightest first
+    fn rank_by_coverage(&self) -> Vec<u8> {
+        let mut ranked: Vec<(u8, f64)> = (0..self.charset.len())
+            .map(|i| (i as u8, self.glyph_coverage(i as u8)))
+            .collect();
+        ranked.sort_by(|a, b| {
+            a.1.partial_cmp(&b.1)
+                .unwrap_or(Ordering::Equal)
+                .then(self.charset.char_at(a.0).cmp(&self.charset.char_at(b.0)))
+        });
+        ranked.into_iter().map(|(c, _)| c).collect()
+    }
+
+    /// Renders every character of the set as a contact sheet of `columns` cells per row, in character set order
+    /// Cells are drawn at the fitness font size and separated by one-pixel grey lines so glyph bounds stay visible
+    pub fn glyph_atlas(&self, columns: u32, white_background: bool) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let columns = columns.clamp(1, (self.char_cache.len() as u32).max(1));
+        let rows = (self.char_cache.len() as u32).div_ceil(columns);
+        let mut atlas = ImageBuffer::from_pixel(
+            columns * (self.char_width + 1) + 1,
+            rows * (self.char_height + 1) + 1,
+            Luma([ATLAS_GRID_INTENSITY]),
+        );
+        for (i, char_img) in self.char_cache.iter().enumerate() {
+            let (x, y) = ((i as u32 % columns) * (self.char_width + 1) + 1, (i as u32 / columns) * (self.char_height + 1) + 1);
+            let bg_color = if white_background { 255u8 } else { 0u8 };
+            for pixel_y in y..y + self.char_height {
+                for pixel_x in x..x + self.char_width {
+                    atlas.put_pixel(pixel_x, pixel_y, Luma([bg_color]));
+                }
+            }
+            self.copy_char_to_image(&mut atlas, char_img, x, y, white_background);
+        }
+        atlas
+    }
+
     /// Returns the dimensions of a single character in pixels
     pub fn char_dimensions(&self) -> (u32, u32) {
         (self.char_width, self.char_height)
     }
 
     /// Generates a larger ASCII art image for debug purposes with optional white background
-    #[allow(dead_code)]
     pub fn generate_debug_ascii_image_with_background(&self, chars: &[u8], width: u32, height: u32, white_background: bool) -> ImageBuffer<Luma<u8>, Vec<u8>> {
         // Use larger font size for debug images (3x larger)
         let debug_char_width = self.char_width * 3;
@@ -180,7 +293,7 @@ impl AsciiGenerator {
         // Render each character at larger size
         let font_data = include_bytes!("../assets/DejaVuSansMono.ttf");
         let font = Font::try_from_bytes(font_data).expect("Failed to load font");
-        let scale = rusttype::Scale::uniform(36.0); // 3x larger than normal (12.0 * 3)
+        let scale = rusttype::Scale::uniform(self.scale.y * 3.0); // 3x larger than normal
 
         for (i, &char_code) in chars.iter().enumerate() {
             let x = (i as u32) % width;
@@ -190,7 +303,12 @@ impl AsciiGenerator {
                 break;
             }
 
-            let ch = char_code as char;
+            let ch = self.charset.char_at(char_code);
+            if let Some(glyph) = render_procedural_glyph(ch, debug_char_width, debug_char_height) {
+                self.copy_char_to_image(&mut result, &glyph, x * debug_char_width, y * debug_char_height, white_background);
+                continue;
+            }
+
             let glyph = font.glyph(ch).scaled(scale);
 
             // Position character with proper baseline, similar to how render_char works
@@ -202,8 +320,8 @@ impl AsciiGenerator {
                     let start_y = y * debug_char_height;
 
                     positioned_glyph.draw(|px, py, v| {
-                        let draw_x = px as i32 + pixel_bb.min.x as i32 + start_x as i32;
-                        let draw_y = py as i32 + pixel_bb.min.y as i32 + start_y as i32;
+                        let draw_x = px as i32 + pixel_bb.min.x + start_x as i32;
+                        let draw_y = py as i32 + pixel_bb.min.y + start_y as i32;
 
                         if draw_x >= 0 && draw_y >= 0 && (draw_x as u32) < img_width && (draw_y as u32) < img_height {
                             let intensity = if white_background {
@@ -222,15 +340,79 @@ impl AsciiGenerator {
     }
 }
 
+/// Draws block elements and braille patterns procedurally so they line up with the character cell
+/// Returns None for characters that should be rendered from the font
+fn render_procedural_glyph(ch: char, width: u32, height: u32) -> Option<ImageBuffer<Luma<u8>, Vec<u8>>> {
+    if (BRAILLE_BLANK..='\u{28FF}').contains(&ch) {
+        return Some(render_braille(ch as u32 as u8, width, height));
+    }
+    render_block_element(ch, width, height)
+}
+
+/// Renders a braille pattern as a 2x4 grid of sub-cells, each lit when its dot is raised
+/// Dots fill their whole sub-cell so each one acts as a pixel during fitness evaluation
+fn render_braille(dots: u8, width: u32, height: u32) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+    // Bit index of the dot at (column, row), following the Unicode dot numbering 1-8
+    const DOT_BITS: [[u8; 4]; 2] = [[0, 1, 2, 6], [3, 4, 5, 7]];
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let col = (x * 2 / width).min(1) as usize;
+        let row = (y * 4 / height).min(3) as usize;
+        let raised = dots & (1 << DOT_BITS[col][row]) != 0;
+        Luma([if raised { 255 } else { 0 }])
+    })
+}
+
+/// Draws Unicode block elements so they fill the character cell exactly
+/// Font outlines for these glyphs rarely line up with the cell, and shades are approximated
+/// by their average intensity rather than a dither pattern
+fn render_block_element(ch: char, width: u32, height: u32) -> Option<ImageBuffer<Luma<u8>, Vec<u8>>> {
+    let intensity = match ch {
+        '█' | '▀' | '▄' | '▌' | '▐' => 255u8,
+        '▓' => 191,
+        '▒' => 128,
+        '░' => 64,
+        _ => return None,
+    };
+
+    Some(ImageBuffer::from_fn(width, height, |x, y| {
+        let inked = match ch {
+            '▀' => y < height / 2,
+            '▄' => y >= height / 2,
+            '▌' => x < width / 2,
+            '▐' => x >= width / 2,
+            _ => true,
+        };
+        Luma([if inked { intensity } else { 0 }])
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::charset::{CharsetPreset, ALLOWED_CHARS};
 
     #[test]
     fn test_ascii_generator_creation() {
         let generator = AsciiGenerator::new();
         assert!(!generator.char_cache.is_empty());
-        assert!(generator.char_cache.len() >= 95); // 0x20 to 0x7F
+        assert_eq!(generator.char_cache.len(), ALLOWED_CHARS.len()); // Only the active set is cached
+    }
+
+    #[test]
+    fn test_glyph_atlas_lays_out_every_glyph() {
+        let generator = AsciiGenerator::with_charset(Charset::parse(" .:#@").unwrap());
+        let (char_width, char_height) = generator.char_dimensions();
+        let atlas = generator.glyph_atlas(2, false);
+        assert_eq!(atlas.dimensions(), (2 * (char_width + 1) + 1, 3 * (char_height + 1) + 1));
+        assert_eq!(atlas.get_pixel(0, 0)[0], ATLAS_GRID_INTENSITY);
+
+        // The third glyph starts the second row, just inside the grid lines
+        let origin = (1, char_height + 2);
+        let glyph = generator.glyph(2).unwrap();
+        assert!(glyph.enumerate_pixels().all(|(x, y, pixel)| atlas.get_pixel(origin.0 + x, origin.1 + y) == pixel));
+        // The unused last cell keeps the grid color
+        assert_eq!(atlas.get_pixel(char_width + 2, 2 * char_height + 3)[0], ATLAS_GRID_INTENSITY);
     }
 
     #[test]
@@ -244,7 +426,7 @@ mod tests {
     #[test]
     fn test_generate_ascii_image() {
         let generator = AsciiGenerator::new();
-        let chars = vec![b'A', b'B', b'C', b'D'];
+        let chars = generator.charset().encode("AbCD").unwrap();
         let result = generator.generate_ascii_image(&chars, 2, 2);
 
         let (char_width, char_height) = generator.char_dimensions();
@@ -253,17 +435,99 @@ mod tests {
     }
 
     #[test]
-    fn test_individual_to_string() {
+    fn test_render_into_reuses_buffer_and_matches_fresh_render() {
         let generator = AsciiGenerator::new();
-        let individual = crate::genetic_algorithm::Individual {
-            chars: vec![b'H', b'i', b'!', b' '],
-            fitness: 0.0,
-        };
+        let chars = generator.charset().encode("A# o").unwrap();
+        let mut buffer = ImageBuffer::new(0, 0);
+
+        generator.render_into(&chars, 2, 2, true, &mut buffer);
+        let expected = generator.generate_ascii_image_with_background(&chars, 2, 2, true);
+        assert_eq!(buffer, expected);
+
+        // Re-rendering different content into the same buffer must fully overwrite it
+        let chars = generator.charset().encode("@8X ").unwrap();
+        generator.render_into(&chars, 2, 2, false, &mut buffer);
+        assert_eq!(buffer, generator.generate_ascii_image(&chars, 2, 2));
+    }
+
+    #[test]
+    fn test_density_ramp_orders_by_coverage() {
+        let generator = AsciiGenerator::new();
+        let ramp = generator.density_ramp();
+
+        let code = |ch: char| generator.charset().index_of(ch).unwrap();
+
+        assert_eq!(ramp.len(), ALLOWED_CHARS.len());
+        assert_eq!(ramp[0], code(' '));
+        assert_eq!(generator.glyph_coverage(code(' ')), 0.0);
+        for pair in ramp.windows(2) {
+            assert!(generator.glyph_coverage(pair[0]) <= generator.glyph_coverage(pair[1]));
+        }
+        // A dense glyph should rank after a light one
+        let pos = |ch: char| ramp.iter().position(|&r| r == code(ch)).unwrap();
+        assert!(pos('.') < pos('@'));
+    }
+
+    #[test]
+    fn test_custom_charset_limits_cache_and_ramp() {
+        let generator = AsciiGenerator::with_charset(Charset::parse("@. ").unwrap());
+        assert_eq!(generator.char_cache.len(), 3);
+        assert_eq!(generator.density_ramp(), &[2, 1, 0]); // " .@"
+    }
+
+    #[test]
+    fn test_blocks_preset_fills_cells() {
+        let generator = AsciiGenerator::with_charset(CharsetPreset::Blocks.charset());
+        let code = |ch: char| generator.charset().index_of(ch).unwrap();
+
+        // Block glyphs are drawn to cover the whole cell, so the ramp follows the shade levels
+        assert_eq!(generator.glyph_coverage(code('█')), 1.0);
+        assert_eq!(generator.density_ramp(), generator.charset().encode(" ░▒▓█").unwrap().as_slice());
+
+        let art = crate::genetic_algorithm::Individual::new(generator.charset().encode("█░").unwrap());
+        assert_eq!(generator.individual_to_string(&art, 1), "█\n░");
+    }
+
+    #[test]
+    fn test_braille_dots_map_to_sub_cells() {
+        let generator = AsciiGenerator::with_charset(CharsetPreset::Braille.charset());
+        let (width, height) = generator.char_dimensions();
+
+        // Dot 1 (bit 0) lights only the top-left sub-cell, dot 8 (bit 7) only the bottom-right
+        let dot1 = &generator.char_cache[0b0000_0001];
+        assert_eq!(dot1.get_pixel(0, 0)[0], 255);
+        assert_eq!(dot1.get_pixel(width - 1, height - 1)[0], 0);
+        let dot8 = &generator.char_cache[0b1000_0000];
+        assert_eq!(dot8.get_pixel(width - 1, height - 1)[0], 255);
+        assert_eq!(dot8.get_pixel(0, 0)[0], 0);
+
+        assert_eq!(generator.glyph_coverage(0), 0.0);
+        assert_eq!(generator.glyph_coverage(0xFF), 1.0);
+        assert_eq!(generator.density_ramp()[0], 0);
+    }
+
+    #[test]
+    fn test_individual_to_string() {
+        let generator = AsciiGenerator::with_charset(Charset::parse("Hi! ").unwrap());
+        let individual = crate::genetic_algorithm::Individual::new(vec![0, 1, 2, 3]);
 
         let result = generator.individual_to_string(&individual, 2);
         assert_eq!(result, "Hi\n! ");
     }
 
+    #[test]
+    fn test_set_font_size_scales_cells() {
+        let mut generator = AsciiGenerator::new();
+        let (small_width, small_height) = generator.char_dimensions();
+
+        generator.set_font_size(DEFAULT_FONT_SIZE * 2.0);
+        let (large_width, large_height) = generator.char_dimensions();
+
+        assert_eq!(generator.font_size(), DEFAULT_FONT_SIZE * 2.0);
+        assert!(large_width > small_width && large_height > small_height);
+        assert_eq!(generator.char_cache[0].dimensions(), (large_width, large_height));
+    }
+
     #[test]
     fn test_render_char() {
         let generator = AsciiGenerator::new();
@@ -1,6 +1,66 @@
 use crate::ascii_generator::AsciiGenerator;
-use crate::genetic_algorithm::{Individual, ALLOWED_CHARS};
+use crate::fitness::{FitnessMetric, OverlapParams, PixelWeights, Scorer};
+use crate::template::LockMask;
+use crate::genetic_algorithm::Individual;
+use crate::progress::{ProgressEvent, ProgressKind, ProgressSink};
 use image::{ImageBuffer, Luma};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Characters kept per cell by a bare `--block-search`
+pub const DEFAULT_BLOCK_CANDIDATES: usize = 6;
+
+/// Partial grids kept by `--mode beam` unless `--beam-width` says otherwise
+pub const DEFAULT_BEAM_WIDTH: usize = 8;
+
+/// Order in which brute force visits the grid's cells (or blocks in a block search)
+/// Only edge scoring, which judges cells with their neighbours in place, depends on it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TraversalOrder {
+    /// Row by row from the top left
+    #[default]
+    Raster,
+    /// A shuffled order, fixed by the seed
+    Random,
+    /// Nearest the centre of the grid first, where the subject usually is
+    CenterOut,
+    /// Most detailed target first, by the spread of its pixel intensities
+    Detail,
+}
+
+impl std::str::FromStr for TraversalOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "raster" => Ok(Self::Raster),
+            "random" => Ok(Self::Random),
+            "center-out" => Ok(Self::CenterOut),
+            "detail" => Ok(Self::Detail),
+            _ => Err(format!("unknown traversal order '{}' (expected 'raster', 'random', 'center-out' or 'detail')", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for TraversalOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Raster => "raster",
+            Self::Random => "random",
+            Self::CenterOut => "center-out",
+            Self::Detail => "detail",
+        })
+    }
+}
+
+/// Per-character tables the overlap search reads instead of rendering each candidate
+struct GlyphStats {
+    /// Whether each pixel of the glyph, row by row, is lit (above the background threshold)
+    lit: Vec<bool>,
+    /// Number of lit pixels
+    ink: u32,
+}
 
 /// Brute force ASCII art generator that finds the best character for each position
 pub struct BruteForceGenerator<'a> {
@@ -8,8 +68,15 @@ pub struct BruteForceGenerator<'a> {
     height: u32,
     ascii_generator: &'a AsciiGenerator,
     target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>,
-    total_non_background_pixels: f64,
-    background_threshold: u8,
+    white_background: bool,
+    scorer: Scorer<'a>,
+    glyph_stats: Vec<GlyphStats>,
+    locks: Option<&'a LockMask>,
+    block_candidates: Option<usize>,
+    beam_width: Option<usize>,
+    error_diffusion: bool,
+    order: TraversalOrder,
+    seed: u64,
 }
 
 impl<'a> BruteForceGenerator<'a> {
@@ -21,100 +88,162 @@ impl<'a> BruteForceGenerator<'a> {
         target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>,
         white_background: bool,
     ) -> Self {
-        // Calculate background threshold and count non-background pixels
-        let background_threshold = if white_background { 200 } else { 50 };
-        let total_non_background_pixels = Self::count_non_background_pixels(target_image, background_threshold, white_background);
-
-        println!("Brute force - Background threshold: {}, Total non-background pixels: {}",
-                 background_threshold, total_non_background_pixels);
-
+        let scorer = Scorer::new(target_image, white_background);
+        let glyph_stats = (0..ascii_generator.charset().len())
+            .map(|code| {
+                let lit: Vec<bool> = ascii_generator.glyph(code as u8)
+                    .map_or(Vec::new(), |glyph| glyph.as_raw().iter().map(|&value| value > scorer.background_threshold()).collect());
+                let ink = lit.iter().filter(|&&lit| lit).count() as u32;
+                GlyphStats { lit, ink }
+            })
+            .collect();
         Self {
             width,
             height,
             ascii_generator,
             target_image,
-            total_non_background_pixels,
-            background_threshold,
+            white_background,
+            scorer,
+            glyph_stats,
+            locks: None,
+            block_candidates: None,
+            beam_width: None,
+            error_diffusion: false,
+            order: TraversalOrder::default(),
+            seed: 0,
         }
     }
 
-    /// Counts pixels that are not background color in the target image
-    fn count_non_background_pixels(
-        target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
-        background_threshold: u8,
-        white_background: bool,
-    ) -> f64 {
-        let mut count = 0;
+    /// Keeps the locked cells of the mask fixed instead of searching them; a mask of a different size is ignored
+    pub fn set_locks(&mut self, locks: &'a LockMask) {
+        if locks.len() == (self.width * self.height) as usize {
+            self.locks = Some(locks);
+        }
+    }
 
-        for pixel in target_image.pixels() {
-            let intensity = pixel[0];
+    /// Scales each target pixel's contribution to scoring, 0 excluding it; weights of a different size than the target are ignored
+    pub fn set_pixel_weights(&mut self, pixel_weights: &'a PixelWeights) {
+        self.scorer.set_pixel_weights(pixel_weights);
+    }
 
-            let is_non_background = if white_background {
-                intensity < background_threshold
-            } else {
-                intensity > background_threshold
-            };
+    /// Selects the measure each cell's candidates are compared by; the default pixel overlap is the fastest
+    pub fn set_fitness_metric(&mut self, metric: FitnessMetric) {
+        self.scorer.set_metric(metric);
+    }
 
-            if is_non_background {
-                count += 1;
-            }
-        }
+    /// Sets the match tolerance and false-positive penalty of the overlap metric
+    pub fn set_overlap_params(&mut self, overlap: OverlapParams) {
+        self.scorer.set_overlap_params(overlap);
+    }
+
+    /// Adds a Sobel edge-agreement term with the given weight relative to the metric (0 disables it)
+    /// Each cell is then scored on the metric blended with how well the glyph's edges follow the target's
+    pub fn set_edge_weight(&mut self, weight: f64) {
+        self.scorer.set_edge_weight(weight);
+    }
+
+    /// Chooses characters jointly for each 2x2 block of cells instead of cell by cell, trying every combination of each
+    /// cell's `candidates` best-ranked characters (`None` restores the per-cell search)
+    /// The work per block grows with the fourth power of `candidates`, so a handful keeps the search tractable
+    pub fn set_block_search(&mut self, candidates: Option<usize>) {
+        self.block_candidates = candidates.map(|candidates| candidates.clamp(1, self.ascii_generator.charset().len()));
+    }
+
+    /// Replaces the greedy search with a beam search keeping the `width` best partial grids (`None` restores the greedy one)
+    /// Cells are filled in raster order; each partial grid is scored incrementally on the 2x2 windows its cells complete,
+    /// so every pair of neighbours is judged together once, and the finished grids are compared on their full fitness
+    pub fn set_beam_width(&mut self, width: Option<usize>) {
+        self.beam_width = width.map(|width| width.max(1));
+    }
+
+    /// Carries each cell's brightness error over to the cells not yet chosen, Floyd–Steinberg style: the difference between
+    /// the ink its target asks for and the ink of its character is spread over the cell to the right (7/16) and the
+    /// three cells below (3/16, 5/16, 1/16), shifting their targets before their characters are picked
+    /// Cells are then judged on their own against the shifted target, so smooth gradients come out as a density of ink
+    /// instead of one brightness level per threshold band
+    pub fn set_error_diffusion(&mut self, error_diffusion: bool) {
+        self.error_diffusion = error_diffusion;
+    }
+
+    /// Sets the order cells (or blocks) are chosen in; error diffusion always runs in raster order
+    pub fn set_traversal_order(&mut self, order: TraversalOrder) {
+        self.order = order;
+    }
+
+    /// Sets the seed of the random traversal order
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the intensity threshold separating background from foreground pixels
+    pub fn background_threshold(&self) -> u8 {
+        self.scorer.background_threshold()
+    }
 
-        count as f64
+    /// Returns the number of foreground pixels in the target image that fitness is measured against (their summed weights when pixel weights are set)
+    pub fn total_non_background_pixels(&self) -> f64 {
+        self.scorer.total_non_background_pixels()
     }
 
     /// Generates ASCII art using brute force approach with optional callback for progress
-    pub fn generate<F>(&self, verbose: bool, mut progress_callback: Option<F>) -> (Individual, f64)
-    where
-        F: FnMut(u32, u32, f64, f64, u32, u32, Option<String>) -> bool,
-    {
-        use std::time::Instant;
+    /// Cells are chosen in raster order; under edge scoring, which spans cell borders, each candidate is scored on the
+    /// 3x3 patch around it, with the cells chosen so far in place and the rest at their best character in isolation
+    /// With a block search, 2x2 blocks are chosen in raster order instead, each scored as a whole (plus a one-cell margin
+    /// under edge scoring); with error diffusion, cells are scored on their own against the shifted target
+    /// Progress is reported after every position (every block in a block search); the sink can stop the search early
+    pub fn generate<P: ProgressSink + ?Sized>(&self, progress: &mut P) -> (Individual, f64) {
+        use web_time::Instant;
+
+        if let Some(beam_width) = self.beam_width {
+            return self.generate_beam(beam_width, progress);
+        }
 
         let start_time = Instant::now();
-        let total_positions = (self.width * self.height) as u32;
-        let mut best_chars = vec![b' '; total_positions as usize];
-
-        println!("Starting brute force generation for {} positions...", total_positions);
+        let total_positions = self.width * self.height;
+        let mut buffer = ImageBuffer::new(0, 0);
+        // Edge scoring looks across cell borders, so candidates are judged within the full render: every cell starts
+        // from its best character in isolation and locked cells are in place before their turn
+        // (SSIM windows cross borders too, but judging them in context did not score better on the bundled images)
+        let in_context = self.scorer.scores_edges() && !self.error_diffusion;
+        let mut best_chars: Vec<u8> = (0..total_positions)
+            .map(|position| match self.locks.and_then(|locks| locks.locked(position as usize)) {
+                Some(locked) => locked,
+                None if in_context => self.find_best_char_for_position(position / self.width, position % self.width),
+                None => self.blank_char(),
+            })
+            .collect();
+
+        let steps = self.steps();
+
+        // Under error diffusion, a copy of the target whose cells are shifted by the error their neighbours passed on
+        let mut diffused = self.error_diffusion.then(|| (self.target_image.clone(), vec![0.0; total_positions as usize]));
+
+        let mut done = 0;
+        for cells in steps {
+            if let Some(candidates) = self.block_candidates {
+                self.find_best_block(&mut best_chars, &cells, candidates, in_context, &mut buffer);
+            } else if let Some((target, errors)) = diffused.as_mut() {
+                let position = cells[0];
+                let locked = self.locks.and_then(|locks| locks.locked(position as usize));
+                best_chars[position as usize] = self.place_with_error_diffusion(target, errors, position, locked);
+            } else {
+                let position = cells[0];
+                // Find the best character for this position, unless the template locks it
+                let locked = self.locks.and_then(|locks| locks.locked(position as usize));
+                best_chars[position as usize] = locked.unwrap_or_else(|| {
+                    if in_context {
+                        self.find_best_char_in_context(&mut best_chars, position, &mut buffer)
+                    } else {
+                        self.find_best_char_for_position(position / self.width, position % self.width)
+                    }
+                });
+            }
+            done += cells.len() as u32;
 
-        // Process each character position
-        for position in 0..total_positions {
-            let row = position / self.width;
-            let col = position % self.width;
-
-            // Find the best character for this position
-            let best_char = self.find_best_char_for_position(row, col, &best_chars, position as usize);
-            best_chars[position as usize] = best_char;
-
-            // Update progress
-            if let Some(ref mut callback) = progress_callback {
-                let progress = (position + 1) as f64 / total_positions as f64;
-                let elapsed = start_time.elapsed().as_secs_f64();
-                
-                let ascii_art = if verbose {
-                    Some(self.ascii_generator.individual_to_string(&Individual::new(best_chars.clone()), self.width))
-                } else {
-                    None
-                };
-
-                let should_continue = callback(
-                    position + 1,
-                    total_positions,
-                    progress,
-                    elapsed,
-                    self.width,
-                    self.height,
-                    ascii_art
-                );
-
-                if !should_continue {
-                    println!("Brute force generation stopped by user");
-                    break;
-                }
-            } else if (position + 1) % 10 == 0 || position + 1 == total_positions {
-                let progress = (position + 1) as f64 / total_positions as f64;
-                let elapsed = start_time.elapsed().as_secs_f64();
-                println!("Progress: {}/{} positions ({:.1}%) - elapsed: {:.1}s", 
-                         position + 1, total_positions, progress * 100.0, elapsed);
+            if !progress.report(&self.progress_event(done, start_time.elapsed().as_secs_f64(), &best_chars, progress.wants_art()))
+                || progress.cancelled()
+            {
+                break;
             }
         }
 
@@ -126,126 +255,429 @@ impl<'a> BruteForceGenerator<'a> {
         let mut result = final_individual;
         result.fitness = final_fitness;
 
-        println!("Brute force generation complete! Final fitness: {:.2}% (total time: {:.1}s)",
-                 final_fitness * 100.0, total_elapsed);
-
         (result, total_elapsed)
     }
 
-    /// Finds the best character for a specific position by testing all allowed characters
-    fn find_best_char_for_position(&self, row: u32, col: u32, current_chars: &[u8], position: usize) -> u8 {
-        let mut best_char = b' ';
-        let mut best_fitness = 0.0;
+    /// Lists the cells each step of `generate` fixes, one cell or one 2x2 block of cells in a block search, in the
+    /// traversal order; ties keep raster order
+    fn steps(&self) -> Vec<Vec<u32>> {
+        let total_positions = self.width * self.height;
+        let mut steps: Vec<Vec<u32>> = match self.block_candidates {
+            Some(_) => (0..self.height).step_by(2)
+                .flat_map(|row| (0..self.width).step_by(2).map(move |col| (row, col)))
+                .map(|(row, col)| {
+                    (row..(row + 2).min(self.height))
+                        .flat_map(|cell_row| (col..(col + 2).min(self.width)).map(move |cell_col| cell_row * self.width + cell_col))
+                        .collect()
+                })
+                .collect(),
+            None => (0..total_positions).map(|position| vec![position]).collect(),
+        };
+
+        // Error diffusion passes error forward in raster order
+        let order = if self.error_diffusion { TraversalOrder::Raster } else { self.order };
+        let (char_width, char_height) = self.ascii_generator.char_dimensions();
+        // Sort keys: squared pixel distance from the grid's centre, or the negated standard deviation of the target
+        // pixels under the step's cells
+        let key = |cells: &Vec<u32>| match order {
+            TraversalOrder::CenterOut => {
+                let (cols, rows) = cells.iter().fold((0.0, 0.0), |(cols, rows), &position| {
+                    (cols + (position % self.width) as f64 + 0.5, rows + (position / self.width) as f64 + 0.5)
+                });
+                let count = cells.len() as f64;
+                ((cols / count - self.width as f64 / 2.0) * char_width as f64).powi(2)
+                    + ((rows / count - self.height as f64 / 2.0) * char_height as f64).powi(2)
+            }
+            _ => {
+                let (mut sum, mut sum_squares, mut count) = (0.0, 0.0, 0.0);
+                for &position in cells {
+                    let origin = ((position % self.width) * char_width, (position / self.width) * char_height);
+                    for y in origin.1..(origin.1 + char_height).min(self.target_image.height()) {
+                        for x in origin.0..(origin.0 + char_width).min(self.target_image.width()) {
+                            let value = self.target_image.get_pixel(x, y)[0] as f64;
+                            sum += value;
+                            sum_squares += value * value;
+                            count += 1.0;
+                        }
+                    }
+                }
+                if count == 0.0 { 0.0 } else { -(sum_squares / count - (sum / count).powi(2)).max(0.0).sqrt() }
+            }
+        };
+        match order {
+            TraversalOrder::Raster => {}
+            TraversalOrder::Random => steps.shuffle(&mut StdRng::seed_from_u64(self.seed)),
+            TraversalOrder::CenterOut | TraversalOrder::Detail => {
+                let mut keyed: Vec<(f64, Vec<u32>)> = steps.into_iter().map(|cells| (key(&cells), cells)).collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                steps = keyed.into_iter().map(|(_, cells)| cells).collect();
+            }
+        }
+        steps
+    }
 
-        // Test each allowed character at this position
-        for &test_char in ALLOWED_CHARS {
-            let mut test_chars = current_chars.to_vec();
-            test_chars[position] = test_char;
+    /// Fills the grid in raster order keeping the `beam_width` best partial grids, as set up by `set_beam_width`
+    /// Progress is reported after every position with the leading partial grid, as in `generate`
+    fn generate_beam<P: ProgressSink + ?Sized>(&self, beam_width: usize, progress: &mut P) -> (Individual, f64) {
+        use web_time::Instant;
 
-            // Calculate fitness for this character choice
-            let fitness = self.calculate_fitness_for_position(row, col, test_char);
-            
-            if fitness > best_fitness {
-                best_fitness = fitness;
-                best_char = test_char;
+        let start_time = Instant::now();
+        let total_positions = self.width * self.height;
+        let blank = self.blank_char();
+        // Try the blank character first so it wins ties, as in the greedy search
+        let all_chars: Vec<u8> = std::iter::once(blank)
+            .chain((0..self.ascii_generator.charset().len()).map(|code| code as u8).filter(|&code| code != blank))
+            .collect();
+        let mut buffer = ImageBuffer::new(0, 0);
+        let mut patch = Vec::with_capacity(4);
+
+        // Partial grids with the summed scores of their completed windows; undecided cells stay blank
+        let mut beam = vec![(vec![blank; total_positions as usize], 0.0)];
+        let mut expansions: Vec<(usize, u8, f64)> = Vec::new();
+        for position in 0..total_positions {
+            let (row, col) = (position / self.width, position % self.width);
+            // The window with this cell at its bottom right holds only decided cells
+            let window = (col.saturating_sub(1), row.saturating_sub(1), col, row);
+            let locked = self.locks.and_then(|locks| locks.locked(position as usize));
+            let options = locked.as_ref().map_or(all_chars.as_slice(), std::slice::from_ref);
+
+            expansions.clear();
+            for (index, (chars, score)) in beam.iter_mut().enumerate() {
+                for &code in options {
+                    chars[position as usize] = code;
+                    expansions.push((index, code, *score + self.score_patch(chars, window, code == blank, &mut patch, &mut buffer)));
+                }
+                chars[position as usize] = blank;
+            }
+            // A stable sort keeps ties in the order of their parents, then of the characters tried
+            expansions.sort_by(|a, b| b.2.total_cmp(&a.2));
+            beam = expansions.iter()
+                .take(beam_width)
+                .map(|&(index, code, score)| {
+                    let mut chars = beam[index].0.clone();
+                    chars[position as usize] = code;
+                    (chars, score)
+                })
+                .collect();
+
+            if !progress.report(&self.progress_event(position + 1, start_time.elapsed().as_secs_f64(), &beam[0].0, progress.wants_art()))
+                || progress.cancelled()
+            {
+                break;
             }
         }
 
-        best_char
+        // Window sums only approximate the fitness of the whole grid, so the survivors are compared on it
+        let best = beam.into_iter()
+            .map(|(chars, _)| {
+                let mut individual = Individual::new(chars);
+                individual.fitness = self.calculate_fitness(&individual);
+                individual
+            })
+            .reduce(|best, individual| if individual.fitness > best.fitness { individual } else { best })
+            .expect("the beam is never empty");
+        (best, start_time.elapsed().as_secs_f64())
     }
 
-    /// Calculates fitness for a specific character at a specific position
-    fn calculate_fitness_for_position(&self, row: u32, col: u32, test_char: u8) -> f64 {
-        // Create a single-character ASCII art image for this position
-        let single_char_chars = vec![test_char];
-        let single_char_image = self.ascii_generator.generate_ascii_image(&single_char_chars, 1, 1);
-        
-        // Get character dimensions
+    /// Describes the search after `done` positions, with the art of `chars` if the sink wants it
+    fn progress_event(&self, done: u32, elapsed: f64, chars: &[u8], with_art: bool) -> ProgressEvent {
+        ProgressEvent {
+            kind: ProgressKind::Position,
+            step: done,
+            total_steps: self.width * self.height,
+            best_fitness: None,
+            fitness_stats: None,
+            diversity: None,
+            mutation_rate: None,
+            restarts: None,
+            crossover_rate: None,
+            elite_size: None,
+            elapsed,
+            population_size: 1,
+            thread_count: 1,
+            width: self.width,
+            height: self.height,
+            ascii_art: with_art.then(|| self.ascii_generator.individual_to_string(&Individual::new(chars.to_vec()), self.width)),
+            status_interval: None,
+            cell_quality: None,
+        }
+    }
+
+    /// Polishes an existing solution with up to `sweeps` passes over every unlocked position, each setting the character
+    /// that scores best there with its neighbours in place; stops early once a pass changes nothing
+    /// Unlike `generate`, candidates are judged on the cell and the cells around it, so metrics that look across cell
+    /// borders (SSIM windows, edges) can still improve on a per-cell optimum
+    pub fn refine(&self, individual: &Individual, sweeps: usize) -> Individual {
+        let mut chars = individual.chars.clone();
+        let mut buffer = ImageBuffer::new(0, 0);
+        for _ in 0..sweeps {
+            let mut changed = false;
+            for position in 0..(self.width * self.height).min(chars.len() as u32) {
+                if self.locks.is_some_and(|locks| locks.locked(position as usize).is_some()) {
+                    continue;
+                }
+                let best_char = self.find_best_char_in_context(&mut chars, position, &mut buffer);
+                changed |= best_char != chars[position as usize];
+                chars[position as usize] = best_char;
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut refined = Individual::new(chars);
+        refined.fitness = self.calculate_fitness(&refined);
+        refined
+    }
+
+    /// Shifts the target of a cell by its diffused error, picks its character against the shifted target (unless it is
+    /// locked) and passes the remaining error on to the unprocessed neighbours
+    fn place_with_error_diffusion(
+        &self,
+        target: &mut ImageBuffer<Luma<u8>, Vec<u8>>,
+        errors: &mut [f64],
+        position: u32,
+        locked: Option<u8>,
+    ) -> u8 {
+        let (row, col) = (position / self.width, position % self.width);
         let (char_width, char_height) = self.ascii_generator.char_dimensions();
-        
-        // Calculate the pixel region in the target image that corresponds to this character position
-        let start_x = col * char_width;
-        let start_y = row * char_height;
-        let end_x = (start_x + char_width).min(self.target_image.width());
-        let end_y = (start_y + char_height).min(self.target_image.height());
-        
-        let mut score = 0.0;
-        let mut total_relevant_pixels = 0.0;
-        
-        // Compare pixels in the character's region
-        for y in start_y..end_y {
-            for x in start_x..end_x {
-                let target_pixel = self.target_image.get_pixel(x, y)[0];
-                let target_is_lit = target_pixel > self.background_threshold;
-                
-                // Get corresponding pixel from the single character image
-                let char_x = x - start_x;
-                let char_y = y - start_y;
-                
-                if char_x < single_char_image.width() && char_y < single_char_image.height() {
-                    let ascii_pixel = single_char_image.get_pixel(char_x, char_y)[0];
-                    let ascii_is_lit = ascii_pixel > self.background_threshold;
-                    
-                    // Only score meaningful pixels (target non-background)
-                    if target_is_lit {
-                        total_relevant_pixels += 1.0;
-                        let diff = (ascii_pixel as i32 - target_pixel as i32).abs();
-                        
-                        if diff < 30 { // Same tolerance as genetic algorithm
-                            score += 1.0;
-                        }
-                    } else if ascii_is_lit {
-                        // Small penalty for false positives
-                        score -= 0.005;
-                    }
+        let origin = (col * char_width, row * char_height);
+        let width = char_width.min(target.width().saturating_sub(origin.0));
+        let height = char_height.min(target.height().saturating_sub(origin.1));
+        // Ink is brightness on black backgrounds and darkness on white ones
+        let to_ink = |value: f64| if self.white_background { 255.0 - value } else { value };
+
+        let shift = errors[position as usize];
+        let mut target_ink = 0.0;
+        for y in origin.1..origin.1 + height {
+            for x in origin.0..origin.0 + width {
+                let pixel = target.get_pixel_mut(x, y);
+                let ink = (to_ink(pixel[0] as f64) + shift).clamp(0.0, 255.0);
+                pixel[0] = to_ink(ink).round() as u8;
+                target_ink += ink;
+            }
+        }
+
+        let blank = self.blank_char();
+        let mut best = (blank, 0.0);
+        let chosen = locked.unwrap_or_else(|| {
+            for test_char in (0..self.ascii_generator.charset().len()).map(|code| code as u8) {
+                let Some(glyph) = self.ascii_generator.glyph(test_char) else { continue };
+                let fitness = self.scorer.score_cell_against(glyph, target, origin, test_char == blank);
+                if fitness > best.1 {
+                    best = (test_char, fitness);
                 }
             }
+            best.0
+        });
+
+        // Tones are compared on the scale the character set can reach, where the densest glyph counts as full ink
+        let max_coverage = self.ascii_generator.glyph_coverage(*self.ascii_generator.density_ramp().last().unwrap_or(&blank));
+        let glyph_ink = if max_coverage > 0.0 { self.ascii_generator.glyph_coverage(chosen) / max_coverage * 255.0 } else { 0.0 };
+        let residual = target_ink / (width * height).max(1) as f64 - glyph_ink;
+        for (dx, dy, weight) in [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)] {
+            let (neighbour_col, neighbour_row) = (col as i64 + dx, row as i64 + dy);
+            if (0..self.width as i64).contains(&neighbour_col) && neighbour_row < self.height as i64 {
+                errors[(neighbour_row * self.width as i64 + neighbour_col) as usize] += residual * weight / 16.0;
+            }
         }
-        
-        // Return fitness for this character position
-        if total_relevant_pixels > 0.0 {
-            let fitness = score / total_relevant_pixels;
-            if fitness < 0.0 { 0.0 } else { fitness }
-        } else {
-            // If no relevant pixels, prefer space character
-            if test_char == b' ' { 1.0 } else { 0.0 }
+        chosen
+    }
+
+    /// Finds the best character for a position given the rest of the grid, scoring the cell together with its neighbours
+    /// Ties keep the current character, so repeated sweeps settle instead of oscillating
+    fn find_best_char_in_context(&self, chars: &mut [u8], position: u32, buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>) -> u8 {
+        let (row, col) = (position / self.width, position % self.width);
+        let bounds = self.with_margin((col, row, col, row));
+        let original = chars[position as usize];
+
+        let mut patch = Vec::with_capacity(9);
+        let mut best = (original, self.score_patch(chars, bounds, original == self.blank_char(), &mut patch, buffer));
+        for test_char in (0..self.ascii_generator.charset().len()).map(|code| code as u8) {
+            chars[position as usize] = test_char;
+            let fitness = self.score_patch(chars, bounds, test_char == self.blank_char(), &mut patch, buffer);
+            if fitness > best.1 {
+                best = (test_char, fitness);
+            }
         }
+        chars[position as usize] = original;
+        best.0
     }
 
-    /// Calculates overall fitness using the same method as genetic algorithm
-    fn calculate_fitness(&self, individual: &Individual) -> f64 {
-        let ascii_image = self.ascii_generator.generate_ascii_image(&individual.chars, self.width, self.height);
+    /// Sets the cells of one block to the combination of characters that scores best on the block, trying each cell's
+    /// `candidates` best characters (locked cells keep theirs); in context, candidates are ranked and the block is scored
+    /// with a one-cell margin of neighbours, otherwise candidates are ranked in isolation
+    /// Combinations are tried from the best-ranked characters down and ties keep the earlier one
+    fn find_best_block(&self, chars: &mut [u8], cells: &[u32], candidates: usize, in_context: bool, buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>) {
+        let blank = self.blank_char();
+        let mut patch = Vec::with_capacity(16);
+        let options: Vec<Vec<u8>> = cells.iter()
+            .map(|&position| match self.locks.and_then(|locks| locks.locked(position as usize)) {
+                Some(locked) => vec![locked],
+                None => {
+                    // Rank the blank character first so it wins ties, as in the per-cell search
+                    let (row, col) = (position / self.width, position % self.width);
+                    let original = chars[position as usize];
+                    let mut ranked: Vec<(u8, f64)> = std::iter::once(blank)
+                        .chain((0..self.ascii_generator.charset().len()).map(|code| code as u8).filter(|&code| code != blank))
+                        .map(|code| {
+                            let fitness = if in_context {
+                                chars[position as usize] = code;
+                                self.score_patch(chars, self.with_margin((col, row, col, row)), code == blank, &mut patch, buffer)
+                            } else {
+                                self.calculate_fitness_for_position(row, col, code)
+                            };
+                            (code, fitness)
+                        })
+                        .collect();
+                    chars[position as usize] = original;
+                    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    ranked.iter().take(candidates).map(|&(code, _)| code).collect()
+                }
+            })
+            .collect();
+
+        let (first, last) = (cells[0], cells[cells.len() - 1]);
+        let block = (first % self.width, first / self.width, last % self.width, last / self.width);
+        let bounds = if in_context { self.with_margin(block) } else { block };
+
+        let mut choice = vec![0; cells.len()];
+        let mut best: Option<(Vec<usize>, f64)> = None;
+        loop {
+            for ((&position, cell_options), &index) in cells.iter().zip(&options).zip(&choice) {
+                chars[position as usize] = cell_options[index];
+            }
+            let is_blank = cells.iter().all(|&position| chars[position as usize] == blank);
+            let fitness = self.score_patch(chars, bounds, is_blank, &mut patch, buffer);
+            if best.as_ref().is_none_or(|(_, best_fitness)| fitness > *best_fitness) {
+                best = Some((choice.clone(), fitness));
+            }
 
-        if self.total_non_background_pixels == 0.0 {
-            return 0.0;
+            // Advance to the next combination, odometer style
+            let Some(cell) = (0..cells.len()).rev().find(|&cell| choice[cell] + 1 < options[cell].len()) else {
+                break;
+            };
+            choice[cell] += 1;
+            choice[cell + 1..].fill(0);
         }
 
-        let min_width = ascii_image.width().min(self.target_image.width());
-        let min_height = ascii_image.height().min(self.target_image.height());
+        let (choice, _) = best.expect("every block has at least one combination");
+        for ((&position, cell_options), index) in cells.iter().zip(&options).zip(choice) {
+            chars[position as usize] = cell_options[index];
+        }
+    }
 
-        let mut score = 0.0;
+    /// Widens the cell bounds `(left, top, right, bottom)` by one cell on each side, within the grid
+    fn with_margin(&self, (left, top, right, bottom): (u32, u32, u32, u32)) -> (u32, u32, u32, u32) {
+        (left.saturating_sub(1), top.saturating_sub(1), (right + 1).min(self.width - 1), (bottom + 1).min(self.height - 1))
+    }
 
-        for y in 0..min_height {
-            for x in 0..min_width {
-                let ascii_pixel = ascii_image.get_pixel(x, y)[0];
-                let target_pixel = self.target_image.get_pixel(x, y)[0];
+    /// Renders the cells within `(left, top, right, bottom)` of the grid into `buffer` and scores them against the target
+    fn score_patch(
+        &self,
+        chars: &[u8],
+        (left, top, right, bottom): (u32, u32, u32, u32),
+        is_blank: bool,
+        patch: &mut Vec<u8>,
+        buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>,
+    ) -> f64 {
+        let (char_width, char_height) = self.ascii_generator.char_dimensions();
+        patch.clear();
+        for patch_row in top..=bottom {
+            let start = (patch_row * self.width + left) as usize;
+            patch.extend_from_slice(&chars[start..start + (right - left + 1) as usize]);
+        }
+        self.ascii_generator.render_into(patch, right - left + 1, bottom - top + 1, false, buffer);
+        self.scorer.score_cell(buffer, (left * char_width, top * char_height), is_blank)
+    }
 
-                let ascii_is_lit = ascii_pixel > self.background_threshold;
-                let target_is_lit = target_pixel > self.background_threshold;
+    /// Finds the best character for a specific position by testing all allowed characters
+    fn find_best_char_for_position(&self, row: u32, col: u32) -> u8 {
+        if let Some(best_char) = self.find_best_char_from_tables(row, col) {
+            return best_char;
+        }
 
-                if target_is_lit {
-                    let diff = (ascii_pixel as i32 - target_pixel as i32).abs();
-                    if diff < 30 {
-                        score += 1.0;
-                    }
-                } else if ascii_is_lit {
-                    score -= 0.005;
-                }
+        let mut best_char = self.blank_char();
+        let mut best_fitness = 0.0;
+
+        // Test each allowed character at this position (256 for braille, so avoid per-candidate copies)
+        for test_char in (0..self.ascii_generator.charset().len()).map(|code| code as u8) {
+            // Calculate fitness for this character choice
+            let fitness = self.calculate_fitness_for_position(row, col, test_char);
+            
+            if fitness > best_fitness {
+                best_fitness = fitness;
+                best_char = test_char;
+            }
+        }
+
+        best_char
+    }
+
+    /// Finds the best character for a position under the plain overlap metric from the glyph tables, visiting only the
+    /// cell's lit target pixels: false positives are the glyph's ink minus its lit pixels over them
+    /// Returns None when scores need more than pixel counts (another metric, an edge term or pixel weights) or the cell
+    /// is clipped by the target's border; the result is the one `calculate_fitness_for_position` would pick
+    fn find_best_char_from_tables(&self, row: u32, col: u32) -> Option<u8> {
+        let (char_width, char_height) = self.ascii_generator.char_dimensions();
+        let origin = (col * char_width, row * char_height);
+        if self.scorer.metric() != FitnessMetric::Overlap
+            || self.scorer.scores_edges()
+            || self.scorer.weights_pixels()
+            || origin.0 + char_width > self.target_image.width()
+            || origin.1 + char_height > self.target_image.height()
+        {
+            return None;
+        }
+
+        // The cell's lit target pixels as (index within the glyph, value)
+        let threshold = self.scorer.background_threshold();
+        let lit_target: Vec<(usize, u8)> = (0..char_height)
+            .flat_map(|y| (0..char_width).map(move |x| (x, y)))
+            .map(|(x, y)| ((y * char_width + x) as usize, self.target_image.get_pixel(origin.0 + x, origin.1 + y)[0]))
+            .filter(|&(_, value)| value > threshold)
+            .collect();
+        let blank = self.blank_char();
+        if lit_target.is_empty() {
+            return Some(blank);
+        }
+
+        let OverlapParams { tolerance, false_positive_penalty } = self.scorer.overlap_params();
+        let mut best = (blank, 0.0);
+        for (code, stats) in self.glyph_stats.iter().enumerate() {
+            let glyph = self.ascii_generator.glyph(code as u8)?.as_raw();
+            let (mut matches, mut covered) = (0u32, 0u32);
+            for &(index, value) in &lit_target {
+                matches += (glyph[index].abs_diff(value) < tolerance) as u32;
+                covered += stats.lit[index] as u32;
+            }
+            let false_positives = stats.ink - covered;
+            let fitness = ((matches as f64 - false_positives as f64 * false_positive_penalty) / lit_target.len() as f64).max(0.0);
+            if fitness > best.1 {
+                best = (code as u8, fitness);
             }
         }
+        Some(best.0)
+    }
+
+    /// Calculates fitness for a specific character at a specific position from its cached glyph
+    fn calculate_fitness_for_position(&self, row: u32, col: u32, test_char: u8) -> f64 {
+        // Compare the glyph with the pixel region in the target image that corresponds to this character position
+        let (char_width, char_height) = self.ascii_generator.char_dimensions();
+        self.ascii_generator.glyph(test_char).map_or(0.0, |glyph| {
+            self.scorer.score_cell(glyph, (col * char_width, row * char_height), test_char == self.blank_char())
+        })
+    }
 
-        let fitness = score / self.total_non_background_pixels;
-        if fitness < 0.0 { 0.0 } else { fitness }
+    /// Returns the lightest character of the active set, used for empty cells
+    fn blank_char(&self) -> u8 {
+        self.ascii_generator.density_ramp()[0]
+    }
+
+    /// Calculates overall fitness using the same method as genetic algorithm
+    fn calculate_fitness(&self, individual: &Individual) -> f64 {
+        let ascii_image = self.ascii_generator.generate_ascii_image(&individual.chars, self.width, self.height);
+        self.scorer.score(&ascii_image, &mut ImageBuffer::new(0, 0))
     }
 }
 
@@ -253,6 +685,8 @@ impl<'a> BruteForceGenerator<'a> {
 mod tests {
     use super::*;
     use crate::ascii_generator::AsciiGenerator;
+    use crate::progress::NoProgress;
+    use crate::charset::{Charset, CharsetPreset, ALLOWED_CHARS};
     use image::ImageBuffer;
 
     fn create_test_ascii_generator() -> AsciiGenerator {
@@ -263,6 +697,191 @@ mod tests {
         ImageBuffer::new(20, 20)
     }
 
+    #[test]
+    fn test_locked_cells_are_kept() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = ImageBuffer::from_pixel(2 * char_width, char_height, Luma([255u8]));
+        let locks = LockMask::new(vec![None, ascii_gen.charset().blank_code()]);
+
+        let mut bf_gen = BruteForceGenerator::new(2, 1, &ascii_gen, &target, false);
+        bf_gen.set_locks(&locks);
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(Some(result.chars[1]), ascii_gen.charset().blank_code());
+        assert_ne!(Some(result.chars[0]), ascii_gen.charset().blank_code());
+    }
+
+    #[test]
+    fn test_ignored_pixels_are_not_scored() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = ImageBuffer::from_pixel(2 * char_width, char_height, Luma([255u8]));
+        // The second cell is a watermark the art should not try to reproduce
+        let weights = PixelWeights::from_ignore_mask(&ImageBuffer::from_fn(2 * char_width, char_height, |x, _| {
+            Luma([if x >= char_width { 255u8 } else { 0 }])
+        }));
+
+        let mut bf_gen = BruteForceGenerator::new(2, 1, &ascii_gen, &target, false);
+        bf_gen.set_pixel_weights(&weights);
+        assert_eq!(bf_gen.total_non_background_pixels(), (char_width * char_height * PixelWeights::FULL as u32) as f64);
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(Some(result.chars[1]), ascii_gen.charset().blank_code());
+        assert_ne!(Some(result.chars[0]), ascii_gen.charset().blank_code());
+    }
+
+    #[test]
+    fn test_ssim_recovers_rendered_characters() {
+        let ascii_gen = create_test_ascii_generator();
+        let chars = ascii_gen.charset().encode("#  @. +").unwrap();
+        let target = ascii_gen.generate_ascii_image(&chars, 7, 1);
+
+        let mut bf_gen = BruteForceGenerator::new(7, 1, &ascii_gen, &target, false);
+        bf_gen.set_fitness_metric(FitnessMetric::Ssim);
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(result.chars, chars);
+        assert!(result.fitness > 0.9, "fitness {}", result.fitness);
+    }
+
+    #[test]
+    fn test_edge_scoring_judges_cells_in_context() {
+        let ascii_gen = create_test_ascii_generator();
+        let chars = ascii_gen.charset().encode("#@.+  #+@ ").unwrap();
+        let target = ascii_gen.generate_ascii_image(&chars, 5, 2);
+
+        // Edges at cell borders only match once the neighbours are in place
+        let mut bf_gen = BruteForceGenerator::new(5, 2, &ascii_gen, &target, false);
+        bf_gen.set_fitness_metric(FitnessMetric::Edge);
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(result.chars, chars);
+        assert!(result.fitness > 0.99, "fitness {}", result.fitness);
+    }
+
+    #[test]
+    fn test_block_search() {
+        let ascii_gen = create_test_ascii_generator();
+        let chars = ascii_gen.charset().encode("#@.+  #+@ :").unwrap();
+        let target = ascii_gen.generate_ascii_image(&chars, 11, 1);
+
+        // SSIM windows straddle cell borders, which a joint choice of neighbouring cells can account for
+        let mut bf_gen = BruteForceGenerator::new(11, 1, &ascii_gen, &target, false);
+        bf_gen.set_fitness_metric(FitnessMetric::Ssim);
+        bf_gen.set_block_search(Some(DEFAULT_BLOCK_CANDIDATES));
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(result.chars, chars);
+
+        // With one candidate per cell the blocks are the per-cell optimum, locked cells included
+        let target = create_test_target_image();
+        let locks = LockMask::new(vec![None, None, ascii_gen.charset().encode("#").unwrap().first().copied(), None]);
+        let mut bf_gen = BruteForceGenerator::new(2, 2, &ascii_gen, &target, false);
+        bf_gen.set_locks(&locks);
+        let (per_cell, _) = bf_gen.generate(&mut NoProgress);
+        bf_gen.set_block_search(Some(1));
+        let (blocks, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(blocks.chars, per_cell.chars);
+        assert_eq!(Some(blocks.chars[2]), locks.locked(2));
+    }
+
+    #[test]
+    fn test_beam_search() {
+        let ascii_gen = create_test_ascii_generator();
+        let chars = ascii_gen.charset().encode("#@.+  #+@ ").unwrap();
+        let target = ascii_gen.generate_ascii_image(&chars, 5, 2);
+
+        // Edges at cell borders are judged with the neighbours decided so far; stray ink in an empty cell costs the
+        // edge metric little, so the art is close to the target rather than identical
+        let mut bf_gen = BruteForceGenerator::new(5, 2, &ascii_gen, &target, false);
+        bf_gen.set_fitness_metric(FitnessMetric::Edge);
+        bf_gen.set_beam_width(Some(DEFAULT_BEAM_WIDTH));
+        let mut calls = 0;
+        let (result, _) = bf_gen.generate(&mut |event: &ProgressEvent| {
+            calls += 1;
+            assert_eq!((event.kind, event.step, event.total_steps), (ProgressKind::Position, calls, 10));
+            assert!(event.ascii_art.is_some());
+            true
+        });
+        assert_eq!(calls, 10);
+        assert!(result.fitness > 0.99, "fitness {}", result.fitness);
+
+        // Locked cells are kept
+        let locks = LockMask::new(vec![None, None, None, ascii_gen.charset().blank_code(), None, None, None, None, None, None]);
+        bf_gen.set_locks(&locks);
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(Some(result.chars[3]), ascii_gen.charset().blank_code());
+    }
+
+    #[test]
+    fn test_error_diffusion_keeps_tone() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        // A dim grey below the background threshold, which the plain search leaves blank
+        let target = ImageBuffer::from_pixel(8 * char_width, 4 * char_height, Luma([40u8]));
+
+        let mut bf_gen = BruteForceGenerator::new(8, 4, &ascii_gen, &target, false);
+        let (plain, _) = bf_gen.generate(&mut NoProgress);
+        assert!(plain.chars.iter().all(|&code| Some(code) == ascii_gen.charset().blank_code()));
+
+        bf_gen.set_error_diffusion(true);
+        let (diffused, _) = bf_gen.generate(&mut NoProgress);
+        let max_coverage = ascii_gen.glyph_coverage(*ascii_gen.density_ramp().last().unwrap());
+        let tone = diffused.chars.iter().map(|&code| ascii_gen.glyph_coverage(code) / max_coverage).sum::<f64>() / 32.0;
+        assert!((tone - 40.0 / 255.0).abs() < 0.05, "tone {}", tone);
+    }
+
+    #[test]
+    fn test_traversal_orders() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        // Only the cell at row 1, column 2 has any detail
+        let target = ImageBuffer::from_fn(3 * char_width, 3 * char_height, |x, y| {
+            Luma([if x / char_width == 2 && y / char_height == 1 && (x + y) % 2 == 0 { 255u8 } else { 0 }])
+        });
+        let mut bf_gen = BruteForceGenerator::new(3, 3, &ascii_gen, &target, false);
+        let order = |bf_gen: &BruteForceGenerator| bf_gen.steps().into_iter().flatten().collect::<Vec<u32>>();
+        assert_eq!(order(&bf_gen), (0..9).collect::<Vec<u32>>());
+
+        bf_gen.set_traversal_order(TraversalOrder::CenterOut);
+        // Cells are taller than wide, so the side neighbours are nearer in pixels than those above and below
+        assert_eq!(order(&bf_gen), vec![4, 3, 5, 1, 7, 0, 2, 6, 8]);
+
+        bf_gen.set_traversal_order(TraversalOrder::Detail);
+        assert_eq!(order(&bf_gen), vec![5, 0, 1, 2, 3, 4, 6, 7, 8]);
+
+        bf_gen.set_traversal_order(TraversalOrder::Random);
+        bf_gen.set_seed(3);
+        let shuffled = order(&bf_gen);
+        assert_eq!(order(&bf_gen), shuffled);
+        let mut sorted = shuffled.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..9).collect::<Vec<u32>>());
+
+        // Blocks are ordered as a whole, and error diffusion keeps raster order
+        bf_gen.set_block_search(Some(2));
+        assert_eq!(bf_gen.steps().iter().map(Vec::len).sum::<usize>(), 9);
+        bf_gen.set_block_search(None);
+        bf_gen.set_error_diffusion(true);
+        assert_eq!(order(&bf_gen), (0..9).collect::<Vec<u32>>());
+        assert_eq!("center-out".parse::<TraversalOrder>(), Ok(TraversalOrder::CenterOut));
+    }
+
+    #[test]
+    fn test_refine_polishes_existing_art() {
+        let ascii_gen = create_test_ascii_generator();
+        let chars = ascii_gen.charset().encode("#@.+  #+@ ").unwrap();
+        let target = ascii_gen.generate_ascii_image(&chars, 5, 2);
+
+        let mut bf_gen = BruteForceGenerator::new(5, 2, &ascii_gen, &target, false);
+        bf_gen.set_fitness_metric(FitnessMetric::Ssim);
+        let start = Individual::new(vec![ascii_gen.charset().blank_code().unwrap(); 10]);
+        let refined = bf_gen.refine(&start, 3);
+        assert_eq!(refined.chars, chars);
+        assert!(refined.fitness > 0.9, "fitness {}", refined.fitness);
+
+        // A brute-force optimum under the overlap metric is already a fixed point
+        let bf_gen = BruteForceGenerator::new(5, 2, &ascii_gen, &target, false);
+        let (best, _) = bf_gen.generate(&mut NoProgress);
+        assert_eq!(bf_gen.refine(&best, 2).chars, best.chars);
+    }
+
     #[test]
     fn test_brute_force_generator_creation() {
         let ascii_gen = create_test_ascii_generator();
@@ -272,7 +891,7 @@ mod tests {
 
         assert_eq!(bf_gen.width, 2);
         assert_eq!(bf_gen.height, 2);
-        assert_eq!(bf_gen.background_threshold, 50);
+        assert_eq!(bf_gen.background_threshold(), 50);
     }
 
     #[test]
@@ -281,11 +900,67 @@ mod tests {
         let target_img = create_test_target_image();
         let bf_gen = BruteForceGenerator::new(2, 2, &ascii_gen, &target_img, false);
 
-        let current_chars = vec![b' '; 4];
-        let best_char = bf_gen.find_best_char_for_position(0, 0, &current_chars, 0);
+        let best_char = bf_gen.find_best_char_for_position(0, 0);
 
         // Should return a valid character from the allowed set
-        assert!(ALLOWED_CHARS.contains(&best_char));
+        assert!((best_char as usize) < ALLOWED_CHARS.len());
+    }
+
+    #[test]
+    fn test_generate_uses_custom_charset() {
+        let ascii_gen = AsciiGenerator::with_charset(Charset::parse("#.").unwrap());
+        let target_img = create_test_target_image();
+        let bf_gen = BruteForceGenerator::new(2, 2, &ascii_gen, &target_img, false);
+
+        let (result, _) = bf_gen.generate(&mut NoProgress);
+        // Empty target: every cell gets the lightest character of the custom set
+        assert_eq!(result.chars, vec![1; 4]); // '.' is code 1 in "#."
+    }
+
+    #[test]
+    fn test_braille_picks_matching_dot_pattern() {
+        let ascii_gen = AsciiGenerator::with_charset(CharsetPreset::Braille.charset());
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        // Light only the left column of dots (dots 1, 2, 3 and 7)
+        let target_img = ImageBuffer::from_fn(char_width, char_height, |x, _| {
+            Luma([if x * 2 < char_width { 255u8 } else { 0 }])
+        });
+        let bf_gen = BruteForceGenerator::new(1, 1, &ascii_gen, &target_img, false);
+
+        assert_eq!(bf_gen.find_best_char_for_position(0, 0), 0b0100_0111);
+    }
+
+    #[test]
+    fn test_glyph_tables_match_the_scorer() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        // Art with a band of mid-grey over it, so tolerance, false positives and empty cells all come into play
+        let chars = ascii_gen.charset().encode("#@.+ o|Z-%").unwrap();
+        let mut target = ascii_gen.generate_ascii_image(&chars, 5, 2);
+        for (x, y, pixel) in target.enumerate_pixels_mut() {
+            if (x + 2 * y) % (2 * char_width) < char_width / 2 || y % char_height == 3 {
+                pixel[0] = pixel[0].saturating_add(120);
+            }
+        }
+
+        for penalty in [0.005, 0.5] {
+            let mut bf_gen = BruteForceGenerator::new(5, 2, &ascii_gen, &target, false);
+            bf_gen.set_overlap_params(OverlapParams { false_positive_penalty: penalty, ..OverlapParams::default() });
+            for (row, col) in (0..2).flat_map(|row| (0..5).map(move |col| (row, col))) {
+                let mut expected = (bf_gen.blank_char(), 0.0);
+                for code in 0..ascii_gen.charset().len() as u8 {
+                    let fitness = bf_gen.calculate_fitness_for_position(row, col, code);
+                    if fitness > expected.1 {
+                        expected = (code, fitness);
+                    }
+                }
+                assert_eq!(bf_gen.find_best_char_from_tables(row, col), Some(expected.0), "cell ({}, {})", row, col);
+            }
+
+            // Scores beyond pixel counts go through the scorer
+            bf_gen.set_fitness_metric(FitnessMetric::Ssim);
+            assert_eq!(bf_gen.find_best_char_from_tables(0, 0), None);
+        }
     }
 
     #[test]
@@ -294,9 +969,9 @@ mod tests {
         let target_img = create_test_target_image();
         let bf_gen = BruteForceGenerator::new(2, 2, &ascii_gen, &target_img, false);
 
-        let individual = Individual::new(vec![b' ', b' ', b' ', b' ']);
+        let individual = Individual::new(vec![0; 4]); // All spaces
         let fitness = bf_gen.calculate_fitness(&individual);
 
-        assert!(fitness >= 0.0 && fitness <= 1.0);
+        assert!((0.0..=1.0).contains(&fitness));
     }
 }
\ No newline at end of file
@@ -0,0 +1,263 @@
+use rand::Rng;
+use std::str::FromStr;
+
+/// Limited character set for ASCII art generation
+pub const ALLOWED_CHARS: &[u8] = b" <>,./?\\|[]{}-_=+AbCDdoOTuUVXZ`~;:'\"!@#$%^&*()8";
+
+/// Maximum number of characters in a set, so every genome code fits in a byte
+pub const MAX_CHARSET_LEN: usize = 256;
+
+/// First Unicode braille pattern (no dots raised); the low byte of each pattern is its dot bitmask
+pub const BRAILLE_BLANK: char = '\u{2800}';
+
+/// The set of characters the generators are allowed to place
+/// Genomes store indices into this set rather than the characters themselves,
+/// which lets a set contain Unicode characters while keeping one byte per cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Charset {
+    chars: Vec<char>,
+    background: Option<u8>,
+    non_space: Vec<u8>,
+}
+
+impl Default for Charset {
+    fn default() -> Self {
+        Self::from_unique(ALLOWED_CHARS.iter().map(|&c| c as char).collect())
+    }
+}
+
+impl Charset {
+    /// Parses a user-supplied character set, ignoring duplicates
+    /// Control characters are rejected and between 2 and 256 distinct characters are required
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut chars = Vec::new();
+
+        for ch in spec.chars() {
+            if ch.is_control() {
+                return Err(format!("character {:?} is not printable", ch));
+            }
+            if !chars.contains(&ch) {
+                chars.push(ch);
+            }
+        }
+
+        if chars.len() < 2 {
+            return Err("character set must contain at least two distinct characters".to_string());
+        }
+        if chars.len() > MAX_CHARSET_LEN {
+            return Err(format!("character set can contain at most {} distinct characters", MAX_CHARSET_LEN));
+        }
+
+        Ok(Self::from_unique(chars))
+    }
+
+    fn from_unique(chars: Vec<char>) -> Self {
+        let background = chars.iter().position(|&c| c == ' ' || c == BRAILLE_BLANK).map(|i| i as u8);
+        let non_space = (0..chars.len())
+            .map(|i| i as u8)
+            .filter(|&i| Some(i) != background)
+            .collect();
+        Self { chars, background, non_space }
+    }
+
+    /// Returns the characters in the set, indexed by genome code
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Returns the number of characters in the set
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Returns true if the set has no characters
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Returns the character for a genome code
+    pub fn char_at(&self, code: u8) -> char {
+        self.chars[code as usize]
+    }
+
+    /// Returns the genome code of a character, if it is part of the set
+    pub fn index_of(&self, ch: char) -> Option<u8> {
+        self.chars.iter().position(|&c| c == ch).map(|i| i as u8)
+    }
+
+    /// Converts text into genome codes, failing if any character is outside the set
+    pub fn encode(&self, text: &str) -> Option<Vec<u8>> {
+        text.chars().map(|ch| self.index_of(ch)).collect()
+    }
+
+    /// Counts how often each character occurs in genome codes, indexed by code
+    pub fn usage(&self, codes: &[u8]) -> Vec<usize> {
+        let mut counts = vec![0; self.chars.len()];
+        for &code in codes {
+            counts[code as usize] += 1;
+        }
+        counts
+    }
+
+    /// Returns true if a blank character (space or empty braille cell) is available for background cells
+    pub fn has_space(&self) -> bool {
+        self.background.is_some()
+    }
+
+    /// Returns the code of the blank character (space or empty braille cell), if the set has one
+    pub fn blank_code(&self) -> Option<u8> {
+        self.background
+    }
+
+    /// Picks any code from the set uniformly
+    pub fn random_code<R: Rng + ?Sized>(&self, rng: &mut R) -> u8 {
+        rng.gen_range(0..self.chars.len()) as u8
+    }
+
+    /// Picks the background (blank) code with the given probability, otherwise a random non-blank code
+    /// Sets without a space never produce a background pick
+    pub fn random_with_background_prob<R: Rng + ?Sized>(&self, background_prob: f64, rng: &mut R) -> u8 {
+        match self.background {
+            Some(space) if self.non_space.is_empty() || rng.gen::<f64>() < background_prob => space,
+            // Choose from non-space characters
+            _ => self.non_space[rng.gen_range(0..self.non_space.len())],
+        }
+    }
+}
+
+impl FromStr for Charset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Named character sets selectable with `--charset-preset`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CharsetPreset {
+    /// The built-in `ALLOWED_CHARS` set
+    #[default]
+    Default,
+    /// A short density ramp of common ASCII characters
+    Minimal,
+    /// Unicode shade and full block characters
+    Blocks,
+    /// Line-drawing ASCII characters
+    Lines,
+    /// Every printable 7-bit ASCII character
+    Full,
+    /// All 256 Unicode braille patterns, each cell a 2x4 dot matrix
+    Braille,
+}
+
+impl CharsetPreset {
+    /// All presets, in the order they are listed in help output
+    pub const ALL: [CharsetPreset; 6] = [
+        CharsetPreset::Default,
+        CharsetPreset::Minimal,
+        CharsetPreset::Blocks,
+        CharsetPreset::Lines,
+        CharsetPreset::Full,
+        CharsetPreset::Braille,
+    ];
+
+    /// Returns the preset's name as accepted on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            CharsetPreset::Default => "default",
+            CharsetPreset::Minimal => "minimal",
+            CharsetPreset::Blocks => "blocks",
+            CharsetPreset::Lines => "lines",
+            CharsetPreset::Full => "full",
+            CharsetPreset::Braille => "braille",
+        }
+    }
+
+    /// Builds the character set for this preset
+    /// Every preset keeps a blank character so background cells can stay empty
+    /// Braille codes equal the pattern's dot bitmask (U+2800 + code)
+    pub fn charset(&self) -> Charset {
+        match self {
+            CharsetPreset::Default => Charset::default(),
+            CharsetPreset::Minimal => Charset::from_unique(" .:-=+*#%@".chars().collect()),
+            CharsetPreset::Blocks => Charset::from_unique(" ░▒▓█".chars().collect()),
+            CharsetPreset::Lines => Charset::from_unique(" |/\\-_".chars().collect()),
+            CharsetPreset::Full => Charset::from_unique((' '..='~').collect()),
+            CharsetPreset::Braille => Charset::from_unique((BRAILLE_BLANK..='\u{28FF}').collect()),
+        }
+    }
+}
+
+impl FromStr for CharsetPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CharsetPreset::ALL
+            .iter()
+            .copied()
+            .find(|preset| preset.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = CharsetPreset::ALL.iter().map(|p| p.name()).collect();
+                format!("unknown charset preset '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_charset() {
+        let charset = Charset::parse("@#%*+=-:. ").unwrap();
+        assert_eq!(charset.chars().iter().collect::<String>(), "@#%*+=-:. ");
+        assert!(charset.has_space());
+        assert_eq!(charset.index_of(' '), Some(9));
+
+        let deduped = Charset::parse("aabba").unwrap();
+        assert_eq!(deduped.chars(), &['a', 'b']);
+        assert!(!deduped.has_space());
+    }
+
+    #[test]
+    fn test_parse_charset_rejects_invalid() {
+        assert!(Charset::parse("x").is_err());
+        assert!(Charset::parse("ab\t").is_err());
+        let too_many: String = ('\u{100}'..'\u{300}').collect();
+        assert!(Charset::parse(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_usage_counts_each_code() {
+        let charset = Charset::parse("@#. ").unwrap();
+        assert_eq!(charset.usage(&charset.encode("## .# ").unwrap()), vec![0, 3, 1, 2]);
+        assert_eq!(charset.usage(&[]), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_random_with_background_prob_respects_set() {
+        let charset = Charset::parse("#@").unwrap();
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            // No space in the set, so background probability can never produce one
+            assert!((charset.random_with_background_prob(1.0, &mut rng) as usize) < charset.len());
+        }
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!("Blocks".parse::<CharsetPreset>(), Ok(CharsetPreset::Blocks));
+        assert!("braile".parse::<CharsetPreset>().is_err());
+
+        let blocks = CharsetPreset::Blocks.charset();
+        assert_eq!(blocks.encode("░█ "), Some(vec![1, 4, 0]));
+        assert_eq!(CharsetPreset::Full.charset().len(), 95);
+        assert_eq!(CharsetPreset::Default.charset(), Charset::default());
+        assert!(CharsetPreset::ALL.iter().all(|p| p.charset().has_space()));
+
+        let braille = CharsetPreset::Braille.charset();
+        assert_eq!(braille.len(), 256);
+        assert_eq!(braille.char_at(0b1000_0001), '\u{2881}');
+    }
+}
@@ -0,0 +1,604 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// File formats the final art can be written in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Plain UTF-8 text, one line per row
+    #[default]
+    Text,
+    /// Classic ANSI art: CP437 bytes with a SAUCE metadata record
+    Ans,
+    /// The art plus run metadata as a JSON object
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "ans" | "ansi" => Ok(OutputFormat::Ans),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format '{}' (expected text, ans or json)", s)),
+        }
+    }
+}
+
+/// Descriptive fields stored in a SAUCE record; longer values are truncated to the field size
+#[derive(Clone, Debug, Default)]
+pub struct SauceInfo {
+    pub title: String,
+    pub author: String,
+    pub group: String,
+}
+
+/// Final art and the metadata describing the run that produced it, serialized by `--format json`
+#[derive(Clone, Debug, Serialize)]
+pub struct RunReport {
+    /// The art as UTF-8 text, rows separated by newlines
+    pub art: String,
+    /// Width in characters
+    pub width: u32,
+    /// Height in characters
+    pub height: u32,
+    /// Final fitness (0.0 to 1.0)
+    pub fitness: f64,
+    /// Generations evaluated by the genetic algorithm; absent in brute-force mode
+    pub generations_run: Option<u32>,
+    /// Wall-clock optimization time in seconds
+    pub elapsed_seconds: f64,
+    /// Optimizer used: "genetic", "brute-force" or "luminance"
+    pub mode: String,
+    /// Characters the optimizer was allowed to place
+    pub charset: String,
+    /// Random seed of the genetic algorithm; absent in brute-force mode
+    pub seed: Option<u64>,
+    /// Genetic algorithm settings the run was started with; absent for `convert`
+    pub parameters: Option<RunParameters>,
+}
+
+/// Run settings recorded in a `RunReport`
+#[derive(Clone, Debug, Serialize)]
+pub struct RunParameters {
+    pub input: String,
+    pub population: usize,
+    pub generations: u32,
+    pub jobs: usize,
+    pub mutation_rate: f64,
+    pub mutation: String,
+    pub targeted_mutation: f64,
+    pub crossover_rate: f64,
+    pub crossover: String,
+    pub replacement: String,
+    pub selection: String,
+    pub tournament_size: usize,
+    pub fitness_metric: String,
+    pub pareto: Option<String>,
+    pub max_ink: Option<f64>,
+    pub ignore_mask: Option<String>,
+    pub center_weight: Option<f64>,
+    pub edge_weight: f64,
+    pub tolerance: u8,
+    pub fp_penalty: f64,
+    pub alps_layers: usize,
+    pub alps_age_gap: u32,
+    pub local_search: usize,
+    pub islands: usize,
+    pub migration_interval: u32,
+    pub migrants: usize,
+    pub block_search: Option<usize>,
+    pub beam_width: Option<usize>,
+    pub error_diffusion: bool,
+    pub traversal: String,
+    pub refine: usize,
+    pub hybrid: Option<f64>,
+    pub smart_init: Option<f64>,
+    pub stall_generations: u32,
+    pub stall_epsilon: f64,
+    pub restart_on_stall: bool,
+    pub target_fitness: Option<f64>,
+    pub sharing_radius: Option<f64>,
+    pub init_char: Option<char>,
+    pub font_size: f32,
+    pub white_background: bool,
+    pub invert_source: bool,
+    pub fit: String,
+    pub cell_aspect: f32,
+    pub gamma: f64,
+    pub auto_contrast: bool,
+    pub equalize: bool,
+    pub clahe: Option<f64>,
+    pub clahe_tiles: Option<u32>,
+    pub levels: Option<String>,
+    pub posterize: Option<u32>,
+}
+
+impl RunParameters {
+    /// Returns the set parameters as (name, value) pairs sorted by name, for listing; unset options are left out
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let serde_json::Value::Object(fields) = serde_json::to_value(self).expect("run parameters serialize to JSON") else {
+            return Vec::new();
+        };
+        fields
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(text) => Some((name, text)),
+                value => Some((name, value.to_string())),
+            })
+            .collect()
+    }
+}
+
+impl RunReport {
+    /// Serializes the report as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("run report serializes to JSON")
+    }
+}
+
+/// One line of the `--log-jsonl` run log, named by its `event` field
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogEvent {
+    /// The genetic algorithm is about to run
+    Start { width: u32, height: u32, seed: u64, parameters: Box<RunParameters> },
+    /// An evaluated generation, with the population's fitness spread (0.0 to 1.0)
+    Generation {
+        generation: u32,
+        best: f64,
+        mean: f64,
+        median: f64,
+        worst: f64,
+        diversity: f64,
+        mutation_rate: f64,
+        elapsed_seconds: f64,
+    },
+    /// The best fitness gained no more than `stall_epsilon` in the last `stall_generations` generations
+    Stagnation { generation: u32, best_fitness: f64, stall_generations: u32, stall_epsilon: f64 },
+    /// The population was reseeded after stagnating; `restarts` counts the restarts so far
+    Restart { generation: u32, restarts: u32 },
+    /// The run ended; the fitness and time include any refinement after evolution
+    Finish { generations_run: u32, fitness: f64, elapsed_seconds: f64, stop_reason: String, restarts: u32 },
+}
+
+/// A `LogEvent` with the Unix time it was written at
+#[derive(Serialize)]
+struct LogLine<'e> {
+    timestamp: f64,
+    #[serde(flatten)]
+    event: &'e LogEvent,
+}
+
+impl LogEvent {
+    /// Serializes the event as a single line of JSON stamped with the current Unix time in seconds
+    pub fn to_json_line(&self) -> String {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+        self.to_json_line_at(timestamp)
+    }
+
+    fn to_json_line_at(&self, timestamp: f64) -> String {
+        serde_json::to_string(&LogLine { timestamp: (timestamp * 1000.0).round() / 1000.0, event: self }).expect("log event serializes to JSON")
+    }
+}
+
+/// Size of a SAUCE record in bytes
+pub const SAUCE_RECORD_LEN: usize = 128;
+
+/// End-of-file marker separating the art from its SAUCE record
+const SAUCE_EOF: u8 = 0x1A;
+
+/// Maps a character to its CP437 byte, if the code page can display it
+pub fn to_cp437(ch: char) -> Option<u8> {
+    match ch {
+        ' '..='~' => Some(ch as u8),
+        '░' => Some(0xB0),
+        '▒' => Some(0xB1),
+        '▓' => Some(0xB2),
+        '█' => Some(0xDB),
+        '▄' => Some(0xDC),
+        '▌' => Some(0xDD),
+        '▐' => Some(0xDE),
+        '▀' => Some(0xDF),
+        _ => None,
+    }
+}
+
+/// Encodes art as an ANSI art file: CP437 text with CRLF line endings, followed by a SAUCE record
+/// White backgrounds are emitted as black characters on a light gray background, the closest CGA pair
+pub fn encode_ans(art: &str, white_background: bool, sauce: &SauceInfo) -> Result<Vec<u8>, String> {
+    encode_ans_animation(&[art], white_background, sauce)
+}
+
+/// Encodes frames as an ANSI animation: each frame is redrawn from the top-left corner of a cleared screen
+/// A single frame produces a plain ANSI art file; the SAUCE record describes the size of one frame
+pub fn encode_ans_animation<S: AsRef<str>>(frames: &[S], white_background: bool, sauce: &SauceInfo) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    data.extend_from_slice(if white_background { b"\x1b[0;30;47m" } else { b"\x1b[0m" });
+    if frames.len() > 1 {
+        data.extend_from_slice(b"\x1b[2J");
+    }
+
+    let mut width = 0usize;
+    let mut rows = 0usize;
+    for frame in frames {
+        if frames.len() > 1 {
+            data.extend_from_slice(b"\x1b[H"); // Cursor home so each frame overwrites the last
+        }
+        let (frame_width, frame_rows) = push_cp437_lines(&mut data, frame.as_ref())?;
+        width = width.max(frame_width);
+        rows = rows.max(frame_rows);
+    }
+    data.extend_from_slice(b"\x1b[0m");
+
+    let file_size = data.len() as u32;
+    data.push(SAUCE_EOF);
+    data.extend_from_slice(&sauce_record(sauce, file_size, width as u16, rows as u16, &today()));
+    Ok(data)
+}
+
+/// Appends art as CP437 bytes with CRLF line endings, returning its width and number of lines
+fn push_cp437_lines(data: &mut Vec<u8>, art: &str) -> Result<(usize, usize), String> {
+    let mut width = 0usize;
+    let mut rows = 0usize;
+    for (row, line) in art.lines().enumerate() {
+        if row > 0 {
+            data.extend_from_slice(b"\r\n");
+        }
+        let mut line_width = 0;
+        for ch in line.chars() {
+            let byte = to_cp437(ch).ok_or_else(|| format!("character {:?} has no CP437 equivalent", ch))?;
+            data.push(byte);
+            line_width += 1;
+        }
+        width = width.max(line_width);
+        rows += 1;
+    }
+    Ok((width, rows))
+}
+
+/// Returns the path of one numbered frame derived from an output path, e.g. `out.txt` -> `out_0001.txt`
+/// Frames are numbered from 1
+pub fn frame_path(output: &Path, index: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = output.extension().map(|ext| ext.to_string_lossy()).unwrap_or("txt".into());
+    output.with_file_name(format!("{}_{:04}.{}", stem, index + 1, extension))
+}
+
+/// Returns the existing numbered frames of a text animation, given its output path or any one of its frames
+/// A path that is not part of a frame series yields just that file if it exists
+pub fn frame_series(path: &Path) -> Vec<PathBuf> {
+    // `art_0003.txt` names the series `art.txt`
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let base = match stem.rsplit_once('_') {
+        Some((name, number)) if number.len() == 4 && number.bytes().all(|b| b.is_ascii_digit()) => {
+            let extension = path.extension().map(|ext| ext.to_string_lossy()).unwrap_or("txt".into());
+            path.with_file_name(format!("{}.{}", name, extension))
+        }
+        _ => path.to_path_buf(),
+    };
+
+    let frames: Vec<PathBuf> = (0..).map(|index| frame_path(&base, index)).take_while(|frame| frame.is_file()).collect();
+    if frames.is_empty() && path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        frames
+    }
+}
+
+/// Replaces a file's contents all at once: the data is written to a hidden temporary file beside it, synced and then
+/// renamed over it, so a crash midway leaves the previous contents rather than a truncated file
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let name = path.file_name().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "not a file path"))?;
+    let temp = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+    let written = std::fs::File::create(&temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    match written.and_then(|()| std::fs::rename(&temp, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
+/// Maps a CP437 byte back to the character `to_cp437` encodes it from, or '?' for anything else
+pub fn from_cp437(byte: u8) -> char {
+    match byte {
+        b' '..=b'~' => byte as char,
+        0xB0 => '░',
+        0xB1 => '▒',
+        0xB2 => '▓',
+        0xDB => '█',
+        0xDC => '▄',
+        0xDD => '▌',
+        0xDE => '▐',
+        0xDF => '▀',
+        _ => '?',
+    }
+}
+
+/// Decodes an ANSI art file into its frames of UTF-8 text, as written by `encode_ans_animation`
+/// The SAUCE record and color sequences are dropped; each cursor-home sequence starts a new frame
+pub fn decode_ans_frames(data: &[u8]) -> Vec<String> {
+    let mut body = data;
+    if body.len() >= SAUCE_RECORD_LEN && body[body.len() - SAUCE_RECORD_LEN..].starts_with(b"SAUCE00") {
+        body = &body[..body.len() - SAUCE_RECORD_LEN];
+    }
+    if let Some(eof) = body.iter().position(|&b| b == SAUCE_EOF) {
+        body = &body[..eof];
+    }
+
+    let mut frames = Vec::new();
+    let mut frame = String::new();
+    let mut bytes = body.iter().copied();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            0x1B => {
+                // Control sequence: ESC '[' parameters, ending with a byte in @..~
+                let final_byte = bytes.by_ref().skip(1).find(|b| (0x40..=0x7E).contains(b));
+                if final_byte == Some(b'H') && !frame.is_empty() {
+                    frames.push(std::mem::take(&mut frame));
+                }
+            }
+            b'\r' => {}
+            b'\n' => frame.push('\n'),
+            _ => frame.push(from_cp437(byte)),
+        }
+    }
+    if !frame.is_empty() {
+        frames.push(frame);
+    }
+    frames
+}
+
+/// Builds a SAUCE 00 record describing an ANSi character file
+fn sauce_record(info: &SauceInfo, file_size: u32, width: u16, rows: u16, date: &str) -> [u8; SAUCE_RECORD_LEN] {
+    let mut record = Vec::with_capacity(SAUCE_RECORD_LEN);
+    record.extend_from_slice(b"SAUCE00");
+    push_field(&mut record, &info.title, 35);
+    push_field(&mut record, &info.author, 20);
+    push_field(&mut record, &info.group, 20);
+    push_field(&mut record, date, 8);
+    record.extend_from_slice(&file_size.to_le_bytes());
+    record.push(1); // DataType: Character
+    record.push(1); // FileType: ANSi
+    record.extend_from_slice(&width.to_le_bytes()); // TInfo1: character width
+    record.extend_from_slice(&rows.to_le_bytes()); // TInfo2: number of lines
+    record.extend_from_slice(&[0; 4]); // TInfo3 and TInfo4 are unused for ANSi
+    record.push(0); // No comment block
+    record.push(0); // TFlags: default blink mode, spacing and aspect
+    let mut font = b"IBM VGA".to_vec();
+    font.resize(22, 0); // TInfoS: zero-padded font name
+    record.extend_from_slice(&font);
+
+    record.try_into().expect("SAUCE record is exactly 128 bytes")
+}
+
+/// Appends a space-padded SAUCE character field, replacing characters CP437 cannot represent
+fn push_field(record: &mut Vec<u8>, value: &str, len: usize) {
+    let mut bytes: Vec<u8> = value.chars().map(|ch| to_cp437(ch).unwrap_or(b'?')).take(len).collect();
+    bytes.resize(len, b' ');
+    record.extend_from_slice(&bytes);
+}
+
+/// Returns the current UTC date as CCYYMMDD
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// Returns the current UTC date and time as CCYYMMDD-hhmmss, for naming files
+pub fn file_timestamp() -> String {
+    file_timestamp_at(SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0))
+}
+
+fn file_timestamp_at(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let time = seconds % 86_400;
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) Gregorian date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_ans_appends_sauce() {
+        let sauce = SauceInfo { title: "duck".to_string(), ..Default::default() };
+        let data = encode_ans("#█\n ░", false, &sauce).unwrap();
+
+        let (body, record) = data.split_at(data.len() - SAUCE_RECORD_LEN);
+        assert_eq!(body, b"\x1b[0m#\xDB\r\n \xB0\x1b[0m\x1a");
+        assert_eq!(&record[..7], b"SAUCE00");
+        assert_eq!(&record[7..11], b"duck");
+        assert_eq!(u32::from_le_bytes(record[90..94].try_into().unwrap()), body.len() as u32 - 1);
+        assert_eq!(&record[94..96], &[1, 1]);
+        assert_eq!(u16::from_le_bytes([record[96], record[97]]), 2); // width
+        assert_eq!(u16::from_le_bytes([record[98], record[99]]), 2); // lines
+    }
+
+    #[test]
+    fn test_encode_ans_animation_homes_cursor_per_frame() {
+        let data = encode_ans_animation(&["ab", "ba"], false, &SauceInfo::default()).unwrap();
+        let body = &data[..data.len() - SAUCE_RECORD_LEN];
+        assert_eq!(body, b"\x1b[0m\x1b[2J\x1b[Hab\x1b[Hba\x1b[0m\x1a");
+    }
+
+    #[test]
+    fn test_frame_path() {
+        assert_eq!(frame_path(Path::new("out/art.txt"), 0), Path::new("out/art_0001.txt"));
+        assert_eq!(frame_path(Path::new("art"), 41), Path::new("art_0042.txt"));
+    }
+
+    #[test]
+    fn test_decode_ans_frames_round_trip() {
+        let frames = ["#█\n ░", "░ \n█#"];
+        let data = encode_ans_animation(&frames, true, &SauceInfo::default()).unwrap();
+        assert_eq!(decode_ans_frames(&data), frames);
+
+        let single = encode_ans("ab\ncd", false, &SauceInfo::default()).unwrap();
+        assert_eq!(decode_ans_frames(&single), ["ab\ncd"]);
+    }
+
+    #[test]
+    fn test_frame_series() {
+        let dir = std::env::temp_dir().join(format!("asciigen_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for index in 0..3 {
+            std::fs::write(frame_path(&dir.join("art.txt"), index), "#").unwrap();
+        }
+        std::fs::write(dir.join("still.txt"), "#").unwrap();
+
+        assert_eq!(frame_series(&dir.join("art.txt")).len(), 3);
+        assert_eq!(frame_series(&dir.join("art_0002.txt")), frame_series(&dir.join("art.txt")));
+        assert_eq!(frame_series(&dir.join("still.txt")), [dir.join("still.txt")]);
+        assert!(frame_series(&dir.join("missing.txt")).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = std::env::temp_dir().join(format!("asciigen_atomic_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("art.txt");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1, "no temporary file is left behind");
+        assert!(write_atomic(&dir.join("missing").join("art.txt"), b"x").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_encode_ans_rejects_non_cp437() {
+        assert!(encode_ans("⣿", false, &SauceInfo::default()).is_err());
+    }
+
+    #[test]
+    fn test_run_report_json() {
+        let report = RunReport {
+            art: "#.\n.#".to_string(),
+            width: 2,
+            height: 2,
+            fitness: 0.5,
+            generations_run: None,
+            elapsed_seconds: 0.25,
+            mode: "brute-force".to_string(),
+            charset: "#. ".to_string(),
+            seed: None,
+            parameters: Some(RunParameters {
+                input: "in.png".to_string(),
+                population: 80,
+                generations: 100,
+                jobs: 4,
+                mutation_rate: 0.01,
+                mutation: "point".to_string(),
+                targeted_mutation: 0.0,
+                crossover_rate: 0.8,
+                crossover: "uniform".to_string(),
+                replacement: "generational".to_string(),
+                selection: "tournament".to_string(),
+                tournament_size: 3,
+                fitness_metric: "overlap".to_string(),
+                pareto: None,
+                max_ink: None,
+                ignore_mask: None,
+                center_weight: None,
+                edge_weight: 0.0,
+                tolerance: 30,
+                fp_penalty: 0.005,
+                alps_layers: 0,
+                alps_age_gap: 10,
+                local_search: 0,
+                islands: 0,
+                migration_interval: 10,
+                migrants: 2,
+                block_search: None,
+                beam_width: None,
+                error_diffusion: false,
+                traversal: "raster".to_string(),
+                refine: 0,
+                hybrid: None,
+                smart_init: None,
+                stall_generations: 0,
+                stall_epsilon: 0.0001,
+                restart_on_stall: false,
+                target_fitness: None,
+                sharing_radius: None,
+                init_char: Some('#'),
+                font_size: 12.0,
+                white_background: false,
+                invert_source: false,
+                fit: "contain".to_string(),
+                cell_aspect: 0.5,
+                gamma: 1.0,
+                auto_contrast: false,
+                equalize: false,
+                clahe: None,
+                clahe_tiles: None,
+                levels: None,
+                posterize: None,
+            }),
+        };
+
+        let value: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(value["art"], "#.\n.#");
+        assert_eq!(value["fitness"], 0.5);
+        assert!(value["seed"].is_null());
+        assert_eq!(value["parameters"]["init_char"], "#");
+
+        let entries = report.parameters.as_ref().unwrap().entries();
+        assert!(entries.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(entries.contains(&("init_char".to_string(), "#".to_string())));
+        assert!(entries.contains(&("population".to_string(), "80".to_string())));
+        assert!(!entries.iter().any(|(name, _)| name == "pareto"));
+        assert_eq!("JSON".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+    }
+
+    #[test]
+    fn test_log_event_json_lines() {
+        let line = LogEvent::Restart { generation: 40, restarts: 2 }.to_json_line_at(1_700_000_000.123_45);
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value, serde_json::json!({ "timestamp": 1_700_000_000.123, "event": "restart", "generation": 40, "restarts": 2 }));
+
+        let finish = LogEvent::Finish { generations_run: 100, fitness: 0.25, elapsed_seconds: 3.5, stop_reason: "stalled".to_string(), restarts: 0 };
+        let value: serde_json::Value = serde_json::from_str(&finish.to_json_line()).unwrap();
+        assert_eq!((value["event"].as_str(), value["stop_reason"].as_str()), (Some("finish"), Some("stalled")));
+        assert!(value["timestamp"].as_f64().unwrap() > 1_700_000_000.0);
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        assert_eq!(file_timestamp_at(19_782 * 86_400 + 13 * 3600 + 5 * 60 + 9), "20240229-130509");
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{Charset, EvolutionSession, ImageProcessor, SessionConfig};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// Opaque handle to a genetic algorithm run, created by `asciigen_session_new`
+pub struct AsciigenSession(EvolutionSession);
+
+/// Settings for `asciigen_session_new`; start from `asciigen_config_default()` and override fields
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct AsciigenConfig {
+    /// Width of the art in characters; the height follows the image's aspect ratio
+    pub width: u32,
+    /// Population size (20-1000)
+    pub population: u32,
+    /// Threads used for fitness evaluation
+    pub threads: u32,
+    /// Font size in pixels used for fitness rendering (4-64)
+    pub font_size: f32,
+    /// True to optimize for dark characters on a white background
+    pub white_background: bool,
+    /// True to invert the source image
+    pub invert_source: bool,
+    /// True to use `seed` for a reproducible run; otherwise a random seed is chosen
+    pub use_seed: bool,
+    pub seed: u64,
+    /// NUL-terminated UTF-8 character set, or NULL for the built-in set
+    pub charset: *const c_char,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Into<String>) {
+    let message = CString::new(message.into().replace('\0', " ")).expect("interior NULs were replaced");
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+/// Returns the default settings: 40 characters wide, population 80, 4 threads, 12 pixel font, built-in set
+#[no_mangle]
+pub extern "C" fn asciigen_config_default() -> AsciigenConfig {
+    let defaults = SessionConfig::default();
+    AsciigenConfig {
+        width: defaults.width,
+        population: defaults.population as u32,
+        threads: defaults.threads as u32,
+        font_size: defaults.font_size,
+        white_background: defaults.white_background,
+        invert_source: defaults.invert_source,
+        use_seed: false,
+        seed: 0,
+        charset: ptr::null(),
+    }
+}
+
+/// Decodes an encoded image (PNG, JPEG, GIF, ...) of `image_len` bytes and prepares a run
+/// Returns NULL on failure; `asciigen_last_error()` then describes the problem
+/// The returned session must be released with `asciigen_session_free`
+///
+/// # Safety
+/// `image_data` must point to `image_len` readable bytes and `config` must be NULL or a valid pointer whose
+/// `charset` is NULL or a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_new(image_data: *const u8, image_len: usize, config: *const AsciigenConfig) -> *mut AsciigenSession {
+    if image_data.is_null() {
+        set_last_error("image_data is NULL");
+        return ptr::null_mut();
+    }
+    let config = if config.is_null() { asciigen_config_default() } else { *config };
+
+    let charset = if config.charset.is_null() {
+        Charset::default()
+    } else {
+        let parsed = CStr::from_ptr(config.charset)
+            .to_str()
+            .map_err(|_| "charset is not valid UTF-8".to_string())
+            .and_then(Charset::parse);
+        match parsed {
+            Ok(charset) => charset,
+            Err(e) => {
+                set_last_error(e);
+                return ptr::null_mut();
+            }
+        }
+    };
+
+    let bytes = std::slice::from_raw_parts(image_data, image_len);
+    let image = match ImageProcessor::new().load_image_from_memory(bytes) {
+        Ok(image) => image,
+        Err(e) => {
+            set_last_error(e.to_string());
+            return ptr::null_mut();
+        }
+    };
+
+    let session_config = SessionConfig {
+        width: config.width,
+        population: config.population as usize,
+        charset,
+        font_size: config.font_size,
+        white_background: config.white_background,
+        invert_source: config.invert_source,
+        seed: config.use_seed.then_some(config.seed),
+        threads: config.threads as usize,
+    };
+    match EvolutionSession::new(&image, &session_config) {
+        Ok(session) => Box::into_raw(Box::new(AsciigenSession(session))),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a session; NULL is ignored
+///
+/// # Safety
+/// `session` must be NULL or a pointer returned by `asciigen_session_new` that has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_free(session: *mut AsciigenSession) {
+    if !session.is_null() {
+        drop(Box::from_raw(session));
+    }
+}
+
+/// Runs the given number of generations and returns the best fitness afterwards (0.0 to 1.0)
+///
+/// # Safety
+/// `session` must be a live pointer returned by `asciigen_session_new`
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_step(session: *mut AsciigenSession, generations: u32) -> f64 {
+    (*session).0.step(generations)
+}
+
+/// Returns the best fitness so far (0.0 to 1.0)
+///
+/// # Safety
+/// `session` must be a live pointer returned by `asciigen_session_new`
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_fitness(session: *const AsciigenSession) -> f64 {
+    (*session).0.fitness()
+}
+
+/// Returns the number of generations run so far
+///
+/// # Safety
+/// `session` must be a live pointer returned by `asciigen_session_new`
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_generation(session: *const AsciigenSession) -> u32 {
+    (*session).0.generation()
+}
+
+/// Writes the art size in characters to `width` and `height`; either may be NULL
+///
+/// # Safety
+/// `session` must be a live pointer returned by `asciigen_session_new`; `width` and `height` must be NULL or writable
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_dimensions(session: *const AsciigenSession, width: *mut u32, height: *mut u32) {
+    let (w, h) = (*session).0.dimensions();
+    if !width.is_null() {
+        *width = w;
+    }
+    if !height.is_null() {
+        *height = h;
+    }
+}
+
+/// Returns the best art so far as a NUL-terminated UTF-8 string, rows separated by '\n'
+/// The caller owns the string and must release it with `asciigen_string_free`
+///
+/// # Safety
+/// `session` must be a live pointer returned by `asciigen_session_new`
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_session_best_art(session: *const AsciigenSession) -> *mut c_char {
+    // Characters are validated printable at charset parse time, so the art never contains NUL
+    CString::new((*session).0.best_art()).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Releases a string returned by this library; NULL is ignored
+///
+/// # Safety
+/// `s` must be NULL or a string returned by `asciigen_session_best_art` that has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn asciigen_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Returns a description of the last error on the calling thread, or NULL if there was none
+/// The string is owned by the library and stays valid until the next failing call on the same thread
+#[no_mangle]
+pub extern "C" fn asciigen_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_c_api_round_trip() {
+        let mut png = Vec::new();
+        RgbImage::from_fn(40, 20, |x, _| if x < 20 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) })
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        unsafe {
+            let mut config = asciigen_config_default();
+            config.width = 8;
+            config.population = 20;
+            config.use_seed = true;
+            config.seed = 9;
+            config.charset = c"#. ".as_ptr();
+
+            let session = asciigen_session_new(png.as_ptr(), png.len(), &config);
+            assert!(!session.is_null());
+            asciigen_session_step(session, 2);
+            assert_eq!(asciigen_session_generation(session), 2);
+
+            let (mut width, mut height) = (0, 0);
+            asciigen_session_dimensions(session, &mut width, &mut height);
+            assert_eq!((width, height), (8, 2));
+
+            let art = asciigen_session_best_art(session);
+            let text = CStr::from_ptr(art).to_str().unwrap().to_string();
+            assert!(text.chars().all(|c| "#. \n".contains(c)));
+            asciigen_string_free(art);
+            asciigen_session_free(session);
+
+            assert!(asciigen_session_new(b"junk".as_ptr(), 4, ptr::null()).is_null());
+            assert!(!asciigen_last_error().is_null());
+        }
+    }
+}
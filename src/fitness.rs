@@ -0,0 +1,1138 @@
+use image::{ImageBuffer, Luma};
+
+/// Intensity difference (out of 255) below which a lit target pixel counts as matched
+pub const INTENSITY_TOLERANCE: u8 = 30;
+
+/// Number of false-positive pixels that cancel out one matched pixel (a 0.005 penalty each)
+pub const FALSE_POSITIVES_PER_MATCH: u64 = 200;
+
+/// Tunable constants of the overlap metric, trading sharpness against coverage
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OverlapParams {
+    /// Intensity difference (out of 255) below which a lit target pixel counts as matched
+    pub tolerance: u8,
+    /// Fitness lost per false-positive pixel, relative to one matched pixel
+    pub false_positive_penalty: f64,
+}
+
+impl Default for OverlapParams {
+    fn default() -> Self {
+        Self {
+            tolerance: INTENSITY_TOLERANCE,
+            false_positive_penalty: 1.0 / FALSE_POSITIVES_PER_MATCH as f64,
+        }
+    }
+}
+
+/// Measure the optimizers maximize when comparing a rendering with the target
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FitnessMetric {
+    /// Fraction of foreground pixels matched within tolerance, minus a small false-positive penalty
+    #[default]
+    Overlap,
+    /// One minus the root-mean-square intensity error, so every pixel counts including the background
+    Mse,
+    /// Mean structural similarity (SSIM) over small windows, rewarding local contrast and structure
+    Ssim,
+    /// Agreement between Sobel edge maps alone, for line art where outlines are all that matter
+    Edge,
+}
+
+impl std::str::FromStr for FitnessMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "overlap" => Ok(Self::Overlap),
+            "mse" => Ok(Self::Mse),
+            "ssim" => Ok(Self::Ssim),
+            "edge" => Ok(Self::Edge),
+            _ => Err(format!("unknown fitness metric '{}' (expected 'overlap', 'mse', 'ssim' or 'edge')", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for FitnessMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Overlap => "overlap",
+            Self::Mse => "mse",
+            Self::Ssim => "ssim",
+            Self::Edge => "edge",
+        })
+    }
+}
+
+/// Returns the intensity threshold separating background from foreground pixels for the background color
+pub fn background_threshold(white_background: bool) -> u8 {
+    if white_background { 200 } else { 50 }
+}
+
+/// Per-pixel importance of the target, aligned with the target image: 0 excludes a pixel from scoring entirely
+/// (such as a watermark or border) and `PixelWeights::FULL` counts it like an unweighted pixel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PixelWeights {
+    width: u32,
+    height: u32,
+    /// Integer weights so the overlap loop can multiply instead of branch
+    weights: Vec<u8>,
+}
+
+impl PixelWeights {
+    /// Weight of a pixel that counts fully
+    pub const FULL: u8 = 255;
+
+    /// Creates weights that count every pixel fully
+    pub fn uniform(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            weights: vec![Self::FULL; (width * height) as usize],
+        }
+    }
+
+    /// Builds weights from an ignore mask already sized to the target: pixels brighter than mid-gray are ignored
+    pub fn from_ignore_mask(mask_image: &ImageBuffer<Luma<u8>, Vec<u8>>) -> Self {
+        Self {
+            width: mask_image.width(),
+            height: mask_image.height(),
+            weights: mask_image.as_raw().iter().map(|&value| if value > 127 { 0 } else { Self::FULL }).collect(),
+        }
+    }
+
+    /// Creates a radial falloff where the center counts fully and weight drops to 1 / (1 + strength) in the corners
+    /// The distance is normalized per axis so the falloff follows the image's aspect ratio; strength 0 is uniform
+    pub fn center_weighted(width: u32, height: u32, strength: f64) -> Self {
+        let (center_x, center_y) = (width as f64 / 2.0, height as f64 / 2.0);
+        let weights = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let dx = (x as f64 + 0.5 - center_x) / center_x.max(1.0);
+                let dy = (y as f64 + 0.5 - center_y) / center_y.max(1.0);
+                // Squared distance normalized to 1.0 in the corners
+                let distance = (dx * dx + dy * dy) / 2.0;
+                let weight = Self::FULL as f64 / (1.0 + strength.max(0.0) * distance);
+                weight.round().max(1.0) as u8
+            })
+            .collect();
+        Self { width, height, weights }
+    }
+
+    /// Multiplies these weights by another set of the same size, so an ignore mask and a falloff can be combined
+    pub fn combine(&mut self, other: &PixelWeights) {
+        if self.dimensions() != other.dimensions() {
+            return;
+        }
+        for (weight, &other_weight) in self.weights.iter_mut().zip(&other.weights) {
+            *weight = ((*weight as u32 * other_weight as u32 + Self::FULL as u32 / 2) / Self::FULL as u32) as u8;
+        }
+    }
+
+    /// Returns the width and height in pixels
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the weights row by row
+    pub fn as_raw(&self) -> &[u8] {
+        &self.weights
+    }
+
+    /// Returns the weight of a pixel; pixels outside the weighted area count fully
+    pub fn weight(&self, x: u32, y: u32) -> u8 {
+        if x < self.width && y < self.height {
+            self.weights[(y * self.width + x) as usize]
+        } else {
+            Self::FULL
+        }
+    }
+
+    /// Returns the number of pixels excluded from scoring
+    pub fn ignored_count(&self) -> usize {
+        self.weights.iter().filter(|&&weight| weight == 0).count()
+    }
+
+    /// Returns the weights covering a row of a compared target region, or None when the region leaves the weighted area
+    fn row(&self, target_origin: (u32, u32), row: usize, width: usize) -> Option<&[u8]> {
+        let y = target_origin.1 as usize + row;
+        let x = target_origin.0 as usize;
+        if y >= self.height as usize || x + width > self.width as usize {
+            return None;
+        }
+        let start = y * self.width as usize + x;
+        Some(&self.weights[start..start + width])
+    }
+}
+
+/// Integer tallies produced by comparing a rendered ASCII region against the target image
+/// With pixel weights each pixel adds its weight instead of 1
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverlapCounts {
+    /// Lit target pixels whose ASCII pixel is within tolerance
+    pub matches: u64,
+    /// Lit ASCII pixels where the target is background
+    pub false_positives: u64,
+    /// Lit target pixels in the compared region
+    pub relevant: u64,
+}
+
+impl OverlapCounts {
+    /// Converts the tallies into a fitness value relative to the given number of non-background pixels
+    /// Clamped to 0.0 so heavy false-positive penalties never produce negative fitness
+    pub fn fitness(&self, non_background_pixels: f64) -> f64 {
+        let scaled_score = (self.matches * FALSE_POSITIVES_PER_MATCH) as f64 - self.false_positives as f64;
+        (scaled_score / (non_background_pixels * FALSE_POSITIVES_PER_MATCH as f64)).max(0.0)
+    }
+
+    /// Like `fitness`, but with a custom fitness cost per false positive instead of the default 0.005
+    pub fn penalized_fitness(&self, non_background_pixels: f64, false_positive_penalty: f64) -> f64 {
+        ((self.matches as f64 - self.false_positives as f64 * false_positive_penalty) / non_background_pixels).max(0.0)
+    }
+}
+
+/// Counts matches and false positives between a region of the ASCII image and a region of the target
+/// Works on raw row slices with integer accumulators so the inner loop can be vectorized
+/// Optional pixel weights, aligned with the target, scale each pixel's contribution
+#[allow(clippy::too_many_arguments)]
+pub fn count_overlap(
+    ascii_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    ascii_origin: (u32, u32),
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    target_origin: (u32, u32),
+    width: u32,
+    height: u32,
+    background_threshold: u8,
+    tolerance: u8,
+    pixel_weights: Option<&PixelWeights>,
+) -> OverlapCounts {
+    let ascii_stride = ascii_image.width() as usize;
+    let target_stride = target_image.width() as usize;
+    let ascii_raw = ascii_image.as_raw();
+    let target_raw = target_image.as_raw();
+    let width = width as usize;
+
+    let mut counts = OverlapCounts::default();
+
+    for row in 0..height as usize {
+        let ascii_start = (ascii_origin.1 as usize + row) * ascii_stride + ascii_origin.0 as usize;
+        let target_start = (target_origin.1 as usize + row) * target_stride + target_origin.0 as usize;
+        let ascii_row = &ascii_raw[ascii_start..ascii_start + width];
+        let target_row = &target_raw[target_start..target_start + width];
+
+        let mut matches = 0u32;
+        let mut false_positives = 0u32;
+        let mut relevant = 0u32;
+
+        let mut tally = |ascii_pixel: u8, target_pixel: u8, weight: u32| {
+            let target_is_lit = target_pixel > background_threshold;
+            let ascii_is_lit = ascii_pixel > background_threshold;
+            let close = ascii_pixel.abs_diff(target_pixel) < tolerance;
+
+            matches += (target_is_lit & close) as u32 * weight;
+            false_positives += (!target_is_lit & ascii_is_lit) as u32 * weight;
+            relevant += target_is_lit as u32 * weight;
+        };
+
+        match pixel_weights.and_then(|weights| weights.row(target_origin, row, width)) {
+            Some(weight_row) => {
+                for ((&ascii_pixel, &target_pixel), &weight) in ascii_row.iter().zip(target_row.iter()).zip(weight_row) {
+                    tally(ascii_pixel, target_pixel, weight as u32);
+                }
+            }
+            None => {
+                for (&ascii_pixel, &target_pixel) in ascii_row.iter().zip(target_row.iter()) {
+                    tally(ascii_pixel, target_pixel, 1);
+                }
+            }
+        }
+
+        counts.matches += matches as u64;
+        counts.false_positives += false_positives as u64;
+        counts.relevant += relevant as u64;
+    }
+
+    #[cfg(feature = "fitness-parity")]
+    debug_assert_parity(&counts, ascii_image, ascii_origin, target_image, target_origin, width as u32, height, background_threshold, tolerance, pixel_weights);
+
+    counts
+}
+
+/// Original floating-point overlap score (+1.0 per match, -0.005 per false positive, times the pixel weight) for the region
+/// Kept as the reference the integer fast path is verified against
+#[cfg(any(test, feature = "fitness-parity"))]
+#[allow(clippy::too_many_arguments)]
+pub fn reference_overlap_score(
+    ascii_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    ascii_origin: (u32, u32),
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    target_origin: (u32, u32),
+    width: u32,
+    height: u32,
+    background_threshold: u8,
+    tolerance: u8,
+    pixel_weights: Option<&PixelWeights>,
+) -> f64 {
+    let mut score = 0.0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let weight = pixel_weights.map_or(1.0, |weights| weights.weight(target_origin.0 + x, target_origin.1 + y) as f64);
+            let ascii_pixel = ascii_image.get_pixel(ascii_origin.0 + x, ascii_origin.1 + y)[0];
+            let target_pixel = target_image.get_pixel(target_origin.0 + x, target_origin.1 + y)[0];
+
+            let ascii_is_lit = ascii_pixel > background_threshold;
+            let target_is_lit = target_pixel > background_threshold;
+
+            if target_is_lit {
+                let diff = (ascii_pixel as i32 - target_pixel as i32).abs();
+                if diff < tolerance as i32 {
+                    score += weight;
+                }
+            } else if ascii_is_lit {
+                score -= 0.005 * weight;
+            }
+        }
+    }
+
+    score
+}
+
+/// Panics in debug builds when the integer tallies disagree with the floating-point reference
+#[cfg(feature = "fitness-parity")]
+#[allow(clippy::too_many_arguments)]
+fn debug_assert_parity(
+    counts: &OverlapCounts,
+    ascii_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    ascii_origin: (u32, u32),
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    target_origin: (u32, u32),
+    width: u32,
+    height: u32,
+    background_threshold: u8,
+    tolerance: u8,
+    pixel_weights: Option<&PixelWeights>,
+) {
+    let reference =
+        reference_overlap_score(ascii_image, ascii_origin, target_image, target_origin, width, height, background_threshold, tolerance, pixel_weights);
+    let fast = counts.matches as f64 - counts.false_positives as f64 / FALSE_POSITIVES_PER_MATCH as f64;
+    debug_assert!(
+        (reference - fast).abs() <= 1e-6 * (1.0 + reference.abs()),
+        "integer fitness {} diverged from reference {}",
+        fast,
+        reference
+    );
+}
+
+/// Counts pixels that are not background color in the target image, summing their weights when pixel weights are given
+pub fn count_non_background_pixels(
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    background_threshold: u8,
+    white_background: bool,
+    pixel_weights: Option<&PixelWeights>,
+) -> f64 {
+    // For black background mode: non-background pixels are bright (> threshold)
+    // For white background mode: non-background pixels are dark (< threshold)
+    let count: u64 = target_image
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| {
+            let intensity = pixel[0];
+            if white_background {
+                intensity < background_threshold
+            } else {
+                intensity > background_threshold
+            }
+        })
+        .map(|(x, y, _)| pixel_weights.map_or(1, |weights| weights.weight(x, y) as u64))
+        .sum();
+
+    count as f64
+}
+
+/// Converts a target pixel to the ink the rendering should show there: its brightness on black backgrounds and its
+/// darkness on white ones, with pixels on the background side of the threshold counting as no ink at all
+fn ink_level(target_pixel: u8, background_threshold: u8, white_background: bool) -> u8 {
+    match white_background {
+        false if target_pixel > background_threshold => target_pixel,
+        true if target_pixel < background_threshold => 255 - target_pixel,
+        _ => 0,
+    }
+}
+
+/// One minus the root-mean-square error between a region of the rendering and the target's ink levels (0.0 to 1.0)
+/// Each pixel's squared error is scaled by its weight, so ignored pixels drop out
+#[allow(clippy::too_many_arguments)]
+pub fn mse_similarity(
+    rendered: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    rendered_origin: (u32, u32),
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    target_origin: (u32, u32),
+    width: u32,
+    height: u32,
+    pixel_weights: Option<&PixelWeights>,
+    background_threshold: u8,
+    white_background: bool,
+) -> f64 {
+    let mut squared_error = 0u64;
+    let mut total_weight = 0u64;
+    for y in 0..height {
+        for x in 0..width {
+            let a = rendered.get_pixel(rendered_origin.0 + x, rendered_origin.1 + y)[0];
+            let b = ink_level(target_image.get_pixel(target_origin.0 + x, target_origin.1 + y)[0], background_threshold, white_background);
+            let weight = pixel_weights.map_or(1, |weights| weights.weight(target_origin.0 + x, target_origin.1 + y) as u64);
+            let diff = a.abs_diff(b) as u64;
+            squared_error += weight * diff * diff;
+            total_weight += weight;
+        }
+    }
+
+    if total_weight == 0 {
+        return 0.0;
+    }
+    1.0 - (squared_error as f64 / total_weight as f64).sqrt() / 255.0
+}
+
+/// Side in pixels of the square windows SSIM statistics are computed over; edge windows may be smaller
+pub const SSIM_WINDOW: u32 = 8;
+
+/// Mean SSIM between a region of the rendering and a region of the target, clamped to 0.0 to 1.0
+/// Windows are tiled without overlap and averaged by the mean pixel weight they cover, so fully ignored windows drop out
+/// On white backgrounds the target's negative is compared, since ink is dark there; target pixels on the background
+/// side of the threshold count as pure background, so noise in flat areas does not reward stray ink
+#[allow(clippy::too_many_arguments)]
+pub fn ssim(
+    rendered: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    rendered_origin: (u32, u32),
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    target_origin: (u32, u32),
+    width: u32,
+    height: u32,
+    pixel_weights: Option<&PixelWeights>,
+    background_threshold: u8,
+    white_background: bool,
+) -> f64 {
+    // Stabilizing constants from the original SSIM paper for 8-bit images
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let rendered_stride = rendered.width() as usize;
+    let target_stride = target_image.width() as usize;
+    let rendered_raw = rendered.as_raw();
+    let target_raw = target_image.as_raw();
+
+    let mut total = 0.0;
+    let mut total_weight = 0.0;
+
+    for window_y in (0..height).step_by(SSIM_WINDOW as usize) {
+        for window_x in (0..width).step_by(SSIM_WINDOW as usize) {
+            let window_width = SSIM_WINDOW.min(width - window_x);
+            let window_height = SSIM_WINDOW.min(height - window_y);
+
+            let (mut sum_a, mut sum_b, mut sum_aa, mut sum_bb, mut sum_ab) = (0u64, 0u64, 0u64, 0u64, 0u64);
+            let mut weight_sum = 0u64;
+            for y in window_y..window_y + window_height {
+                let rendered_row = (rendered_origin.1 + y) as usize * rendered_stride + rendered_origin.0 as usize;
+                let target_row = (target_origin.1 + y) as usize * target_stride + target_origin.0 as usize;
+                for x in window_x..window_x + window_width {
+                    let a = rendered_raw[rendered_row + x as usize] as u64;
+                    let b = ink_level(target_raw[target_row + x as usize], background_threshold, white_background) as u64;
+                    sum_a += a;
+                    sum_b += b;
+                    sum_aa += a * a;
+                    sum_bb += b * b;
+                    sum_ab += a * b;
+                    weight_sum += pixel_weights.map_or(1, |weights| weights.weight(target_origin.0 + x, target_origin.1 + y) as u64);
+                }
+            }
+
+            let count = (window_width * window_height) as f64;
+            let window_weight = match pixel_weights {
+                Some(_) => weight_sum as f64 / (count * PixelWeights::FULL as f64),
+                None => 1.0,
+            };
+            if window_weight == 0.0 {
+                continue;
+            }
+
+            let (mean_a, mean_b) = (sum_a as f64 / count, sum_b as f64 / count);
+            let variance_a = sum_aa as f64 / count - mean_a * mean_a;
+            let variance_b = sum_bb as f64 / count - mean_b * mean_b;
+            let covariance = sum_ab as f64 / count - mean_a * mean_b;
+            let similarity = ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (variance_a + variance_b + C2));
+
+            total += window_weight * similarity;
+            total_weight += window_weight;
+        }
+    }
+
+    if total_weight == 0.0 {
+        return 0.0;
+    }
+    (total / total_weight).clamp(0.0, 1.0)
+}
+
+/// Sobel gradient magnitude (scaled to 0-255) above which a pixel counts as an edge
+pub const EDGE_THRESHOLD: u8 = 32;
+
+/// Writes a binary edge map of the image into `edges` (255 on edges, 0 elsewhere), reallocating only on size changes
+/// Uses the L1 Sobel magnitude with clamped borders, so a full black-to-white step scores 127
+pub fn sobel_edges_into(image: &ImageBuffer<Luma<u8>, Vec<u8>>, edges: &mut ImageBuffer<Luma<u8>, Vec<u8>>) {
+    let (width, height) = image.dimensions();
+    if edges.dimensions() != (width, height) {
+        *edges = ImageBuffer::new(width, height);
+    }
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let raw = image.as_raw();
+    let stride = width as usize;
+    let at = |x: usize, y: usize| raw[y * stride + x] as i32;
+
+    for y in 0..height as usize {
+        let (up, down) = (y.saturating_sub(1), (y + 1).min(height as usize - 1));
+        for x in 0..width as usize {
+            let (left, right) = (x.saturating_sub(1), (x + 1).min(width as usize - 1));
+            let gx = at(right, up) + 2 * at(right, y) + at(right, down) - at(left, up) - 2 * at(left, y) - at(left, down);
+            let gy = at(left, down) + 2 * at(x, down) + at(right, down) - at(left, up) - 2 * at(x, up) - at(right, up);
+            let magnitude = (gx.abs() + gy.abs()) / 8;
+            edges.as_mut()[y * stride + x] = if magnitude > EDGE_THRESHOLD as i32 { 255 } else { 0 };
+        }
+    }
+}
+
+/// The target's Sobel edge map, used by the edge metric and the edge-agreement term
+/// Edges of the rendering that coincide with target edges score like matches; edges on flat target areas are penalized
+#[derive(Clone, Debug)]
+pub struct EdgeTarget {
+    target_edges: ImageBuffer<Luma<u8>, Vec<u8>>,
+    total_edge_pixels: f64,
+}
+
+impl EdgeTarget {
+    /// Precomputes the target's edge map and its (weighted) number of edge pixels
+    pub fn new(target_image: &ImageBuffer<Luma<u8>, Vec<u8>>, pixel_weights: Option<&PixelWeights>) -> Self {
+        let mut target_edges = ImageBuffer::new(0, 0);
+        sobel_edges_into(target_image, &mut target_edges);
+        let total_edge_pixels = count_non_background_pixels(&target_edges, 127, false, pixel_weights);
+        Self {
+            target_edges,
+            total_edge_pixels,
+        }
+    }
+
+    /// Returns the target's edge map
+    pub fn target_edges(&self) -> &ImageBuffer<Luma<u8>, Vec<u8>> {
+        &self.target_edges
+    }
+
+    /// Scores how well an edge map of a region agrees with the target's edges at `target_origin` (0.0 to 1.0)
+    /// `total_edge_pixels` is the number of target edge pixels the score is relative to, or None for the edges in
+    /// the region itself; a region without target edges scores 1.0 when the rendering adds none either
+    pub fn agreement(
+        &self,
+        rendered_edges: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        target_origin: (u32, u32),
+        pixel_weights: Option<&PixelWeights>,
+        total_edge_pixels: Option<f64>,
+    ) -> f64 {
+        let width = rendered_edges.width().min(self.target_edges.width().saturating_sub(target_origin.0));
+        let height = rendered_edges.height().min(self.target_edges.height().saturating_sub(target_origin.1));
+        let counts = count_overlap(rendered_edges, (0, 0), &self.target_edges, target_origin, width, height, 127, INTENSITY_TOLERANCE, pixel_weights);
+
+        let total = total_edge_pixels.unwrap_or(counts.relevant as f64);
+        if total == 0.0 {
+            return if counts.false_positives == 0 { 1.0 } else { 0.0 };
+        }
+        counts.fitness(total)
+    }
+}
+
+/// Scores renderings against one target with the selected metric, pixel weights and edge term
+/// Shared by every optimizer so whole grids and single cells are judged the same way
+#[derive(Clone, Debug)]
+pub struct Scorer<'a> {
+    target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>,
+    metric: FitnessMetric,
+    background_threshold: u8,
+    white_background: bool,
+    total_non_background_pixels: f64,
+    pixel_weights: Option<&'a PixelWeights>,
+    overlap: OverlapParams,
+    edge_weight: f64,
+    track_edges: bool,
+    edge_target: Option<EdgeTarget>,
+}
+
+impl<'a> Scorer<'a> {
+    /// Creates a scorer using the overlap metric with uniform weights and no edge term
+    pub fn new(target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>, white_background: bool) -> Self {
+        let background_threshold = background_threshold(white_background);
+        Self {
+            target_image,
+            metric: FitnessMetric::default(),
+            background_threshold,
+            white_background,
+            total_non_background_pixels: count_non_background_pixels(target_image, background_threshold, white_background, None),
+            pixel_weights: None,
+            overlap: OverlapParams::default(),
+            edge_weight: 0.0,
+            track_edges: false,
+            edge_target: None,
+        }
+    }
+
+    /// Selects the measure renderings are scored by
+    pub fn set_metric(&mut self, metric: FitnessMetric) {
+        self.metric = metric;
+        self.refresh_edge_target();
+    }
+
+    /// Scales each target pixel's contribution, 0 excluding it; weights of a different size than the target are ignored
+    pub fn set_pixel_weights(&mut self, pixel_weights: &'a PixelWeights) {
+        if pixel_weights.dimensions() != self.target_image.dimensions() {
+            return;
+        }
+        self.pixel_weights = Some(pixel_weights);
+        self.total_non_background_pixels =
+            count_non_background_pixels(self.target_image, self.background_threshold, self.white_background, self.pixel_weights);
+        self.refresh_edge_target();
+    }
+
+    /// Sets the match tolerance and false-positive penalty of the overlap metric
+    pub fn set_overlap_params(&mut self, overlap: OverlapParams) {
+        self.overlap = overlap;
+    }
+
+    /// Blends Sobel edge agreement into the metric with the given weight (0 disables it)
+    /// Scores become (metric + weight * edge agreement) / (1 + weight), so they stay between 0.0 and 1.0
+    pub fn set_edge_weight(&mut self, weight: f64) {
+        self.edge_weight = weight.max(0.0);
+        self.refresh_edge_target();
+    }
+
+    /// Keeps the target's edge map available to `edge_agreement` even when the metric and edge term don't use it
+    pub fn set_track_edges(&mut self, track_edges: bool) {
+        self.track_edges = track_edges;
+        self.refresh_edge_target();
+    }
+
+    /// Returns true if scores include edge agreement, through the edge metric or the edge term
+    /// Sobel edges reach across cell borders, so a cell's edge score also depends on its neighbours
+    pub fn scores_edges(&self) -> bool {
+        self.metric == FitnessMetric::Edge || self.edge_weight > 0.0
+    }
+
+    /// Builds the target's edge map when scoring or edge tracking needs it
+    fn refresh_edge_target(&mut self) {
+        let needed = self.scores_edges() || self.track_edges;
+        self.edge_target = needed.then(|| EdgeTarget::new(self.target_image, self.pixel_weights));
+    }
+
+    /// Returns the target image renderings are compared with
+    pub fn target_image(&self) -> &'a ImageBuffer<Luma<u8>, Vec<u8>> {
+        self.target_image
+    }
+
+    /// Returns the selected metric
+    pub fn metric(&self) -> FitnessMetric {
+        self.metric
+    }
+
+    /// Returns the match tolerance and false-positive penalty of the overlap metric
+    pub fn overlap_params(&self) -> OverlapParams {
+        self.overlap
+    }
+
+    /// Returns true if pixel weights scale the scores
+    pub fn weights_pixels(&self) -> bool {
+        self.pixel_weights.is_some()
+    }
+
+    /// Returns the pixel weights scaling the scores, if any
+    pub fn pixel_weights(&self) -> Option<&'a PixelWeights> {
+        self.pixel_weights
+    }
+
+    /// Returns the intensity threshold separating background from foreground pixels
+    pub fn background_threshold(&self) -> u8 {
+        self.background_threshold
+    }
+
+    /// Returns the number of foreground pixels in the target (their summed weights when pixel weights are set)
+    pub fn total_non_background_pixels(&self) -> f64 {
+        self.total_non_background_pixels
+    }
+
+    /// Scores a rendering of the whole grid (0.0 to 1.0); `edges` is scratch space for the rendering's edge map
+    pub fn score(&self, rendered: &ImageBuffer<Luma<u8>, Vec<u8>>, edges: &mut ImageBuffer<Luma<u8>, Vec<u8>>) -> f64 {
+        // Handle edge case of no non-background pixels to compare
+        if self.metric == FitnessMetric::Overlap && self.total_non_background_pixels == 0.0 {
+            return 0.0;
+        }
+
+        // Compare the overlapping area to handle any size mismatches
+        let width = rendered.width().min(self.target_image.width());
+        let height = rendered.height().min(self.target_image.height());
+
+        let edge_agreement = self.scores_edges().then(|| self.edge_agreement(rendered, edges));
+
+        let score = match self.metric {
+            FitnessMetric::Overlap => {
+                count_overlap(rendered, (0, 0), self.target_image, (0, 0), width, height, self.background_threshold, self.overlap.tolerance, self.pixel_weights)
+                    .penalized_fitness(self.total_non_background_pixels, self.overlap.false_positive_penalty)
+            }
+            FitnessMetric::Edge => edge_agreement.unwrap_or(0.0),
+            _ => self.compare_intensity(rendered, (0, 0), self.target_image, (0, 0), width, height),
+        };
+        self.blend(score, edge_agreement)
+    }
+
+    /// Scores how well the Sobel edges of a whole-grid rendering follow the target's (0.0 to 1.0)
+    /// `edges` is scratch space for the rendering's edge map; returns 0.0 unless edges are scored or tracked
+    pub fn edge_agreement(&self, rendered: &ImageBuffer<Luma<u8>, Vec<u8>>, edges: &mut ImageBuffer<Luma<u8>, Vec<u8>>) -> f64 {
+        let Some(edge_target) = &self.edge_target else { return 0.0 };
+        sobel_edges_into(rendered, edges);
+        edge_target.agreement(edges, (0, 0), self.pixel_weights, Some(edge_target.total_edge_pixels))
+    }
+
+    /// Fills `scores` with the unnormalized overlap score (matches minus penalized false positives) of every cell
+    /// of `cell_size` pixels in a whole-grid rendering, in row-major order
+    /// Whatever the metric, these show which cells reproduce the target worst
+    /// Works in one pass over pixel rows, as per-cell `count_overlap` calls would spend most of their time on setup
+    pub fn cell_scores(&self, rendered: &ImageBuffer<Luma<u8>, Vec<u8>>, cell_size: (u32, u32), scores: &mut Vec<f32>) {
+        let (cell_width, cell_height) = (cell_size.0.max(1) as usize, cell_size.1.max(1) as usize);
+        let columns = rendered.width() as usize / cell_width;
+        let width = (columns * cell_width).min(self.target_image.width() as usize);
+        let height = (rendered.height() as usize / cell_height * cell_height).min(self.target_image.height() as usize);
+        let (ascii_stride, target_stride) = (rendered.width() as usize, self.target_image.width() as usize);
+        let (background_threshold, tolerance) = (self.background_threshold, self.overlap.tolerance);
+        let penalty = self.overlap.false_positive_penalty;
+
+        scores.clear();
+        scores.resize(columns * (rendered.height() as usize / cell_height), 0.0);
+        // Tallies are summed down each pixel column over a row of cells, so the inner loop spans the full width
+        let unweighted = vec![1u8; width];
+        let mut column_matches = vec![0u32; width];
+        let mut column_false_positives = vec![0u32; width];
+        for y in 0..height {
+            let ascii_row = &rendered.as_raw()[y * ascii_stride..y * ascii_stride + width];
+            let target_row = &self.target_image.as_raw()[y * target_stride..y * target_stride + width];
+            let weight_row = self.pixel_weights.and_then(|weights| weights.row((0, 0), y, width)).unwrap_or(&unweighted);
+
+            let pixels = ascii_row.iter().zip(target_row).zip(weight_row);
+            for ((matches, false_positives), ((&ascii_pixel, &target_pixel), &weight)) in
+                column_matches.iter_mut().zip(column_false_positives.iter_mut()).zip(pixels)
+            {
+                let target_is_lit = target_pixel > background_threshold;
+                *matches += (target_is_lit & (ascii_pixel.abs_diff(target_pixel) < tolerance)) as u32 * weight as u32;
+                *false_positives += (!target_is_lit & (ascii_pixel > background_threshold)) as u32 * weight as u32;
+            }
+
+            if (y + 1) % cell_height == 0 || y + 1 == height {
+                let cells = column_matches.chunks(cell_width).zip(column_false_positives.chunks(cell_width));
+                for (score, (matches, false_positives)) in scores[y / cell_height * columns..].iter_mut().zip(cells) {
+                    let (matches, false_positives) = (matches.iter().sum::<u32>(), false_positives.iter().sum::<u32>());
+                    *score = (matches as f64 - false_positives as f64 * penalty) as f32;
+                }
+                column_matches.fill(0);
+                column_false_positives.fill(0);
+            }
+        }
+    }
+
+    /// Returns the best unnormalized overlap score each cell of a `grid` (columns, rows) can reach with any of the
+    /// given glyph renderings, in row-major order: the ceiling `cell_scores` can be measured against
+    pub fn cell_ceilings(&self, glyphs: &[ImageBuffer<Luma<u8>, Vec<u8>>], grid: (u32, u32)) -> Vec<f32> {
+        let Some(cell_size) = glyphs.first().map(|glyph| glyph.dimensions()) else { return vec![0.0; (grid.0 * grid.1) as usize] };
+        (0..grid.0 * grid.1)
+            .map(|cell| {
+                let origin = ((cell % grid.0) * cell_size.0, (cell / grid.0) * cell_size.1);
+                glyphs.iter().map(|glyph| self.cell_overlap(glyph, (0, 0), origin, cell_size)).fold(0.0, f64::max) as f32
+            })
+            .collect()
+    }
+
+    /// Rates each cell of a whole-grid rendering by its shortfall from its entry in `ceilings` (from `cell_ceilings`),
+    /// in row-major order: 1 where no character would do better, 0 for the cell furthest below its best
+    pub fn cell_quality(&self, rendered: &ImageBuffer<Luma<u8>, Vec<u8>>, cell_size: (u32, u32), ceilings: &[f32]) -> Vec<f32> {
+        let mut scores = Vec::new();
+        self.cell_scores(rendered, cell_size, &mut scores);
+        let shortfalls: Vec<f32> = scores.iter().zip(ceilings).map(|(&score, &ceiling)| (ceiling - score).max(0.0)).collect();
+        let worst = shortfalls.iter().copied().fold(0.0, f32::max);
+        shortfalls.iter().map(|&shortfall| if worst > 0.0 { 1.0 - shortfall / worst } else { 1.0 }).collect()
+    }
+
+    /// Unnormalized overlap score of one cell of a rendering against the target cell at `target_origin`
+    fn cell_overlap(
+        &self,
+        rendered: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        rendered_origin: (u32, u32),
+        target_origin: (u32, u32),
+        cell_size: (u32, u32),
+    ) -> f64 {
+        let width = cell_size.0
+            .min(rendered.width().saturating_sub(rendered_origin.0))
+            .min(self.target_image.width().saturating_sub(target_origin.0));
+        let height = cell_size.1
+            .min(rendered.height().saturating_sub(rendered_origin.1))
+            .min(self.target_image.height().saturating_sub(target_origin.1));
+        let counts = count_overlap(
+            rendered,
+            rendered_origin,
+            self.target_image,
+            target_origin,
+            width,
+            height,
+            self.background_threshold,
+            self.overlap.tolerance,
+            self.pixel_weights,
+        );
+        counts.matches as f64 - counts.false_positives as f64 * self.overlap.false_positive_penalty
+    }
+
+    /// Scores one glyph rendered on its own against the target cell at `target_origin` (0.0 to 1.0)
+    /// Scores are relative to the cell, so a cell without foreground prefers the blank character under overlap
+    pub fn score_cell(&self, glyph_image: &ImageBuffer<Luma<u8>, Vec<u8>>, target_origin: (u32, u32), is_blank: bool) -> f64 {
+        self.score_cell_against(glyph_image, self.target_image, target_origin, is_blank)
+    }
+
+    /// Scores a glyph like `score_cell`, but against `target_image`, an adjusted copy of the target with the same size
+    /// Edge agreement still follows the original target's edges
+    pub fn score_cell_against(
+        &self,
+        glyph_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        target_origin: (u32, u32),
+        is_blank: bool,
+    ) -> f64 {
+        let width = glyph_image.width().min(target_image.width().saturating_sub(target_origin.0));
+        let height = glyph_image.height().min(target_image.height().saturating_sub(target_origin.1));
+
+        let edge_agreement = self.edge_target.as_ref().filter(|_| self.scores_edges()).map(|edge_target| {
+            let mut glyph_edges = ImageBuffer::new(0, 0);
+            sobel_edges_into(glyph_image, &mut glyph_edges);
+            edge_target.agreement(&glyph_edges, target_origin, self.pixel_weights, None)
+        });
+
+        let score = match self.metric {
+            FitnessMetric::Overlap => {
+                let counts = count_overlap(
+                    glyph_image,
+                    (0, 0),
+                    target_image,
+                    target_origin,
+                    width,
+                    height,
+                    self.background_threshold,
+                    self.overlap.tolerance,
+                    self.pixel_weights,
+                );
+                if counts.relevant > 0 {
+                    counts.penalized_fitness(counts.relevant as f64, self.overlap.false_positive_penalty)
+                } else {
+                    // If no relevant pixels, prefer the lightest character (space in the default set)
+                    if is_blank { 1.0 } else { 0.0 }
+                }
+            }
+            FitnessMetric::Edge => edge_agreement.unwrap_or(0.0),
+            _ => self.compare_intensity(glyph_image, (0, 0), target_image, target_origin, width, height),
+        };
+        self.blend(score, edge_agreement)
+    }
+
+    /// Compares a region of the rendering with a target region using the intensity-based metric (MSE or SSIM)
+    fn compare_intensity(
+        &self,
+        rendered: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        rendered_origin: (u32, u32),
+        target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        target_origin: (u32, u32),
+        width: u32,
+        height: u32,
+    ) -> f64 {
+        let compare = match self.metric {
+            FitnessMetric::Ssim => ssim,
+            _ => mse_similarity,
+        };
+        compare(
+            rendered,
+            rendered_origin,
+            target_image,
+            target_origin,
+            width,
+            height,
+            self.pixel_weights,
+            self.background_threshold,
+            self.white_background,
+        )
+    }
+
+    /// Blends a metric score with the edge agreement when the edge term is enabled
+    fn blend(&self, score: f64, edge_agreement: Option<f64>) -> f64 {
+        match edge_agreement {
+            Some(agreement) if self.metric != FitnessMetric::Edge && self.edge_weight > 0.0 => {
+                (score + self.edge_weight * agreement) / (1.0 + self.edge_weight)
+            }
+            _ => score,
+        }
+    }
+}
+
+/// Full comparison of a rendered grid against its target, as reported by `asciigen score`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreReport {
+    /// The optimizers' fitness (0.0 to 1.0)
+    pub fitness: f64,
+    /// Match and false-positive tallies behind the fitness
+    pub counts: OverlapCounts,
+    /// Foreground pixels in the whole target
+    pub non_background_pixels: f64,
+    /// Mean absolute intensity difference over the compared area (0 to 255), independent of the background threshold
+    pub mean_absolute_error: f64,
+}
+
+/// Scores a rendering against the target exactly as the optimizers do, plus a threshold-free error measure
+/// The rendering uses the optimizers' convention of white glyphs on black, whatever the output background
+pub fn score_rendering(
+    rendered: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+    white_background: bool,
+    overlap: OverlapParams,
+) -> ScoreReport {
+    let background_threshold = background_threshold(white_background);
+    let non_background_pixels = count_non_background_pixels(target_image, background_threshold, white_background, None);
+    let width = rendered.width().min(target_image.width());
+    let height = rendered.height().min(target_image.height());
+
+    let counts = count_overlap(rendered, (0, 0), target_image, (0, 0), width, height, background_threshold, overlap.tolerance, None);
+    let fitness = if non_background_pixels == 0.0 { 0.0 } else { counts.penalized_fitness(non_background_pixels, overlap.false_positive_penalty) };
+
+    let total_error: u64 = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| rendered.get_pixel(x, y)[0].abs_diff(target_image.get_pixel(x, y)[0]) as u64)
+        .sum();
+    let area = (width as u64 * height as u64).max(1);
+
+    ScoreReport {
+        fitness,
+        counts,
+        non_background_pixels,
+        mean_absolute_error: total_error as f64 / area as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn random_image(width: u32, height: u32) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let mut rng = rand::thread_rng();
+        ImageBuffer::from_fn(width, height, |_, _| Luma([rng.gen()]))
+    }
+
+    #[test]
+    fn test_score_rendering_perfect_match() {
+        let target = ImageBuffer::from_fn(8, 4, |x, _| Luma([if x < 4 { 255u8 } else { 0 }]));
+        let report = score_rendering(&target, &target, false, OverlapParams::default());
+        assert_eq!(report.fitness, 1.0);
+        assert_eq!(report.counts.matches, 16);
+        assert_eq!(report.mean_absolute_error, 0.0);
+
+        let blank = ImageBuffer::new(8, 4);
+        let report = score_rendering(&blank, &target, false, OverlapParams::default());
+        assert_eq!(report.fitness, 0.0);
+        assert_eq!(report.mean_absolute_error, 127.5);
+    }
+
+    #[test]
+    fn test_count_overlap_matches_reference() {
+        let ascii = random_image(24, 18);
+        let target = random_image(30, 20);
+
+        let counts = count_overlap(&ascii, (0, 0), &target, (3, 1), 21, 17, 50, INTENSITY_TOLERANCE, None);
+        let reference = reference_overlap_score(&ascii, (0, 0), &target, (3, 1), 21, 17, 50, INTENSITY_TOLERANCE, None);
+
+        let fast = counts.matches as f64 - counts.false_positives as f64 / FALSE_POSITIVES_PER_MATCH as f64;
+        assert!((fast - reference).abs() < 1e-6, "fast {} vs reference {}", fast, reference);
+    }
+
+    #[test]
+    fn test_pixel_weights_match_reference() {
+        let ascii = random_image(24, 18);
+        let target = random_image(30, 20);
+        // Ignore a band of columns and a row crossing the compared region, on top of a center falloff
+        let mut weights = PixelWeights::center_weighted(30, 20, 4.0);
+        weights.combine(&PixelWeights::from_ignore_mask(&ImageBuffer::from_fn(30, 20, |x, y| {
+            Luma([if x % 7 == 0 || y == 5 { 255u8 } else { 0 }])
+        })));
+
+        let counts = count_overlap(&ascii, (0, 0), &target, (3, 1), 21, 17, 50, INTENSITY_TOLERANCE, Some(&weights));
+        let reference = reference_overlap_score(&ascii, (0, 0), &target, (3, 1), 21, 17, 50, INTENSITY_TOLERANCE, Some(&weights));
+        let fast = counts.matches as f64 - counts.false_positives as f64 / FALSE_POSITIVES_PER_MATCH as f64;
+        assert!((fast - reference).abs() < 1e-6, "fast {} vs reference {}", fast, reference);
+
+        // A fully ignored target has nothing left to score
+        let ignored = PixelWeights::from_ignore_mask(&ImageBuffer::from_pixel(30, 20, Luma([255u8])));
+        assert_eq!(ignored.ignored_count(), 600);
+        assert_eq!(count_overlap(&ascii, (0, 0), &target, (3, 1), 21, 17, 50, INTENSITY_TOLERANCE, Some(&ignored)), OverlapCounts::default());
+        assert_eq!(count_non_background_pixels(&target, 50, false, Some(&ignored)), 0.0);
+    }
+
+    #[test]
+    fn test_center_weighted_falloff() {
+        let weights = PixelWeights::center_weighted(40, 20, 3.0);
+        assert!(weights.weight(20, 10) >= PixelWeights::FULL - 2);
+        assert!(weights.weight(10, 10) < weights.weight(20, 10));
+        assert!(weights.weight(0, 0) < weights.weight(10, 10));
+        // Corners fall to roughly 1 / (1 + strength)
+        assert!((weights.weight(0, 0) as i32 - 64).abs() <= 3, "corner weight {}", weights.weight(0, 0));
+
+        assert_eq!(PixelWeights::center_weighted(40, 20, 0.0), PixelWeights::uniform(40, 20));
+    }
+
+    #[test]
+    fn test_ssim() {
+        let target = random_image(20, 12);
+        assert!((ssim(&target, (0, 0), &target, (0, 0), 20, 12, None, 0, false) - 1.0).abs() < 1e-9);
+
+        // Structure matters: a flat image of the same mean brightness scores far lower than the image itself
+        let flat = ImageBuffer::from_pixel(20, 12, Luma([127u8]));
+        assert!(ssim(&flat, (0, 0), &target, (0, 0), 20, 12, None, 0, false) < 0.1);
+
+        // On a white background the rendering is compared with the target's negative
+        let negative = ImageBuffer::from_fn(20, 12, |x, y| Luma([255 - target.get_pixel(x, y)[0]]));
+        assert!((ssim(&negative, (0, 0), &target, (0, 0), 20, 12, None, 255, true) - 1.0).abs() < 1e-9);
+
+        // Dim noise below the background threshold is matched by an empty rendering
+        let noise = ImageBuffer::from_fn(20, 12, |x, y| Luma([((x * 7 + y * 3) % 9) as u8]));
+        assert_eq!(ssim(&ImageBuffer::new(20, 12), (0, 0), &noise, (0, 0), 20, 12, None, 50, false), 1.0);
+
+        // Windows that are fully ignored drop out of the average
+        let weights = PixelWeights::from_ignore_mask(&ImageBuffer::from_fn(20, 12, |x, _| Luma([if x >= 8 { 255u8 } else { 0 }])));
+        let mut half = target.clone();
+        for (x, _, pixel) in half.enumerate_pixels_mut() {
+            if x >= 8 {
+                pixel[0] = 0;
+            }
+        }
+        assert!((ssim(&half, (0, 0), &target, (0, 0), 20, 12, Some(&weights), 0, false) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_edge_term() {
+        // A bright square on black: its outline is the only edge
+        let square = ImageBuffer::from_fn(16, 16, |x, y| Luma([if (4..12).contains(&x) && (4..12).contains(&y) { 255u8 } else { 0 }]));
+        let mut edges = ImageBuffer::new(0, 0);
+        sobel_edges_into(&square, &mut edges);
+        assert_eq!(edges.get_pixel(4, 8)[0], 255);
+        assert_eq!(edges.get_pixel(8, 8)[0], 0);
+        assert_eq!(edges.get_pixel(0, 0)[0], 0);
+
+        let edge_target = EdgeTarget::new(&square, None);
+        assert_eq!(edge_target.agreement(&edges, (0, 0), None, None), 1.0);
+        let flat = ImageBuffer::new(16, 16);
+        assert_eq!(edge_target.agreement(&flat, (0, 0), None, None), 0.0);
+
+        // A blurred copy misses most pixel values but keeps the outline in place, which the edge term credits
+        let blurred = image::imageops::blur(&square, 2.0);
+        let mut scorer = Scorer::new(&square, false);
+        let mut scratch = ImageBuffer::new(0, 0);
+        let overlap_only = scorer.score(&blurred, &mut scratch);
+        scorer.set_edge_weight(1.0);
+        assert_eq!(scorer.score(&square, &mut scratch), 1.0);
+        assert_eq!(scorer.score(&flat, &mut scratch), 0.0);
+        assert!(scorer.score(&blurred, &mut scratch) > overlap_only);
+
+        // Tracking edges measures agreement without changing the score
+        let mut scorer = Scorer::new(&square, false);
+        scorer.set_track_edges(true);
+        assert_eq!(scorer.score(&blurred, &mut scratch), overlap_only);
+        assert_eq!(scorer.edge_agreement(&square, &mut scratch), 1.0);
+        assert_eq!(scorer.edge_agreement(&flat, &mut scratch), 0.0);
+    }
+
+    #[test]
+    fn test_cell_scores() {
+        // Target lights the left cell only; the rendering lights the right cell only
+        let target = ImageBuffer::from_fn(8, 4, |x, _| Luma([if x < 4 { 255u8 } else { 0 }]));
+        let rendered = ImageBuffer::from_fn(8, 4, |x, _| Luma([if x >= 4 { 255u8 } else { 0 }]));
+        let mut scorer = Scorer::new(&target, false);
+        scorer.set_overlap_params(OverlapParams { false_positive_penalty: 0.5, ..OverlapParams::default() });
+
+        let mut scores = Vec::new();
+        scorer.cell_scores(&rendered, (4, 4), &mut scores);
+        assert_eq!(scores, vec![0.0, -8.0]);
+        scorer.cell_scores(&target, (4, 4), &mut scores);
+        assert_eq!(scores, vec![16.0, 0.0]);
+
+        // The best glyph per cell sets its ceiling: full for the lit cell, blank for the dark one
+        let glyphs = [ImageBuffer::from_pixel(4, 4, Luma([255u8])), ImageBuffer::new(4, 4)];
+        assert_eq!(scorer.cell_ceilings(&glyphs, (2, 1)), vec![16.0, 0.0]);
+    }
+
+    #[test]
+    fn test_scorer_metrics() {
+        let target = ImageBuffer::from_fn(16, 8, |x, _| Luma([if x < 8 { 255u8 } else { 0 }]));
+        let blank = ImageBuffer::new(16, 8);
+        let mut scratch = ImageBuffer::new(0, 0);
+        let mut scorer = Scorer::new(&target, false);
+
+        for metric in [FitnessMetric::Overlap, FitnessMetric::Mse, FitnessMetric::Ssim, FitnessMetric::Edge] {
+            assert_eq!(metric.to_string().parse::<FitnessMetric>(), Ok(metric));
+            scorer.set_metric(metric);
+            assert!((scorer.score(&target, &mut scratch) - 1.0).abs() < 1e-9, "{} of a perfect rendering", metric);
+            assert!(scorer.score(&blank, &mut scratch) < 1.0, "{} of a blank rendering", metric);
+        }
+        assert!("psnr".parse::<FitnessMetric>().is_err());
+
+        // Half the pixels are fully wrong: the RMSE is 255 / sqrt(2)
+        scorer.set_metric(FitnessMetric::Mse);
+        assert!((scorer.score(&blank, &mut scratch) - (1.0 - 1.0 / 2f64.sqrt())).abs() < 1e-9);
+
+        // Cells are scored on their own region: the blank glyph wins an empty cell
+        assert_eq!(scorer.score_cell(&ImageBuffer::new(8, 8), (8, 0), true), 1.0);
+    }
+
+    #[test]
+    fn test_overlap_counts_fitness() {
+        let counts = OverlapCounts { matches: 10, false_positives: 200, relevant: 20 };
+        // 10 matches minus one match worth of penalties over 20 non-background pixels
+        assert!((counts.fitness(20.0) - 0.45).abs() < 1e-12);
+
+        let penalized = OverlapCounts { matches: 0, false_positives: 50, relevant: 0 };
+        assert_eq!(penalized.fitness(10.0), 0.0);
+
+        // The default penalty reproduces the integer fitness; a harsher one costs 0.1 per false positive
+        let default_penalty = OverlapParams::default().false_positive_penalty;
+        assert!((counts.penalized_fitness(20.0, default_penalty) - counts.fitness(20.0)).abs() < 1e-12);
+        let sparse = OverlapCounts { matches: 10, false_positives: 20, relevant: 20 };
+        assert!((sparse.penalized_fitness(20.0, 0.1) - 0.4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tolerance_controls_matches() {
+        let target = ImageBuffer::from_pixel(4, 4, Luma([200u8]));
+        let rendered = ImageBuffer::from_pixel(4, 4, Luma([160u8]));
+        let mut scorer = Scorer::new(&target, false);
+        let mut scratch = ImageBuffer::new(0, 0);
+        assert_eq!(scorer.score(&rendered, &mut scratch), 0.0);
+
+        scorer.set_overlap_params(OverlapParams { tolerance: 41, ..OverlapParams::default() });
+        assert_eq!(scorer.score(&rendered, &mut scratch), 1.0);
+        assert_eq!(reference_overlap_score(&rendered, (0, 0), &target, (0, 0), 4, 4, 50, 41, None), 16.0);
+    }
+
+    #[test]
+    fn test_count_non_background_pixels() {
+        let mut img = ImageBuffer::new(2, 2);
+        img.put_pixel(0, 0, Luma([10u8]));
+        img.put_pixel(1, 0, Luma([100u8]));
+        img.put_pixel(0, 1, Luma([220u8]));
+        img.put_pixel(1, 1, Luma([250u8]));
+
+        assert_eq!(count_non_background_pixels(&img, 50, false, None), 3.0);
+        assert_eq!(count_non_background_pixels(&img, 200, true, None), 2.0);
+    }
+}
@@ -1,70 +1,430 @@
 use crate::ascii_generator::AsciiGenerator;
+use crate::charset::Charset;
+use crate::fitness::{FitnessMetric, OverlapParams, PixelWeights, Scorer};
+use crate::progress::{FitnessStats, ProgressEvent, ProgressKind, ProgressSink, Tuning};
+use crate::template::LockMask;
 use image::{ImageBuffer, Luma};
-use rand::{Rng, thread_rng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Strategy used to merge offspring back into the population each generation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReplacementStrategy {
+    /// Rebuild the whole population from elites plus offspring every generation
+    #[default]
+    Generational,
+    /// Deterministic crowding: each offspring replaces the most similar individual if it is at least as fit
+    Crowding,
+}
+
+impl std::str::FromStr for ReplacementStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "generational" => Ok(Self::Generational),
+            "crowding" => Ok(Self::Crowding),
+            _ => Err(format!("unknown replacement strategy '{}' (expected 'generational' or 'crowding')", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for ReplacementStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Generational => "generational",
+            Self::Crowding => "crowding",
+        })
+    }
+}
+
+/// Number of consecutive cells exchanged together by block crossover
+pub const CROSSOVER_BLOCK_LEN: usize = 8;
+
+/// How two parents' genomes are combined into a pair of children
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CrossoverOperator {
+    /// Every cell is swapped independently with the crossover rate
+    #[default]
+    Uniform,
+    /// The cells between two random cut points are swapped, with the crossover rate as the chance of crossing at all
+    TwoPoint,
+    /// Runs of `CROSSOVER_BLOCK_LEN` consecutive cells are swapped independently with the crossover rate,
+    /// so good patches within a row survive together
+    Block,
+    /// A random rectangular sub-grid is swapped, with the crossover rate as the chance of crossing at all,
+    /// so patches spanning several rows survive together
+    Rectangle,
+    /// Whole rows are swapped independently with the crossover rate, preserving horizontal structure
+    Rows,
+    /// Whole columns are swapped independently with the crossover rate, preserving vertical structure
+    Columns,
+}
+
+impl std::str::FromStr for CrossoverOperator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "uniform" => Ok(Self::Uniform),
+            "two-point" => Ok(Self::TwoPoint),
+            "block" => Ok(Self::Block),
+            "rectangle" => Ok(Self::Rectangle),
+            "rows" => Ok(Self::Rows),
+            "columns" => Ok(Self::Columns),
+            _ => Err(format!(
+                "unknown crossover operator '{}' (expected 'uniform', 'two-point', 'block', 'rectangle', 'rows' or 'columns')",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for CrossoverOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Uniform => "uniform",
+            Self::TwoPoint => "two-point",
+            Self::Block => "block",
+            Self::Rectangle => "rectangle",
+            Self::Rows => "rows",
+            Self::Columns => "columns",
+        })
+    }
+}
+
+/// Side of the largest square block copied by block-copy mutation
+pub const MUTATION_BLOCK_SIZE: usize = 3;
+
+/// What happens at each cell picked for mutation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MutationOperator {
+    /// The cell is replaced by a random character
+    #[default]
+    Point,
+    /// The cell is swapped with another random cell
+    Swap,
+    /// The cell's row is rotated one position left or right
+    Shift,
+    /// A block of up to `MUTATION_BLOCK_SIZE` square starting at the cell is copied to a random location
+    BlockCopy,
+    /// Each mutation picks one of the other operators at random
+    Mixed,
+}
+
+impl std::str::FromStr for MutationOperator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "point" => Ok(Self::Point),
+            "swap" => Ok(Self::Swap),
+            "shift" => Ok(Self::Shift),
+            "block-copy" => Ok(Self::BlockCopy),
+            "mixed" => Ok(Self::Mixed),
+            _ => Err(format!("unknown mutation operator '{}' (expected 'point', 'swap', 'shift', 'block-copy' or 'mixed')", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for MutationOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Point => "point",
+            Self::Swap => "swap",
+            Self::Shift => "shift",
+            Self::BlockCopy => "block-copy",
+            Self::Mixed => "mixed",
+        })
+    }
+}
+
+/// How parents are chosen from the population for breeding
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// The fittest of a few randomly drawn individuals (three by default)
+    #[default]
+    Tournament,
+    /// Fitness-proportionate (roulette wheel) selection
+    Roulette,
+    /// Linear ranking: probability proportional to rank, independent of how far apart the fitness values are
+    Rank,
+    /// Stochastic universal sampling: fitness-proportionate with evenly spaced pointers, so each individual's
+    /// number of selections stays close to its expected share
+    Sus,
+}
+
+impl std::str::FromStr for SelectionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tournament" => Ok(Self::Tournament),
+            "roulette" => Ok(Self::Roulette),
+            "rank" => Ok(Self::Rank),
+            "sus" => Ok(Self::Sus),
+            _ => Err(format!("unknown selection strategy '{}' (expected 'tournament', 'roulette', 'rank' or 'sus')", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for SelectionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Tournament => "tournament",
+            Self::Roulette => "roulette",
+            Self::Rank => "rank",
+            Self::Sus => "sus",
+        })
+    }
+}
+
+impl SelectionStrategy {
+    /// Chooses `count` parents from the pool, with repetition; `tournament_size` only applies to tournament selection
+    /// SUS results are shuffled so consecutive parents pair up at random
+    fn select<'p>(self, pool: &[&'p Individual], count: usize, tournament_size: usize, rng: &mut StdRng) -> Vec<&'p Individual> {
+        match self {
+            Self::Tournament => (0..count).map(|_| tournament(pool, tournament_size, rng)).collect(),
+            Self::Roulette | Self::Rank => {
+                let cumulative = self.cumulative_weights(pool);
+                let total = cumulative[cumulative.len() - 1];
+                (0..count).map(|_| pool[spin(&cumulative, rng.gen::<f64>() * total)]).collect()
+            }
+            Self::Sus => {
+                let cumulative = self.cumulative_weights(pool);
+                let step = cumulative[cumulative.len() - 1] / count.max(1) as f64;
+                let start = rng.gen::<f64>() * step;
+                let mut parents: Vec<&Individual> = (0..count).map(|i| pool[spin(&cumulative, start + i as f64 * step)]).collect();
+                parents.shuffle(rng);
+                parents
+            }
+        }
+    }
+
+    /// Returns the running sums of the pool's selection weights: fitness, or linear rank for rank selection
+    /// Falls back to uniform weights when every fitness is zero
+    fn cumulative_weights(self, pool: &[&Individual]) -> Vec<f64> {
+        let mut weights: Vec<f64> = if self == Self::Rank {
+            let mut order: Vec<usize> = (0..pool.len()).collect();
+            order.sort_by(|&a, &b| pool[a].fitness.partial_cmp(&pool[b].fitness).unwrap_or(Ordering::Equal));
+            let mut ranks = vec![0.0; pool.len()];
+            for (rank, i) in order.into_iter().enumerate() {
+                ranks[i] = (rank + 1) as f64;
+            }
+            ranks
+        } else {
+            pool.iter().map(|individual| individual.fitness.max(0.0)).collect()
+        };
+        if weights.iter().all(|&weight| weight == 0.0) {
+            weights.fill(1.0);
+        }
+
+        let mut total = 0.0;
+        for weight in &mut weights {
+            total += *weight;
+            *weight = total;
+        }
+        weights
+    }
+}
+
+/// Returns the index of the first running sum beyond the pointer, clamped to the last individual
+fn spin(cumulative: &[f64], pointer: f64) -> usize {
+    cumulative.partition_point(|&sum| sum <= pointer).min(cumulative.len() - 1)
+}
+
+/// Default number of individuals drawn for each tournament
+pub const DEFAULT_TOURNAMENT_SIZE: usize = 3;
+
+/// Most individuals each one is compared with when estimating niche counts for fitness sharing
+pub const SHARING_SAMPLE_SIZE: usize = 50;
+
+/// Most individuals compared pairwise when measuring population diversity
+pub const DIVERSITY_SAMPLE_SIZE: usize = 32;
+
+/// Returns the number of logical cores, the thread count used when 0 threads are requested
+pub fn available_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// Estimates each individual's niche count for fitness sharing: 1 for itself plus, for every other individual within
+/// `radius` differing cells, 1 - distance / radius (the triangular sharing function)
+/// Only the `sample` indices are compared against, scaled up to the whole population, so large populations stay cheap
+fn niche_counts(population: &[Individual], sample: &[usize], radius: f64) -> Vec<f64> {
+    population
+        .par_iter()
+        .enumerate()
+        .map(|(i, individual)| {
+            let others: Vec<usize> = sample.iter().copied().filter(|&j| j != i).collect();
+            let shared: f64 = others
+                .iter()
+                .map(|&j| individual.hamming_distance(&population[j]) as f64)
+                .filter(|&distance| distance < radius)
+                .map(|distance| 1.0 - distance / radius)
+                .sum();
+            1.0 + shared * (population.len() - 1) as f64 / others.len().max(1) as f64
+        })
+        .collect()
+}
+
+/// Performs tournament selection among the given candidates: the fittest of `tournament_size` random draws
+fn tournament<'p>(pool: &[&'p Individual], tournament_size: usize, rng: &mut StdRng) -> &'p Individual {
+    let mut best_individual = pool[rng.gen_range(0..pool.len())];
+
+    for _ in 1..tournament_size {
+        let candidate = pool[rng.gen_range(0..pool.len())];
+        if candidate.fitness > best_individual.fitness {
+            best_individual = candidate;
+        }
+    }
 
-/// Limited character set for ASCII art generation
-pub const ALLOWED_CHARS: &[u8] = b" <>,./?\\|[]{}-_=+AbCDdoOTuUVXZ`~;:'\"!@#$%^&*()8";
+    best_individual
+}
+
+/// Objective maximized alongside fitness by multi-objective (NSGA-II) evolution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParetoObjective {
+    /// Ink economy: the fraction of cells left blank, trading likeness for sparser art
+    Ink,
+    /// Edge fidelity: agreement of the rendering's Sobel edges with the target's
+    Edge,
+}
+
+impl std::str::FromStr for ParetoObjective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ink" => Ok(Self::Ink),
+            "edge" => Ok(Self::Edge),
+            _ => Err(format!("unknown objective '{}' (expected 'ink' or 'edge')", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for ParetoObjective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ink => "ink",
+            Self::Edge => "edge",
+        })
+    }
+}
+
+/// Why the last `evolve` call returned
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StopReason {
+    /// The requested number of generations ran
+    #[default]
+    GenerationLimit,
+    /// The best fitness gained no more than the stall epsilon over the stall window
+    Stalled,
+    /// The best fitness reached the target fitness
+    TargetReached,
+    /// The progress sink asked to stop or was cancelled
+    Interrupted,
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::GenerationLimit => "generation limit",
+            Self::Stalled => "stalled",
+            Self::TargetReached => "target reached",
+            Self::Interrupted => "interrupted",
+        })
+    }
+}
+
+/// One individual of a `Checkpoint`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedIndividual {
+    /// The art's characters in row-major order, without line breaks
+    pub chars: String,
+    pub fitness: f64,
+    pub age: u32,
+    pub island: usize,
+}
+
+/// Population and evolution state saved by `GeneticAlgorithm::checkpoint`, so a run can be continued with `resume`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub width: u32,
+    pub height: u32,
+    /// Generations completed before the checkpoint
+    pub generations_run: u32,
+    /// The population, best first
+    pub population: Vec<SavedIndividual>,
+    /// The best individual archived by restarts after stalling
+    pub best_ever: Option<SavedIndividual>,
+    pub restarts: u32,
+    pub mutation_rate: f64,
+    pub crossover_rate: f64,
+    pub elite_size: usize,
+    /// Seeds the random number generator of the resumed run
+    pub seed: u64,
+}
 
 /// Represents an individual in the genetic algorithm population
 #[derive(Clone, Debug)]
 pub struct Individual {
+    /// Character codes (indices into the active `Charset`) in row-major order
     pub chars: Vec<u8>,
+    /// Fitness from the most recent evaluation (0.0 to 1.0)
     pub fitness: f64,
+    /// Number of generations this individual's genetic material has been evolving (used by ALPS)
+    pub age: u32,
+    /// Sub-population the individual belongs to under the island model
+    pub island: usize,
+    /// Second objective from the most recent evaluation under multi-objective evolution (0.0 to 1.0)
+    pub objective: f64,
+    /// Per-cell error from the most recent evaluation: how far each cell's score falls short of the best any
+    /// character reaches there (empty unless error-targeted mutation is enabled)
+    pub cell_errors: Vec<f32>,
 }
 
 impl Individual {
     /// Creates a new individual with random ASCII characters (for tests)
     #[cfg(test)]
     pub fn new_random(size: usize) -> Self {
-        Self::new_random_with_background_prob(size, 0.0)
+        Self::new_random_with_background_prob(size, 0.0, &Charset::default(), &mut rand::thread_rng())
     }
 
-    /// Creates a new individual with random ASCII characters using background probability
-    pub fn new_random_with_background_prob(size: usize, background_prob: f64) -> Self {
-        let mut rng = thread_rng();
+    /// Creates a new individual with random characters from the set using background probability
+    pub fn new_random_with_background_prob<R: Rng + ?Sized>(size: usize, background_prob: f64, charset: &Charset, rng: &mut R) -> Self {
         let chars: Vec<u8> = (0..size)
-            .map(|_| {
-                if rng.gen::<f64>() < background_prob {
-                    b' ' // Space character for background
-                } else {
-                    // Choose from non-space characters
-                    let non_space_chars: Vec<u8> = ALLOWED_CHARS.iter()
-                        .filter(|&&c| c != b' ')
-                        .copied()
-                        .collect();
-                    non_space_chars[rng.gen_range(0..non_space_chars.len())]
-                }
-            })
+            .map(|_| charset.random_with_background_prob(background_prob, rng))
             .collect();
 
         Self {
             chars,
             fitness: 0.0,
+            age: 0,
+            island: 0,
+            objective: 0.0,
+            cell_errors: Vec::new(),
         }
     }
 
     /// Creates a new individual with a specified initialization character
     /// 95% of characters will be the init_char, 5% will be random
-    pub fn new_with_init_char(size: usize, init_char: char) -> Self {
-        let mut rng = thread_rng();
-        let init_byte = init_char as u8;
-
+    pub fn new_with_init_char<R: Rng + ?Sized>(size: usize, init_char: char, charset: &Charset, rng: &mut R) -> Self {
         // Ensure the init_char is in the allowed character set
-        let init_byte = if ALLOWED_CHARS.contains(&init_byte) {
-            init_byte
-        } else {
-            b' ' // Default to space if invalid character
-        };
+        let init_code = charset.index_of(init_char).unwrap_or(0); // Default to the first character if invalid
 
         let chars: Vec<u8> = (0..size)
             .map(|_| {
                 if rng.gen::<f64>() < 0.05 { // 5% chance for random character
-                    ALLOWED_CHARS[rng.gen_range(0..ALLOWED_CHARS.len())]
+                    charset.random_code(rng)
                 } else {
-                    init_byte
+                    init_code
                 }
             })
             .collect();
@@ -72,6 +432,10 @@ impl Individual {
         Self {
             chars,
             fitness: 0.0,
+            age: 0,
+            island: 0,
+            objective: 0.0,
+            cell_errors: Vec::new(),
         }
     }
 
@@ -80,50 +444,407 @@ impl Individual {
         Self {
             chars,
             fitness: 0.0,
+            age: 0,
+            island: 0,
+            objective: 0.0,
+            cell_errors: Vec::new(),
+        }
+    }
+
+    /// Parses text art into an individual, returning it with the art's width and height in characters
+    /// Rows of spaces are kept as part of the grid; trailing empty lines are dropped and short lines are padded with the blank character
+    pub fn from_art(text: &str, charset: &Charset) -> Result<(Self, u32, u32), String> {
+        let mut lines: Vec<&str> = text.lines().collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        if width == 0 {
+            return Err("the art is empty".to_string());
         }
+
+        let mut chars = Vec::with_capacity(width * lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            for ch in line.chars() {
+                let code = charset
+                    .index_of(ch)
+                    .ok_or_else(|| format!("character {:?} on line {} is not in the character set", ch, row + 1))?;
+                chars.push(code);
+            }
+            let padding = width - line.chars().count();
+            if padding > 0 {
+                let blank = charset
+                    .blank_code()
+                    .ok_or_else(|| format!("line {} is short and the character set has no space to pad it", row + 1))?;
+                chars.extend(std::iter::repeat_n(blank, padding));
+            }
+        }
+
+        Ok((Self::new(chars), width as u32, lines.len() as u32))
     }
 
-    /// Performs uniform crossover with another individual
-    pub fn crossover(&self, other: &Individual, crossover_rate: f64) -> (Individual, Individual) {
-        let mut rng = thread_rng();
+    /// Performs crossover with another individual using the given operator
+    /// Each child starts as a copy of one parent and takes the exchanged cells from the other, along with their cell errors;
+    /// `width` is the grid width in characters, which the two-dimensional operators need
+    pub fn crossover<R: Rng + ?Sized>(&self, other: &Individual, operator: CrossoverOperator, crossover_rate: f64, width: usize, rng: &mut R) -> (Individual, Individual) {
         let mut child1_chars = self.chars.clone();
         let mut child2_chars = other.chars.clone();
+        let len = self.chars.len().min(other.chars.len());
+        // A cell's error depends on little but its own character, so it can follow the character into the child
+        let inherit_errors = self.cell_errors.len() >= len && other.cell_errors.len() >= len;
+        let mut child1_errors = if inherit_errors { self.cell_errors.clone() } else { Vec::new() };
+        let mut child2_errors = if inherit_errors { other.cell_errors.clone() } else { Vec::new() };
+        let width = width.clamp(1, len.max(1));
+        let height = len / width;
+        let mut swap = |cells: std::ops::Range<usize>| {
+            child1_chars[cells.clone()].copy_from_slice(&other.chars[cells.clone()]);
+            child2_chars[cells.clone()].copy_from_slice(&self.chars[cells.clone()]);
+            if inherit_errors {
+                child1_errors[cells.clone()].copy_from_slice(&other.cell_errors[cells.clone()]);
+                child2_errors[cells.clone()].copy_from_slice(&self.cell_errors[cells]);
+            }
+        };
 
-        for i in 0..self.chars.len().min(other.chars.len()) {
-            if rng.gen::<f64>() < crossover_rate {
-                child1_chars[i] = other.chars[i];
-                child2_chars[i] = self.chars[i];
+        match operator {
+            CrossoverOperator::Uniform => {
+                for i in 0..len {
+                    if rng.gen::<f64>() < crossover_rate {
+                        swap(i..i + 1);
+                    }
+                }
+            }
+            CrossoverOperator::TwoPoint => {
+                if rng.gen::<f64>() < crossover_rate {
+                    let (a, b) = (rng.gen_range(0..=len), rng.gen_range(0..=len));
+                    swap(a.min(b)..a.max(b));
+                }
+            }
+            CrossoverOperator::Block => {
+                for start in (0..len).step_by(CROSSOVER_BLOCK_LEN) {
+                    if rng.gen::<f64>() < crossover_rate {
+                        swap(start..(start + CROSSOVER_BLOCK_LEN).min(len));
+                    }
+                }
+            }
+            CrossoverOperator::Rectangle => {
+                if rng.gen::<f64>() < crossover_rate {
+                    let (x1, x2) = (rng.gen_range(0..=width), rng.gen_range(0..=width));
+                    let (y1, y2) = (rng.gen_range(0..=height), rng.gen_range(0..=height));
+                    for row in y1.min(y2)..y1.max(y2) {
+                        swap(row * width + x1.min(x2)..row * width + x1.max(x2));
+                    }
+                }
+            }
+            CrossoverOperator::Rows => {
+                for row in 0..height {
+                    if rng.gen::<f64>() < crossover_rate {
+                        swap(row * width..(row + 1) * width);
+                    }
+                }
+            }
+            CrossoverOperator::Columns => {
+                for col in 0..width {
+                    if rng.gen::<f64>() < crossover_rate {
+                        for row in 0..height {
+                            swap(row * width + col..row * width + col + 1);
+                        }
+                    }
+                }
             }
         }
 
-        (Individual::new(child1_chars), Individual::new(child2_chars))
+        (
+            Individual { cell_errors: child1_errors, ..Individual::new(child1_chars) },
+            Individual { cell_errors: child2_errors, ..Individual::new(child2_chars) },
+        )
+    }
+
+    /// Counts the positions at which two individuals hold different characters
+    pub fn hamming_distance(&self, other: &Individual) -> usize {
+        self.chars.iter()
+            .zip(other.chars.iter())
+            .filter(|(a, b)| a != b)
+            .count()
     }
 
     /// Performs mutation on the individual (for tests)
     #[cfg(test)]
     pub fn mutate(&mut self, mutation_rate: f64) {
-        self.mutate_with_background_prob(mutation_rate, 0.0);
+        self.mutate_with_background_prob(mutation_rate, 0.0, &Charset::default(), &mut rand::thread_rng());
     }
 
     /// Performs mutation on the individual using background probability
-    pub fn mutate_with_background_prob(&mut self, mutation_rate: f64, background_prob: f64) {
-        let mut rng = thread_rng();
-
+    pub fn mutate_with_background_prob<R: Rng + ?Sized>(&mut self, mutation_rate: f64, background_prob: f64, charset: &Charset, rng: &mut R) {
         for char in &mut self.chars {
             if rng.gen::<f64>() < mutation_rate {
-                if rng.gen::<f64>() < background_prob {
-                    *char = b' '; // Space character for background
-                } else {
-                    // Choose from non-space characters
-                    let non_space_chars: Vec<u8> = ALLOWED_CHARS.iter()
-                        .filter(|&&c| c != b' ')
-                        .copied()
-                        .collect();
-                    *char = non_space_chars[rng.gen_range(0..non_space_chars.len())];
+                *char = charset.random_with_background_prob(background_prob, rng);
+            }
+        }
+    }
+
+    /// Performs mutation biased toward the cells with the highest error from the last evaluation
+    /// Each cell's rate is scaled by its error relative to the mean, blended with 1 by `bias` (0 to 1), so the expected
+    /// number of mutations is unchanged; without cell errors this is plain mutation
+    pub fn mutate_targeted<R: Rng + ?Sized>(&mut self, mutation_rate: f64, bias: f64, background_prob: f64, charset: &Charset, rng: &mut R) {
+        for cell in self.mutation_sites(mutation_rate, bias, rng) {
+            self.chars[cell] = charset.random_with_background_prob(background_prob, rng);
+        }
+    }
+
+    /// Picks the cells to mutate: each with the mutation rate, scaled toward high cell errors by `bias` as in `mutate_targeted`
+    fn mutation_sites<R: Rng + ?Sized>(&self, mutation_rate: f64, bias: f64, rng: &mut R) -> Vec<usize> {
+        let mean_error = self.cell_errors.iter().map(|&error| error as f64).sum::<f64>() / self.chars.len().max(1) as f64;
+        if bias <= 0.0 || self.cell_errors.len() != self.chars.len() || mean_error <= 0.0 {
+            return (0..self.chars.len()).filter(|_| rng.gen::<f64>() < mutation_rate).collect();
+        }
+
+        (0..self.chars.len())
+            .filter(|&cell| rng.gen::<f64>() < mutation_rate * (1.0 - bias + bias * self.cell_errors[cell] as f64 / mean_error))
+            .collect()
+    }
+
+    /// Swaps the characters of two cells
+    pub fn swap_cells(&mut self, a: usize, b: usize) {
+        self.chars.swap(a, b);
+    }
+
+    /// Rotates the row containing `cell` of a grid `width` characters wide by one position, to the right or left
+    pub fn shift_row(&mut self, cell: usize, width: usize, right: bool) {
+        let width = width.max(1);
+        let start = cell / width * width;
+        let end = (start + width).min(self.chars.len());
+        let row = &mut self.chars[start..end];
+        if right {
+            row.rotate_right(1);
+        } else {
+            row.rotate_left(1);
+        }
+    }
+
+    /// Copies the block of `size` (columns, rows) starting at cell `from` so it starts at cell `to`,
+    /// on a grid `width` characters wide; the block is clipped wherever it would leave the grid
+    pub fn copy_block(&mut self, from: usize, to: usize, size: (usize, usize), width: usize) {
+        let width = width.max(1);
+        let height = self.chars.len() / width;
+        let (from_x, from_y, to_x, to_y) = (from % width, from / width, to % width, to / width);
+        let columns = size.0.min(width - from_x).min(width - to_x);
+        let rows = size.1.min(height.saturating_sub(from_y)).min(height.saturating_sub(to_y));
+        // Rows are copied through a buffer since the source and destination blocks may overlap
+        let mut block = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            let start = (from_y + row) * width + from_x;
+            block.extend_from_slice(&self.chars[start..start + columns]);
+        }
+        for row in 0..rows {
+            let start = (to_y + row) * width + to_x;
+            self.chars[start..start + columns].copy_from_slice(&block[row * columns..(row + 1) * columns]);
+        }
+    }
+}
+
+/// Crossover and mutation settings applied when breeding offspring
+#[derive(Clone, Copy, Debug)]
+struct Variation<'c> {
+    crossover: CrossoverOperator,
+    width: usize,
+    crossover_rate: f64,
+    mutation: MutationOperator,
+    mutation_rate: f64,
+    targeted_mutation: f64,
+    background_prob: f64,
+    charset: &'c Charset,
+    locks: Option<&'c LockMask>,
+}
+
+impl Variation<'_> {
+    /// Breeds each parent pair in parallel on its own random stream and keeps the first `count` children
+    /// Pair `i` always draws from `task_rng(base_seed, i)`, so the offspring do not depend on the thread count
+    fn breed_pairs(&self, pairs: &[(&Individual, &Individual)], count: usize, base_seed: u64) -> Vec<Individual> {
+        let mut offspring: Vec<Individual> = pairs
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(index, (parent1, parent2))| {
+                let (child1, child2) = self.breed(parent1, parent2, &mut task_rng(base_seed, index));
+                [child1, child2]
+            })
+            .collect();
+        offspring.truncate(count);
+        offspring
+    }
+
+    /// Produces two children by crossing over the parents and mutating the results
+    /// Locked cells are restored afterwards, so they never change
+    fn breed<R: Rng + ?Sized>(&self, parent1: &Individual, parent2: &Individual, rng: &mut R) -> (Individual, Individual) {
+        let (mut child1, mut child2) = parent1.crossover(parent2, self.crossover, self.crossover_rate, self.width, rng);
+
+        for child in [&mut child1, &mut child2] {
+            self.mutate(child, rng);
+            if let Some(locks) = self.locks {
+                locks.apply(child);
+            }
+        }
+
+        (child1, child2)
+    }
+
+    /// Applies the mutation operator at each cell picked for mutation
+    fn mutate<R: Rng + ?Sized>(&self, individual: &mut Individual, rng: &mut R) {
+        const OPERATORS: [MutationOperator; 4] =
+            [MutationOperator::Point, MutationOperator::Swap, MutationOperator::Shift, MutationOperator::BlockCopy];
+        let len = individual.chars.len();
+
+        for cell in individual.mutation_sites(self.mutation_rate, self.targeted_mutation, rng) {
+            let operator = match self.mutation {
+                MutationOperator::Mixed => OPERATORS[rng.gen_range(0..OPERATORS.len())],
+                operator => operator,
+            };
+            match operator {
+                MutationOperator::Swap => individual.swap_cells(cell, rng.gen_range(0..len)),
+                MutationOperator::Shift => individual.shift_row(cell, self.width, rng.gen()),
+                MutationOperator::BlockCopy => {
+                    let size = (rng.gen_range(1..=MUTATION_BLOCK_SIZE), rng.gen_range(1..=MUTATION_BLOCK_SIZE));
+                    individual.copy_block(cell, rng.gen_range(0..len), size, self.width);
+                }
+                _ => individual.chars[cell] = self.charset.random_with_background_prob(self.background_prob, rng),
+            }
+        }
+    }
+}
+
+/// Read-only scoring inputs shared by all rayon workers during population evaluation
+#[derive(Clone, Copy)]
+struct FitnessContext<'a> {
+    ascii_generator: &'a AsciiGenerator,
+    scorer: &'a Scorer<'a>,
+    pareto: Option<ParetoObjective>,
+    cell_ceilings: Option<&'a [f32]>,
+    width: u32,
+    height: u32,
+}
+
+/// Per-worker buffers reused across evaluations: the rendered genome and its edge map
+#[derive(Default)]
+struct ScratchBuffers {
+    render: ImageBuffer<Luma<u8>, Vec<u8>>,
+    edges: ImageBuffer<Luma<u8>, Vec<u8>>,
+}
+
+/// Scratch buffers kept between generations, at most one set per worker evaluating at the same time, so a warm
+/// population is evaluated without allocating any images
+#[derive(Default)]
+struct ScratchPool(Mutex<Vec<ScratchBuffers>>);
+
+impl ScratchPool {
+    /// Lends a set of buffers, allocating one only if every pooled set is in use; it returns to the pool when dropped
+    fn lend(&self) -> PooledScratch<'_> {
+        let buffers = self.0.lock().expect("scratch pool lock").pop().unwrap_or_default();
+        PooledScratch { pool: self, buffers }
+    }
+}
+
+/// Buffers borrowed from a `ScratchPool`
+struct PooledScratch<'p> {
+    pool: &'p ScratchPool,
+    buffers: ScratchBuffers,
+}
+
+impl Drop for PooledScratch<'_> {
+    fn drop(&mut self) {
+        self.pool.0.lock().expect("scratch pool lock").push(std::mem::take(&mut self.buffers));
+    }
+}
+
+impl FitnessContext<'_> {
+    /// Renders the genome into the worker's scratch buffers and stores its fitness, second objective and cell errors
+    fn evaluate(&self, individual: &mut Individual, scratch: &mut ScratchBuffers) {
+        self.ascii_generator.render_into(&individual.chars, self.width, self.height, false, &mut scratch.render);
+        individual.fitness = self.scorer.score(&scratch.render, &mut scratch.edges);
+        if let Some(ceilings) = self.cell_ceilings {
+            self.scorer.cell_scores(&scratch.render, self.ascii_generator.char_dimensions(), &mut individual.cell_errors);
+            for (error, &ceiling) in individual.cell_errors.iter_mut().zip(ceilings) {
+                *error = (ceiling - *error).max(0.0);
+            }
+        }
+        individual.objective = match self.pareto {
+            Some(ParetoObjective::Ink) => blank_share(self.ascii_generator, &individual.chars),
+            Some(ParetoObjective::Edge) => self.scorer.edge_agreement(&scratch.render, &mut scratch.edges),
+            None => 0.0,
+        };
+    }
+}
+
+/// Fraction of a genome's cells holding the character with the least ink, the objective of `ParetoObjective::Ink`
+fn blank_share(ascii_generator: &AsciiGenerator, chars: &[u8]) -> f64 {
+    let blank = ascii_generator.density_ramp()[0];
+    chars.iter().filter(|&&c| c == blank).count() as f64 / chars.len().max(1) as f64
+}
+
+/// Returns true if `a` is at least as good as `b` on fitness and the second objective, and better on one of them
+fn dominates(a: &Individual, b: &Individual) -> bool {
+    a.fitness >= b.fitness && a.objective >= b.objective && (a.fitness > b.fitness || a.objective > b.objective)
+}
+
+/// Splits individuals into Pareto fronts by fast non-dominated sorting
+/// Front 0 holds the indices no one dominates, front 1 those dominated only by front 0, and so on
+fn non_dominated_fronts(individuals: &[Individual]) -> Vec<Vec<usize>> {
+    let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); individuals.len()];
+    let mut dominator_count = vec![0usize; individuals.len()];
+    for i in 0..individuals.len() {
+        for j in i + 1..individuals.len() {
+            if dominates(&individuals[i], &individuals[j]) {
+                dominated[i].push(j);
+                dominator_count[j] += 1;
+            } else if dominates(&individuals[j], &individuals[i]) {
+                dominated[j].push(i);
+                dominator_count[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..individuals.len()).filter(|&i| dominator_count[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominated[i] {
+                dominator_count[j] -= 1;
+                if dominator_count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+/// Returns each individual's Pareto rank (0 for the first front) and crowding distance within its front
+/// The crowding distance sums the normalized gaps between an individual's neighbours on both objectives;
+/// the extremes of each front get infinity so they are always preferred
+fn crowded_ranking(individuals: &[Individual]) -> (Vec<usize>, Vec<f64>) {
+    let mut rank = vec![0; individuals.len()];
+    let mut crowding = vec![0.0; individuals.len()];
+    let objectives: [fn(&Individual) -> f64; 2] = [|individual| individual.fitness, |individual| individual.objective];
+
+    for (front_rank, mut front) in non_dominated_fronts(individuals).into_iter().enumerate() {
+        for &i in &front {
+            rank[i] = front_rank;
+        }
+        for objective in objectives {
+            front.sort_by(|&a, &b| objective(&individuals[a]).partial_cmp(&objective(&individuals[b])).unwrap_or(Ordering::Equal));
+            let (first, last) = (front[0], front[front.len() - 1]);
+            let range = objective(&individuals[last]) - objective(&individuals[first]);
+            crowding[first] = f64::INFINITY;
+            crowding[last] = f64::INFINITY;
+            if range > 0.0 {
+                for window in front.windows(3) {
+                    crowding[window[1]] += (objective(&individuals[window[2]]) - objective(&individuals[window[0]])) / range;
                 }
             }
         }
     }
+    (rank, crowding)
 }
 
 /// Main genetic algorithm implementation
@@ -133,18 +854,166 @@ pub struct GeneticAlgorithm<'a> {
     width: u32,
     height: u32,
     ascii_generator: &'a AsciiGenerator,
-    target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>,
-    total_non_background_pixels: f64,
-    background_threshold: u8,
+    scorer: Scorer<'a>,
     background_prob: f64,
+    mutation: MutationOperator,
     mutation_rate: f64,
+    targeted_mutation: f64,
+    cell_ceilings: Vec<f32>,
+    local_search_cells: usize,
+    glyph_images: Vec<ImageBuffer<Luma<u8>, Vec<u8>>>,
+    crossover: CrossoverOperator,
     crossover_rate: f64,
     elite_size: usize,
     thread_count: usize,
+    pool: Option<Arc<rayon::ThreadPool>>,
+    init_char: Option<char>,
+    replacement_strategy: ReplacementStrategy,
+    selection: SelectionStrategy,
+    tournament_size: usize,
+    alps: Option<AlpsConfig>,
+    islands: Option<IslandConfig>,
+    pareto: Option<ParetoObjective>,
+    locks: Option<&'a LockMask>,
+    max_ink: Option<f64>,
+    sharing_radius: Option<f64>,
+    shared_fitness: Vec<f64>,
+    stall_generations: u32,
+    stall_epsilon: f64,
+    stop_reason: StopReason,
+    restart_on_stall: bool,
+    target_fitness: Option<f64>,
+    best_ever: Option<Individual>,
+    restarts: u32,
+    fitness_cache: FitnessCache,
+    scratch: ScratchPool,
+    /// Evaluates the overlap fitness on a GPU once `enable_gpu` found one
+    #[cfg(feature = "gpu")]
+    gpu: Option<crate::gpu::GpuEvaluator>,
+    generations_run: u32,
+    /// Generation the next `evolve` call starts counting from, set by `resume`
+    start_generation: u32,
+    rng: StdRng,
+}
+
+/// Mutation rate applied to warm-start copies so the seeded population keeps some diversity
+const WARM_START_MUTATION_RATE: f64 = 0.05;
+
+/// Generations' worth of individuals whose scores the fitness cache keeps
+const FITNESS_CACHE_GENERATIONS: usize = 4;
+
+/// Scores of recently evaluated genomes keyed by a hash of their characters, so genomes seen again this run, such as
+/// carried-over elites or offspring identical to a parent, are not rendered again
+/// The least recently used entries are dropped once the cache holds more than `capacity`; 0 disables it
+struct FitnessCache {
+    capacity: usize,
+    entries: HashMap<u64, CachedScore>,
+    clock: u64,
+}
+
+/// Evaluation results of one genome and when the cache last handed them out
+struct CachedScore {
+    fitness: f64,
+    objective: f64,
+    cell_errors: Vec<f32>,
+    last_used: u64,
+}
+
+impl FitnessCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), clock: 0 }
+    }
+
+    fn genome_hash(chars: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        chars.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Drops every entry; called whenever a setting changes how genomes score
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Copies a cached evaluation into the individual, returning false if the genome has not been scored
+    fn restore(&mut self, hash: u64, individual: &mut Individual) -> bool {
+        let Some(entry) = self.entries.get_mut(&hash) else { return false };
+        self.clock += 1;
+        entry.last_used = self.clock;
+        individual.fitness = entry.fitness;
+        individual.objective = entry.objective;
+        individual.cell_errors.clone_from(&entry.cell_errors);
+        true
+    }
+
+    /// Stores an evaluated individual's results
+    fn insert(&mut self, hash: u64, individual: &Individual) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        let entry = CachedScore {
+            fitness: individual.fitness,
+            objective: individual.objective,
+            cell_errors: individual.cell_errors.clone(),
+            last_used: self.clock,
+        };
+        self.entries.insert(hash, entry);
+    }
+
+    /// Drops the least recently used entries beyond the capacity
+    fn evict(&mut self) {
+        let surplus = self.entries.len().saturating_sub(self.capacity);
+        if surplus == 0 {
+            return;
+        }
+        let mut ages: Vec<u64> = self.entries.values().map(|entry| entry.last_used).collect();
+        let (_, &mut cutoff, _) = ages.select_nth_unstable(surplus - 1);
+        self.entries.retain(|_, entry| entry.last_used > cutoff);
+    }
+}
+
+/// Island model settings: sub-populations that breed separately and exchange their best individuals
+#[derive(Clone, Copy, Debug)]
+struct IslandConfig {
+    count: usize,
+    migration_interval: u32,
+    migrants: usize,
+}
+
+/// Returns the random stream for the `index`th task of a parallel step
+/// Each step draws `base_seed` from the run's generator, and every task seeds its own generator from
+/// `base_seed + index`, so what a task draws never depends on which thread runs it or in what order
+fn task_rng(base_seed: u64, index: usize) -> StdRng {
+    StdRng::seed_from_u64(base_seed.wrapping_add(index as u64))
+}
+
+/// Sends copies of each island's best `migrants` individuals to the next island in a ring, replacing its worst
+/// Islands are sorted best first on entry and on return
+fn migrate(islands: &mut [Vec<Individual>], migrants: usize) {
+    let outgoing: Vec<Vec<Individual>> = islands.iter().map(|members| members.iter().take(migrants).cloned().collect()).collect();
+    let count = islands.len();
+    for (from, group) in outgoing.into_iter().enumerate() {
+        let to = (from + 1) % count;
+        let members = &mut islands[to];
+        // Never replace an island's whole population
+        let arriving = group.len().min(members.len().saturating_sub(1));
+        members.truncate(members.len() - arriving);
+        members.extend(group.into_iter().take(arriving).map(|migrant| Individual { island: to, ..migrant }));
+        members.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+    }
+}
+
+/// Age-layered population structure settings
+#[derive(Clone, Copy, Debug)]
+struct AlpsConfig {
+    layers: usize,
+    age_gap: u32,
 }
 
 impl<'a> GeneticAlgorithm<'a> {
-    /// Creates a new genetic algorithm instance
+    /// Creates a new genetic algorithm instance; a `thread_count` of 0 uses one thread per logical core
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         width: u32,
         height: u32,
@@ -156,39 +1025,26 @@ impl<'a> GeneticAlgorithm<'a> {
         white_background: bool,
     ) -> Self {
         let individual_size = (width * height) as usize;
+        let thread_count = if thread_count == 0 { available_threads() } else { thread_count };
 
-        // Calculate background threshold and count non-background pixels
-        let background_threshold = if white_background { 200 } else { 50 }; // Threshold for what counts as "background"
-        let total_non_background_pixels = Self::count_non_background_pixels(target_image, background_threshold, white_background);
+        // The scorer derives the background threshold and counts non-background pixels
+        let scorer = Scorer::new(target_image, white_background);
 
         // Calculate background probability for random initialization
         let total_pixels = (target_image.width() * target_image.height()) as f64;
-        let background_prob = (total_pixels - total_non_background_pixels) / total_pixels;
+        let background_prob = (total_pixels - scorer.total_non_background_pixels()) / total_pixels;
 
+        let mut rng = StdRng::from_entropy();
         let population: Vec<Individual> = (0..population_size)
-            .map(|_| {
-                match init_char {
-                    Some(ch) => Individual::new_with_init_char(individual_size, ch),
-                    None => Individual::new_random_with_background_prob(individual_size, background_prob),
-                }
-            })
+            .map(|_| Self::create_random_individual(individual_size, init_char, background_prob, ascii_generator.charset(), &mut rng))
             .collect();
 
-        println!("Background threshold: {}, Total non-background pixels: {}, Background probability: {:.1}%",
-                 background_threshold, total_non_background_pixels, background_prob * 100.0);
-
-        // Set up thread pool for parallel processing
-        // Only initialize if not already initialized (for testing compatibility)
-        if let Err(e) = rayon::ThreadPoolBuilder::new()
-            .num_threads(thread_count)
-            .build_global()
-        {
-            // Check if the global pool is already initialized, which is fine for tests
-            let error_string = format!("{:?}", e);
-            if !error_string.contains("GlobalPoolAlreadyInitialized") {
-                panic!("Failed to initialize thread pool: {:?}", e);
-            }
-        }
+        // Each instance owns its thread pool, so several can coexist in one process with different thread counts
+        // WebAssembly has no threads, and a pool that fails to start falls back to rayon's global pool
+        #[cfg(not(target_arch = "wasm32"))]
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(thread_count).build().ok().map(Arc::new);
+        #[cfg(target_arch = "wasm32")]
+        let pool = None;
 
         Self {
             population,
@@ -196,305 +1052,1152 @@ impl<'a> GeneticAlgorithm<'a> {
             width,
             height,
             ascii_generator,
-            target_image,
-            total_non_background_pixels,
-            background_threshold,
+            scorer,
             background_prob,
+            mutation: MutationOperator::default(),
             mutation_rate: 0.01,
+            targeted_mutation: 0.0,
+            cell_ceilings: Vec::new(),
+            local_search_cells: 0,
+            glyph_images: Vec::new(),
+            crossover: CrossoverOperator::default(),
             crossover_rate: 0.8,
             elite_size: population_size / 10, // Top 10% are elite
             thread_count,
+            pool,
+            init_char,
+            replacement_strategy: ReplacementStrategy::default(),
+            selection: SelectionStrategy::default(),
+            tournament_size: DEFAULT_TOURNAMENT_SIZE,
+            alps: None,
+            islands: None,
+            pareto: None,
+            locks: None,
+            max_ink: None,
+            sharing_radius: None,
+            shared_fitness: Vec::new(),
+            stall_generations: 0,
+            stall_epsilon: 0.0,
+            stop_reason: StopReason::default(),
+            restart_on_stall: false,
+            target_fitness: None,
+            best_ever: None,
+            restarts: 0,
+            fitness_cache: FitnessCache::new(population_size * FITNESS_CACHE_GENERATIONS),
+            scratch: ScratchPool::default(),
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            generations_run: 0,
+            start_generation: 0,
+            rng,
         }
     }
 
-    /// Counts pixels that are not background color in the target image
-    fn count_non_background_pixels(
-        target_image: &ImageBuffer<Luma<u8>, Vec<u8>>,
-        background_threshold: u8,
-        white_background: bool,
-    ) -> f64 {
-        let mut count = 0;
-
-        for pixel in target_image.pixels() {
-            let intensity = pixel[0];
+    /// Reseeds the random number generator and regenerates the initial population from it
+    /// Two runs with the same seed and settings then produce identical results
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.randomize_population();
+        self.clear_archive();
+        self.generations_run = 0;
+        self.start_generation = 0;
+    }
 
-            // For black background mode: non-background pixels are bright (> threshold)
-            // For white background mode: non-background pixels are dark (< threshold)
-            let is_non_background = if white_background {
-                intensity < background_threshold
-            } else {
-                intensity > background_threshold
-            };
+    /// Forgets the individuals archived by earlier restarts, since the population they came from was replaced
+    fn clear_archive(&mut self) {
+        self.best_ever = None;
+        self.restarts = 0;
+    }
 
-            if is_non_background {
-                count += 1;
-            }
+    /// Archives the current best if it beats everything archived before, then starts over from a random population
+    fn restart(&mut self) {
+        let best = &self.population[0];
+        if self.best_ever.as_ref().is_none_or(|archived| best.fitness > archived.fitness) {
+            self.best_ever = Some(best.clone());
         }
+        self.randomize_population();
+        self.restarts += 1;
+    }
 
-        count as f64
+    /// Replaces the whole population with fresh random individuals, spread over the islands if there are any
+    fn randomize_population(&mut self) {
+        let individual_size = (self.width * self.height) as usize;
+        let islands = self.islands.map_or(1, |islands| islands.count);
+        self.population = (0..self.population_size)
+            .map(|i| Individual {
+                island: i % islands,
+                ..Self::create_random_individual(individual_size, self.init_char, self.background_prob, self.ascii_generator.charset(), &mut self.rng)
+            })
+            .collect();
+        self.apply_locks();
     }
 
-    /// Runs the genetic algorithm for the specified number of generations with optional UI callback
-    /// If generations is 0, runs continuously until user interrupts via callback
-    pub fn evolve<F>(&mut self, generations: u32, verbose: bool, status_interval: f64, mut ui_callback: Option<F>) -> (Individual, f64)
-    where
-        F: FnMut(u32, u32, f64, f64, usize, usize, u32, u32, Option<String>) -> bool,
-    {
-        use std::time::{Duration, Instant};
+    /// Replaces the population with variations of an earlier solution, such as the previous animation frame's best
+    /// One exact copy is kept and the rest are mutated copies, so evolution starts close to the old result
+    /// but can still adapt to the new target; individuals of a different size are ignored
+    pub fn warm_start(&mut self, previous_best: &Individual) {
+        self.seed_population(previous_best, 1.0);
+    }
+
+    /// Replaces the given fraction of the population (at least one individual) with variations of existing art,
+    /// keeping the rest random: one exact copy plus mutated copies, so evolution refines the art instead of
+    /// starting from noise; individuals of a different size are ignored
+    /// Returns the number of individuals seeded
+    pub fn seed_population(&mut self, seed: &Individual, fraction: f64) -> usize {
+        if seed.chars.len() != (self.width * self.height) as usize {
+            return 0;
+        }
+
+        let count = ((self.population_size as f64 * fraction).round() as usize).clamp(1, self.population_size);
+        let charset = self.ascii_generator.charset();
+        for (i, slot) in self.population.iter_mut().take(count).enumerate() {
+            let mut individual = Individual { island: slot.island, ..Individual::new(seed.chars.clone()) };
+            if i > 0 {
+                individual.mutate_with_background_prob(WARM_START_MUTATION_RATE, self.background_prob, charset, &mut self.rng);
+            }
+            *slot = individual;
+        }
+        self.apply_locks();
+        self.clear_archive();
+        self.generations_run = 0;
+        self.start_generation = 0;
+        count
+    }
+
+    /// Freezes the locked cells of the mask in every current and future individual; a mask of a different size is ignored
+    pub fn set_locks(&mut self, locks: &'a LockMask) {
+        if locks.len() != (self.width * self.height) as usize {
+            return;
+        }
+        self.locks = Some(locks);
+        self.apply_locks();
+    }
+
+    /// Scales each target pixel's contribution to fitness, 0 excluding it; weights of a different size than the target are ignored
+    pub fn set_pixel_weights(&mut self, pixel_weights: &'a PixelWeights) {
+        self.scorer.set_pixel_weights(pixel_weights);
+        self.cell_ceilings.clear();
+        self.fitness_cache.clear();
+        #[cfg(feature = "gpu")]
+        self.reload_gpu();
+    }
+
+    /// Selects the measure fitness is based on; the default pixel overlap is the fastest
+    pub fn set_fitness_metric(&mut self, metric: FitnessMetric) {
+        self.scorer.set_metric(metric);
+        self.fitness_cache.clear();
+    }
+
+    /// Sets the match tolerance and false-positive penalty of the overlap metric
+    pub fn set_overlap_params(&mut self, overlap: OverlapParams) {
+        self.scorer.set_overlap_params(overlap);
+        self.cell_ceilings.clear();
+        self.fitness_cache.clear();
+        #[cfg(feature = "gpu")]
+        self.reload_gpu();
+    }
+
+    /// Evaluates fitness on the first GPU that runs compute shaders from now on, returning its name
+    /// Fails without changing anything when there is none, so evaluation stays on the CPU; only the overlap metric
+    /// without the edge term runs on the GPU, so other settings, `ParetoObjective::Edge` and targeted mutation keep
+    /// evaluating on the CPU with the same results
+    #[cfg(feature = "gpu")]
+    pub fn enable_gpu(&mut self) -> Result<&str, String> {
+        let gpu = crate::gpu::GpuEvaluator::new(self.ascii_generator, &self.scorer, self.width, self.height)?;
+        Ok(self.gpu.insert(gpu).adapter_name())
+    }
+
+    /// Name of the GPU fitness is evaluated on, if `enable_gpu` found one
+    #[cfg(feature = "gpu")]
+    pub fn gpu_adapter(&self) -> Option<&str> {
+        self.gpu.as_ref().map(|gpu| gpu.adapter_name())
+    }
+
+    /// Uploads the scoring settings to the GPU again after they changed
+    #[cfg(feature = "gpu")]
+    fn reload_gpu(&mut self) {
+        if let Some(gpu) = &mut self.gpu {
+            gpu.reload(self.ascii_generator, &self.scorer, self.width, self.height);
+        }
+    }
+
+    /// Adds a Sobel edge-agreement term to fitness with the given weight relative to the metric (0 disables it)
+    /// Fitness becomes (metric + weight * edge agreement) / (1 + weight), so it stays between 0.0 and 1.0
+    pub fn set_edge_weight(&mut self, weight: f64) {
+        self.scorer.set_edge_weight(weight);
+        self.fitness_cache.clear();
+    }
+
+    /// Switches to multi-objective evolution (NSGA-II) maximizing fitness and the given objective together
+    /// Replaces the replacement strategy and ALPS; `best` stays the fittest individual and `pareto_front`
+    /// returns the trade-offs to choose from
+    pub fn set_pareto_objective(&mut self, objective: ParetoObjective) {
+        self.pareto = Some(objective);
+        self.scorer.set_track_edges(objective == ParetoObjective::Edge);
+        self.fitness_cache.clear();
+    }
+
+    /// Limits the fraction of cells (0.0 to 1.0) that may hold a non-blank character
+    /// Individuals over the budget are repaired before evaluation by blanking randomly chosen surplus cells;
+    /// ignored when the character set has no blank character
+    pub fn set_max_ink(&mut self, max_ink: f64) {
+        self.max_ink = Some(max_ink.clamp(0.0, 1.0));
+    }
+
+    /// Enables fitness sharing: parents are selected on fitness divided by a niche count that grows with the number of
+    /// individuals differing in fewer than `radius` (a fraction of the cells) of their cells, preventing premature convergence
+    /// Elites, replacement and reported fitness still use raw fitness
+    pub fn set_fitness_sharing(&mut self, radius: f64) {
+        self.sharing_radius = Some(radius.clamp(0.0, 1.0));
+    }
+
+    /// Writes the locked cells into every individual of the population
+    fn apply_locks(&mut self) {
+        if let Some(locks) = self.locks {
+            for individual in &mut self.population {
+                locks.apply(individual);
+            }
+        }
+    }
+
+    /// Returns the intensity threshold separating background from foreground pixels
+    pub fn background_threshold(&self) -> u8 {
+        self.scorer.background_threshold()
+    }
+
+    /// Returns the number of threads evaluating fitness
+    pub fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    /// Returns the number of foreground pixels in the target image that fitness is measured against (their summed weights when pixel weights are set)
+    pub fn total_non_background_pixels(&self) -> f64 {
+        self.scorer.total_non_background_pixels()
+    }
+
+    /// Returns the fraction of background pixels, used as the probability of placing a space
+    pub fn background_probability(&self) -> f64 {
+        self.background_prob
+    }
+
+    /// Returns the fittest individual as of the last evaluation, or the one archived before a restart if it scored higher
+    pub fn best(&self) -> &Individual {
+        match &self.best_ever {
+            Some(archived) if archived.fitness > self.population[0].fitness => archived,
+            _ => &self.population[0],
+        }
+    }
+
+    /// Returns the best, mean, median and worst fitness of the population as of the last evaluation
+    pub fn fitness_stats(&self) -> FitnessStats {
+        // Evaluation leaves the population sorted best first
+        let count = self.population.len();
+        let fitness = |i: usize| self.population[i].fitness;
+        FitnessStats {
+            best: fitness(0),
+            mean: self.population.iter().map(|individual| individual.fitness).sum::<f64>() / count as f64,
+            median: if count.is_multiple_of(2) { (fitness(count / 2 - 1) + fitness(count / 2)) / 2.0 } else { fitness(count / 2) },
+            worst: fitness(count - 1),
+        }
+    }
+
+    /// Rates each cell of the best individual by its shortfall from the best score any character reaches there,
+    /// in row-major order: 1 where no character would do better, 0 for the cell furthest below its best
+    /// Shows which regions the search is struggling with; costs one render and a per-cell scoring pass
+    pub fn cell_quality(&mut self) -> Vec<f32> {
+        if self.cell_ceilings.is_empty() {
+            self.cell_ceilings = self.best_cell_scores();
+        }
+        let rendered = self.ascii_generator.generate_ascii_image(&self.best().chars, self.width, self.height);
+        self.scorer.cell_quality(&rendered, self.ascii_generator.char_dimensions(), &self.cell_ceilings)
+    }
+
+    /// Returns the mean fraction of cells in which two individuals differ (0 for a population of clones)
+    /// Pairs are taken from up to `DIVERSITY_SAMPLE_SIZE` individuals spread evenly over the sorted population
+    pub fn diversity(&self) -> f64 {
+        let count = self.population.len();
+        let sample: Vec<&Individual> =
+            (0..DIVERSITY_SAMPLE_SIZE.min(count)).map(|i| &self.population[i * count / DIVERSITY_SAMPLE_SIZE.min(count)]).collect();
+        let cells = self.population[0].chars.len().max(1);
+        let (mut total, mut pairs) = (0, 0);
+        for (i, a) in sample.iter().enumerate() {
+            for b in &sample[i + 1..] {
+                total += a.hamming_distance(b);
+                pairs += 1;
+            }
+        }
+        if pairs == 0 { 0.0 } else { total as f64 / (pairs * cells) as f64 }
+    }
+
+    /// Returns the non-dominated individuals of the last evaluation, one per distinct trade-off, fittest first
+    /// Under multi-objective evolution these are the solutions where neither objective can improve without the other getting worse
+    pub fn pareto_front(&self) -> Vec<Individual> {
+        let mut front: Vec<Individual> = non_dominated_fronts(&self.population)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|i| self.population[i].clone())
+            .collect();
+        front.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+        front.dedup_by(|a, b| (a.fitness, a.objective) == (b.fitness, b.objective));
+        front
+    }
+
+    /// Returns the number of generations completed by `evolve`, or by `step` since the population was last reset
+    pub fn generations_run(&self) -> u32 {
+        self.generations_run
+    }
+
+    /// Returns how many times evolution restarted from a random population after stalling
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Returns why the last `evolve` call stopped
+    pub fn stop_reason(&self) -> StopReason {
+        self.stop_reason
+    }
+
+    /// Saves the population and evolution state, typically after `evolve` was interrupted
+    /// The random number generator itself cannot be saved, so a seed drawn from it is stored instead: a resumed run
+    /// is reproducible, but not identical to one that was never interrupted
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let charset = self.ascii_generator.charset();
+        let save = |individual: &Individual| SavedIndividual {
+            chars: individual.chars.iter().map(|&code| charset.char_at(code)).collect(),
+            fitness: individual.fitness,
+            age: individual.age,
+            island: individual.island,
+        };
+        Checkpoint {
+            width: self.width,
+            height: self.height,
+            generations_run: self.generations_run,
+            population: self.population.iter().map(save).collect(),
+            best_ever: self.best_ever.as_ref().map(save),
+            restarts: self.restarts,
+            mutation_rate: self.mutation_rate,
+            crossover_rate: self.crossover_rate,
+            elite_size: self.elite_size,
+            seed: self.rng.gen(),
+        }
+    }
+
+    /// Restores a checkpoint taken with the same art size, population size and character set
+    /// The next `evolve` call continues its generation count, so its `generations` limit includes the generations run
+    /// before the checkpoint
+    pub fn resume(&mut self, checkpoint: &Checkpoint) -> Result<(), String> {
+        if (checkpoint.width, checkpoint.height) != (self.width, self.height) {
+            return Err(format!("the checkpoint is for {}x{} art, not {}x{}", checkpoint.width, checkpoint.height, self.width, self.height));
+        }
+        if checkpoint.population.len() != self.population_size {
+            return Err(format!("the checkpoint holds {} individuals, not {}", checkpoint.population.len(), self.population_size));
+        }
+        let charset = self.ascii_generator.charset();
+        let cells = (self.width * self.height) as usize;
+        let restore = |saved: &SavedIndividual| match charset.encode(&saved.chars) {
+            Some(chars) if chars.len() == cells => Ok(Individual {
+                fitness: saved.fitness,
+                age: saved.age,
+                island: saved.island,
+                ..Individual::new(chars)
+            }),
+            Some(_) => Err(format!("a checkpoint individual does not have {} cells", cells)),
+            None => Err("the checkpoint uses characters outside the character set".to_string()),
+        };
+        let population = checkpoint.population.iter().map(restore).collect::<Result<Vec<_>, _>>()?;
+        let best_ever = checkpoint.best_ever.as_ref().map(restore).transpose()?;
+
+        self.population = population;
+        self.apply_locks();
+        self.best_ever = best_ever;
+        self.restarts = checkpoint.restarts;
+        self.set_mutation_rate(checkpoint.mutation_rate);
+        self.set_crossover_rate(checkpoint.crossover_rate);
+        self.set_elite_size(checkpoint.elite_size);
+        self.rng = StdRng::seed_from_u64(checkpoint.seed);
+        self.generations_run = checkpoint.generations_run;
+        self.start_generation = checkpoint.generations_run;
+        Ok(())
+    }
+
+    /// Creates a fresh individual using either the init character or the background probability
+    fn create_random_individual(size: usize, init_char: Option<char>, background_prob: f64, charset: &Charset, rng: &mut StdRng) -> Individual {
+        match init_char {
+            Some(ch) => Individual::new_with_init_char(size, ch, charset, rng),
+            None => Individual::new_random_with_background_prob(size, background_prob, charset, rng),
+        }
+    }
+
+    /// Returns the current crossover and mutation settings
+    fn variation(&self) -> Variation<'a> {
+        Variation {
+            crossover: self.crossover,
+            width: self.width as usize,
+            crossover_rate: self.crossover_rate,
+            mutation: self.mutation,
+            mutation_rate: self.mutation_rate,
+            targeted_mutation: self.targeted_mutation,
+            background_prob: self.background_prob,
+            charset: self.ascii_generator.charset(),
+            locks: self.locks,
+        }
+    }
+
+    /// Sets the per-character probability that a child's character is replaced by a random one
+    pub fn set_mutation_rate(&mut self, mutation_rate: f64) {
+        self.mutation_rate = mutation_rate;
+    }
+
+    /// Returns the per-character mutation probability
+    pub fn mutation_rate(&self) -> f64 {
+        self.mutation_rate
+    }
+
+    /// Sets the crossover rate: the per-cell swap probability for uniform crossover, per block for block crossover,
+    /// and the chance of crossing at all for two-point crossover
+    pub fn set_crossover_rate(&mut self, crossover_rate: f64) {
+        self.crossover_rate = crossover_rate;
+    }
+
+    /// Returns the crossover rate
+    pub fn crossover_rate(&self) -> f64 {
+        self.crossover_rate
+    }
+
+    /// Sets how many of the fittest individuals are copied unchanged into each generation (10% of the population by
+    /// default); at least one individual is always bred
+    pub fn set_elite_size(&mut self, elite_size: usize) {
+        self.elite_size = elite_size.min(self.population_size.saturating_sub(1));
+    }
+
+    /// Returns how many individuals are copied unchanged into each generation
+    pub fn elite_size(&self) -> usize {
+        self.elite_size
+    }
+
+    /// Applies the parameter changes a progress sink asked for, clamping rates to 0-1; `evolve` applies the status
+    /// interval itself
+    pub fn apply_tuning(&mut self, tuning: Tuning) {
+        if let Some(mutation_rate) = tuning.mutation_rate {
+            self.set_mutation_rate(mutation_rate.clamp(0.0, 1.0));
+        }
+        if let Some(crossover_rate) = tuning.crossover_rate {
+            self.set_crossover_rate(crossover_rate.clamp(0.0, 1.0));
+        }
+        if let Some(elite_size) = tuning.elite_size {
+            self.set_elite_size(elite_size);
+        }
+    }
+
+    /// Sets what happens at each cell picked for mutation; structural operators move characters around instead of replacing them
+    pub fn set_mutation_operator(&mut self, mutation: MutationOperator) {
+        self.mutation = mutation;
+    }
+
+    /// Biases mutation toward the cells furthest below the best score any character reaches there,
+    /// from 0 (uniform) to 1 (proportional to that shortfall, so cells holding their best character stop mutating)
+    /// Enabling it makes every evaluation also compute a per-cell error map
+    pub fn set_targeted_mutation(&mut self, bias: f64) {
+        self.targeted_mutation = bias.clamp(0.0, 1.0);
+        // Cached entries may lack the per-cell errors targeted mutation needs
+        self.fitness_cache.clear();
+    }
+
+    /// Sets the operator that combines parents into children
+    pub fn set_crossover_operator(&mut self, crossover: CrossoverOperator) {
+        self.crossover = crossover;
+    }
+
+    /// Sets how parents are chosen for breeding; multi-objective evolution always uses crowded tournaments
+    pub fn set_selection_strategy(&mut self, selection: SelectionStrategy) {
+        self.selection = selection;
+    }
+
+    /// Sets how many individuals each tournament draws (at least 1); larger tournaments raise selection pressure
+    pub fn set_tournament_size(&mut self, tournament_size: usize) {
+        self.tournament_size = tournament_size.max(1);
+    }
+
+    /// Sets how offspring are merged back into the population
+    pub fn set_replacement_strategy(&mut self, strategy: ReplacementStrategy) {
+        self.replacement_strategy = strategy;
+    }
+
+    /// Enables the age-layered population structure (ALPS) with the given number of layers
+    /// Fresh random individuals enter the bottom layer every `age_gap` generations; 0 layers disables ALPS
+    pub fn set_alps(&mut self, layers: usize, age_gap: u32) {
+        self.alps = if layers == 0 {
+            None
+        } else {
+            Some(AlpsConfig { layers, age_gap: age_gap.max(1) })
+        };
+    }
+
+    /// Stops `evolve` early once the best fitness has gained no more than `epsilon` over `generations` consecutive
+    /// generations (0 disables the check)
+    pub fn set_stall_generations(&mut self, generations: u32, epsilon: f64) {
+        self.stall_generations = generations;
+        self.stall_epsilon = epsilon.max(0.0);
+    }
+
+    /// Makes a stall restart evolution from a fresh random population instead of stopping it, until the generation
+    /// budget runs out; the best individual found before each restart is archived, and `best` and `evolve` return it
+    /// unless a later population beats it
+    pub fn set_restart_on_stall(&mut self, enabled: bool) {
+        self.restart_on_stall = enabled;
+    }
+
+    /// Stops `evolve` as soon as the best fitness reaches `target` (0.0 to 1.0), so callers can ask for
+    /// "good enough" instead of a generation count
+    pub fn set_target_fitness(&mut self, target: Option<f64>) {
+        self.target_fitness = target;
+    }
+
+    /// Runs a memetic local search each generation: `cells` random cells of every elite are set to the character that
+    /// scores best there before the elites breed and carry forward (0 disables it)
+    pub fn set_local_search(&mut self, cells: usize) {
+        self.local_search_cells = cells;
+    }
+
+    /// Splits the population into `count` islands (fewer than 2 disables the island model) that select and breed
+    /// separately, in parallel; every `migration_interval` generations copies of each island's best `migrants`
+    /// individuals replace the worst of the next island
+    pub fn set_islands(&mut self, count: usize, migration_interval: u32, migrants: usize) {
+        if count < 2 {
+            self.islands = None;
+            return;
+        }
+        self.islands = Some(IslandConfig { count, migration_interval: migration_interval.max(1), migrants });
+        for (i, individual) in self.population.iter_mut().enumerate() {
+            individual.island = i % count;
+        }
+    }
+
+    /// Runs the genetic algorithm for the specified number of generations, reporting progress to a sink
+    /// If generations is 0, runs continuously until user interrupts via callback, evolution stalls or the target
+    /// fitness is reached
+    /// Progress is reported every `status_interval` seconds, with the current best ASCII art if the sink wants it;
+    /// the sink can stop evolution early, and is polled for cancellation and parameter changes every generation
+    pub fn evolve<P: ProgressSink + ?Sized>(&mut self, generations: u32, status_interval: f64, progress: &mut P) -> (Individual, f64) {
+        use std::time::Duration;
+        use web_time::Instant;
 
         let start_time = Instant::now();
         let mut last_update = start_time;
-        let update_interval = Duration::from_secs_f64(status_interval);
+        let mut update_interval = Duration::from_secs_f64(status_interval);
         let continuous_mode = generations == 0;
+        self.stop_reason = StopReason::GenerationLimit;
+        // Best fitness that counted as progress and the generation it was reached
+        let mut stall_best = f64::NEG_INFINITY;
+        // A resumed run carries on counting from the checkpoint
+        let mut generation = std::mem::take(&mut self.start_generation);
+        let mut stall_since = generation;
+
+        // Last generation passed to `ProgressSink::record`
+        let mut recorded = None;
 
-        let mut generation = 0u32;
         loop {
             // Check if we should stop (either reached generation limit or continuous mode interrupted)
             if !continuous_mode && generation >= generations {
                 break;
             }
-            self.evaluate_population();
+            if progress.cancelled() {
+                self.stop_reason = StopReason::Interrupted;
+                break;
+            }
+            self.in_pool(Self::evaluate_population);
 
-            let now = Instant::now();
-            if now.duration_since(last_update) >= update_interval {
+            if self.target_fitness.is_some_and(|target| self.best().fitness >= target) {
+                self.stop_reason = StopReason::TargetReached;
+                break;
+            }
+            if self.stall_generations > 0 {
                 let best_fitness = self.population[0].fitness;
-                let elapsed = now.duration_since(start_time).as_secs_f64();
-
-                // Prepare ASCII art for callback if verbose or UI callback exists
-                let ascii_art = if verbose || ui_callback.is_some() {
-                    Some(self.ascii_generator.individual_to_string(&self.population[0], self.width))
-                } else {
-                    None
-                };
-
-                // Call UI callback if provided
-                if let Some(ref mut callback) = ui_callback {
-                    let should_continue = callback(
-                        generation,
-                        generations,
-                        best_fitness,
-                        elapsed,
-                        self.population_size,
-                        self.thread_count,
-                        self.width,
-                        self.height,
-                        ascii_art.clone()
-                    );
-                    if !should_continue {
-                        println!("Evolution stopped by user");
+                if best_fitness > stall_best + self.stall_epsilon {
+                    stall_best = best_fitness;
+                    stall_since = generation;
+                } else if generation - stall_since >= self.stall_generations {
+                    if !self.restart_on_stall {
+                        self.stop_reason = StopReason::Stalled;
                         break;
                     }
-                } else {
-                    // Fallback to console output
-                    if continuous_mode {
-                        println!("Generation {}: Best fitness = {:.2}% (elapsed: {:.1}s) [Continuous mode - press Ctrl+C to stop]",
-                                 generation, best_fitness * 100.0, elapsed);
-                    } else {
-                        println!("Generation {}: Best fitness = {:.2}% (elapsed: {:.1}s)",
-                                 generation, best_fitness * 100.0, elapsed);
-                    }
+                    // The fresh population is evaluated and starts its own stall window on the next pass
+                    self.restart();
+                    stall_best = f64::NEG_INFINITY;
+                    continue;
+                }
+            }
 
-                    if verbose {
-                        if let Some(ref art) = ascii_art {
-                            println!("Current best ASCII art:\n{}\n", art);
-                        }
-                    }
+            let now = Instant::now();
+            let elapsed = now.duration_since(start_time).as_secs_f64();
+            if progress.records() {
+                progress.record(&self.progress_event(generation, generations, elapsed, progress.records_art(generation)));
+                recorded = Some(generation);
+            }
+            if now.duration_since(last_update) >= update_interval {
+                let mut event = self.progress_event(generation, generations, elapsed, progress.wants_art());
+                event.status_interval = Some(update_interval.as_secs_f64());
+                if progress.wants_cell_quality() {
+                    event.cell_quality = Some(self.cell_quality());
+                }
+                if !progress.report(&event) {
+                    self.stop_reason = StopReason::Interrupted;
+                    break;
                 }
 
                 last_update = now;
             }
 
+            if let Some(tuning) = progress.tune() {
+                if let Some(interval) = tuning.status_interval {
+                    update_interval = Duration::try_from_secs_f64(interval.max(0.0)).unwrap_or(Duration::MAX);
+                }
+                self.apply_tuning(tuning);
+            }
+
             // Always create new generation unless we're stopping
-            self.create_new_generation();
+            self.in_pool(|ga| ga.create_next_generation(generation + 1));
             generation += 1;
         }
 
-        self.evaluate_population();
+        self.in_pool(Self::evaluate_population);
+        self.generations_run = generation;
         let total_elapsed = Instant::now().duration_since(start_time).as_secs_f64();
-        if continuous_mode {
-            println!("Final generation {}: Best fitness = {:.2}% (total time: {:.1}s)",
-                     generation - 1, self.population[0].fitness * 100.0, total_elapsed);
-        } else {
-            println!("Final generation {}: Best fitness = {:.2}% (total time: {:.1}s)",
-                     generations - 1, self.population[0].fitness * 100.0, total_elapsed);
+        // The final population is recorded too, unless the run stopped right after recording it
+        if progress.records() && recorded != Some(generation) {
+            progress.record(&self.progress_event(generation, generations, total_elapsed, progress.records_art(generation)));
+        }
+
+        (self.best().clone(), total_elapsed)
+    }
+
+    /// Describes the run at `generation` of `generations` (0 in continuous mode), with the best art if `with_art` is set
+    fn progress_event(&self, generation: u32, generations: u32, elapsed: f64, with_art: bool) -> ProgressEvent {
+        ProgressEvent {
+            kind: ProgressKind::Generation,
+            step: generation,
+            total_steps: generations,
+            best_fitness: Some(self.best().fitness),
+            fitness_stats: Some(self.fitness_stats()),
+            diversity: Some(self.diversity()),
+            mutation_rate: Some(self.mutation_rate),
+            restarts: Some(self.restarts),
+            crossover_rate: Some(self.crossover_rate),
+            elite_size: Some(self.elite_size),
+            status_interval: None,
+            elapsed,
+            population_size: self.population_size,
+            thread_count: self.thread_count,
+            width: self.width,
+            height: self.height,
+            ascii_art: with_art.then(|| self.ascii_generator.individual_to_string(self.best(), self.width)),
+            cell_quality: None,
         }
+    }
 
-        (self.population[0].clone(), total_elapsed)
+    /// Advances evolution by a single generation and returns the new best individual
+    /// Lets callers such as a browser event loop drive evolution incrementally and show a snapshot between steps
+    pub fn step(&mut self) -> &Individual {
+        if self.generations_run == 0 {
+            self.in_pool(Self::evaluate_population);
+        }
+        let next_generation = self.generations_run + 1;
+        self.in_pool(|ga| ga.create_next_generation(next_generation));
+        self.in_pool(Self::evaluate_population);
+        self.generations_run += 1;
+        &self.population[0]
+    }
+
+    /// Runs `op` inside this instance's thread pool, so its parallel iterators use `thread_count` threads
+    fn in_pool<R: Send>(&mut self, op: impl FnOnce(&mut Self) -> R + Send) -> R {
+        match self.pool.clone() {
+            Some(pool) => pool.install(|| op(self)),
+            None => op(self),
+        }
+    }
+
+    /// Replaces the (evaluated and sorted) population with the next generation using the configured strategy,
+    /// after any local search on the elites
+    fn create_next_generation(&mut self, next_generation: u32) {
+        if self.pareto.is_none() {
+            self.refine_elites();
+        }
+        if self.pareto.is_some() {
+            self.create_nsga2_generation();
+        } else if let Some(islands) = self.islands {
+            self.create_island_generation(islands, next_generation);
+        } else if let Some(alps) = self.alps {
+            self.create_alps_generation(alps, next_generation);
+        } else {
+            match self.replacement_strategy {
+                ReplacementStrategy::Generational => self.create_new_generation(),
+                ReplacementStrategy::Crowding => self.create_crowding_generation(),
+            }
+        }
     }
 
     /// Evaluates the fitness of all individuals in the population using parallel processing
     fn evaluate_population(&mut self) {
-        // Clone chars to avoid borrowing issues and prepare for parallel processing
-        let chars_list: Vec<Vec<u8>> = self.population
-            .iter()
-            .map(|individual| individual.chars.clone())
-            .collect();
+        let mut population = std::mem::take(&mut self.population);
+        self.evaluate_individuals(&mut population);
+        self.population = population;
 
-        // Create Arc references for thread-safe sharing
-        let ascii_gen = Arc::new(self.ascii_generator);
-        let target_img = Arc::new(self.target_image.clone());
-        let width = self.width;
-        let height = self.height;
+        // Sort population by fitness (descending)
+        self.population.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+        self.share_fitness();
+    }
 
-        // Calculate fitness in parallel
-        let total_non_bg = self.total_non_background_pixels;
-        let bg_threshold = self.background_threshold;
-        let fitness_values: Vec<f64> = chars_list
-            .par_iter()
-            .map(|chars| {
-                Self::calculate_fitness_for_chars_static(
-                    chars,
-                    &ascii_gen,
-                    &target_img,
-                    width,
-                    height,
-                    total_non_bg,
-                    bg_threshold
-                )
-            })
+    /// Recomputes the shared fitness of the sorted population when fitness sharing is enabled
+    fn share_fitness(&mut self) {
+        let Some(radius) = self.sharing_radius else { return };
+        let cells = (self.width * self.height) as f64;
+        let sample = rand::seq::index::sample(&mut self.rng, self.population.len(), SHARING_SAMPLE_SIZE.min(self.population.len())).into_vec();
+        let niche_counts = niche_counts(&self.population, &sample, radius * cells);
+        self.shared_fitness = self.population.iter().zip(niche_counts).map(|(individual, count)| individual.fitness / count).collect();
+    }
+
+    /// Calculates and stores the fitness of each given individual in parallel, after enforcing the ink budget
+    /// Each rayon worker keeps its own render buffer so no image is allocated per individual
+    fn evaluate_individuals(&mut self, individuals: &mut [Individual]) {
+        for individual in individuals.iter_mut() {
+            self.repair_ink(individual);
+        }
+        if self.targeted_mutation > 0.0 && self.cell_ceilings.is_empty() {
+            self.cell_ceilings = self.best_cell_scores();
+        }
+
+        // Genomes scored earlier this run take their cached results; only the rest are rendered
+        let hashes: Vec<u64> = individuals.par_iter().map(|individual| FitnessCache::genome_hash(&individual.chars)).collect();
+        let cache = &mut self.fitness_cache;
+        let mut misses: Vec<(&mut Individual, u64)> = individuals
+            .iter_mut()
+            .zip(hashes)
+            .filter_map(|(individual, hash)| (!cache.restore(hash, individual)).then_some((individual, hash)))
             .collect();
 
-        // Update fitness values
-        for (individual, fitness) in self.population.iter_mut().zip(fitness_values.iter()) {
-            individual.fitness = *fitness;
+        #[cfg(feature = "gpu")]
+        if self.evaluate_on_gpu(&mut misses) {
+            for (individual, hash) in misses {
+                self.fitness_cache.insert(hash, individual);
+            }
+            self.fitness_cache.evict();
+            return;
         }
 
-        // Sort population by fitness (descending)
-        self.population.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+        let (context, pool) = (self.fitness_context(), &self.scratch);
+        misses
+            .par_iter_mut()
+            .for_each_init(
+                || pool.lend(),
+                |scratch, (individual, _)| context.evaluate(individual, &mut scratch.buffers),
+            );
+
+        for (individual, hash) in misses {
+            self.fitness_cache.insert(hash, individual);
+        }
+        self.fitness_cache.evict();
+    }
+
+    /// Scores the individuals on the GPU when one is enabled and the settings allow it, returning false to leave them
+    /// to the CPU; a GPU that fails is dropped and the CPU takes over for the rest of the run
+    #[cfg(feature = "gpu")]
+    fn evaluate_on_gpu(&mut self, individuals: &mut [(&mut Individual, u64)]) -> bool {
+        let cpu_only = self.scorer.metric() != FitnessMetric::Overlap
+            || self.scorer.scores_edges()
+            || self.pareto == Some(ParetoObjective::Edge)
+            || self.targeted_mutation > 0.0;
+        let Some(gpu) = self.gpu.as_mut().filter(|_| !cpu_only) else { return false };
+
+        let genomes: Vec<&[u8]> = individuals.iter().map(|(individual, _)| individual.chars.as_slice()).collect();
+        let counts = match gpu.overlap_counts(&genomes) {
+            Ok(counts) => counts,
+            Err(_) => {
+                self.gpu = None;
+                return false;
+            }
+        };
+        let (total, penalty) = (self.scorer.total_non_background_pixels(), self.scorer.overlap_params().false_positive_penalty);
+        for ((individual, _), counts) in individuals.iter_mut().zip(counts) {
+            individual.fitness = if total == 0.0 { 0.0 } else { counts.penalized_fitness(total, penalty) };
+            individual.objective = match self.pareto {
+                Some(ParetoObjective::Ink) => blank_share(self.ascii_generator, &individual.chars),
+                _ => 0.0,
+            };
+        }
+        true
+    }
+
+    /// Scores every character of the set in every cell, returning the best score each cell can reach
+    fn best_cell_scores(&mut self) -> Vec<f32> {
+        self.render_glyph_images();
+        self.scorer.cell_ceilings(&self.glyph_images, (self.width, self.height))
+    }
+
+    /// Renders each character of the set on its own once, for the searches that try every character in a cell
+    fn render_glyph_images(&mut self) {
+        if self.glyph_images.is_empty() {
+            self.glyph_images = (0..self.ascii_generator.charset().len())
+                .map(|code| self.ascii_generator.generate_ascii_image(&[code as u8], 1, 1))
+                .collect();
+        }
+    }
+
+    /// Hill-climbs the elites in place: a few random unlocked cells of each are set to whichever character scores best
+    /// there, judged cell by cell as brute force does, so a cell never gets worse
+    /// Fitness is not refreshed until the next evaluation
+    fn refine_elites(&mut self) {
+        if self.local_search_cells == 0 {
+            return;
+        }
+        self.render_glyph_images();
+
+        let (char_width, char_height) = self.ascii_generator.char_dimensions();
+        let (width, cells, blank) = (self.width as usize, self.local_search_cells, self.ascii_generator.density_ramp()[0]);
+        let (scorer, glyphs, locks) = (&self.scorer, &self.glyph_images, self.locks);
+        let elites = self.elite_size.clamp(1, self.population.len());
+        let base_seed: u64 = self.rng.gen();
+
+        self.population[..elites].par_iter_mut().enumerate().for_each(|(index, individual)| {
+            let mut rng = task_rng(base_seed, index);
+            let len = individual.chars.len();
+            for cell in rand::seq::index::sample(&mut rng, len, cells.min(len)) {
+                if locks.is_some_and(|locks| locks.locked(cell).is_some()) {
+                    continue;
+                }
+                let origin = ((cell % width) as u32 * char_width, (cell / width) as u32 * char_height);
+                let score = |code: u8| scorer.score_cell(&glyphs[code as usize], origin, code == blank);
+                let mut best = (individual.chars[cell], score(individual.chars[cell]));
+                for code in (0..glyphs.len()).map(|code| code as u8) {
+                    let candidate = score(code);
+                    if candidate > best.1 {
+                        best = (code, candidate);
+                    }
+                }
+                individual.chars[cell] = best.0;
+            }
+        });
+    }
+
+    /// Blanks randomly chosen unlocked cells until the individual is within the ink budget
+    fn repair_ink(&mut self, individual: &mut Individual) {
+        let (Some(max_ink), Some(blank)) = (self.max_ink, self.ascii_generator.charset().blank_code()) else { return };
+        let allowed = (individual.chars.len() as f64 * max_ink).floor() as usize;
+        let inked = individual.chars.iter().filter(|&&c| c != blank).count();
+        if inked <= allowed {
+            return;
+        }
+
+        let removable: Vec<usize> = (0..individual.chars.len())
+            .filter(|&i| individual.chars[i] != blank && self.locks.is_none_or(|locks| locks.locked(i).is_none()))
+            .collect();
+        let surplus = (inked - allowed).min(removable.len());
+        for pick in rand::seq::index::sample(&mut self.rng, removable.len(), surplus) {
+            individual.chars[removable[pick]] = blank;
+        }
+    }
+
+    /// Bundles the read-only data needed to score genomes so it can be shared across threads
+    fn fitness_context(&self) -> FitnessContext<'_> {
+        FitnessContext {
+            ascii_generator: self.ascii_generator,
+            scorer: &self.scorer,
+            pareto: self.pareto,
+            cell_ceilings: (self.targeted_mutation > 0.0).then_some(self.cell_ceilings.as_slice()),
+            width: self.width,
+            height: self.height,
+        }
     }
 
     /// Calculates fitness as percentage of matching pixels between ASCII art and target image
     #[cfg(test)]
     fn calculate_fitness(&self, individual: &Individual) -> f64 {
-        self.calculate_fitness_for_chars(&individual.chars)
+        let mut individual = individual.clone();
+        self.fitness_context().evaluate(&mut individual, &mut self.scratch.lend().buffers);
+        individual.fitness
     }
 
-    /// Calculates fitness for a given character array
-    #[cfg(test)]
-    fn calculate_fitness_for_chars(&self, chars: &[u8]) -> f64 {
-        Self::calculate_fitness_for_chars_static(
-            chars,
-            &Arc::new(self.ascii_generator),
-            &Arc::new(self.target_image.clone()),
-            self.width,
-            self.height,
-            self.total_non_background_pixels,
-            self.background_threshold
-        )
+    /// Creates a new generation using selection, crossover, and mutation
+    fn create_new_generation(&mut self) {
+        let mut new_population = Vec::with_capacity(self.population_size);
+
+        // Keep elite individuals
+        for i in 0..self.elite_size {
+            new_population.push(self.population[i].clone());
+        }
+
+        // Generate offspring to fill the rest of the population
+        let offspring_count = self.population_size - new_population.len();
+        for child in self.breed_offspring(offspring_count) {
+            new_population.push(child);
+        }
+
+        self.population = new_population;
     }
 
-    /// Static version of fitness calculation for parallel processing
-    fn calculate_fitness_for_chars_static(
-        chars: &[u8],
-        ascii_generator: &Arc<&AsciiGenerator>,
-        target_image: &Arc<ImageBuffer<Luma<u8>, Vec<u8>>>,
-        width: u32,
-        height: u32,
-        total_non_background_pixels: f64,
-        background_threshold: u8
-    ) -> f64 {
-        // Step 1: Generate ASCII art image from the character array
-        let ascii_image = ascii_generator.generate_ascii_image(chars, width, height);
-
-        // Step 2: Handle edge case of no non-background pixels to compare
-        if total_non_background_pixels == 0.0 {
-            return 0.0;
-        }
-
-        // Step 3: Find the overlapping dimensions to handle any size mismatches
-        let min_width = ascii_image.width().min(target_image.width());
-        let min_height = ascii_image.height().min(target_image.height());
-
-        // Step 4: Calculate fitness based on non-background pixel comparison
-        let mut score = 0.0;
-
-        // Step 5: Compare every pixel in both images
-        for y in 0..min_height {
-            for x in 0..min_width {
-                // Step 6: Extract grayscale values (0-255) from both images
-                let ascii_pixel = ascii_image.get_pixel(x, y)[0];
-                let target_pixel = target_image.get_pixel(x, y)[0];
-
-                // Step 7: Determine if pixels are "lit" (non-background)
-                let ascii_is_lit = ascii_pixel > background_threshold;
-                let target_is_lit = target_pixel > background_threshold;
-
-                // Step 8: Only score based on meaningful pixels (target non-background)
-                if target_is_lit {
-                    // Step 9: Calculate absolute difference between pixel intensities
-                    let diff = (ascii_pixel as i32 - target_pixel as i32).abs();
-
-                    // Step 10: Award points for close matches within tolerance
-                    if diff < 30 { // Tolerance of 30 out of 255 levels
-                        score += 1.0;
+    /// Breeds offspring and lets each one replace the most similar individual if it is at least as fit
+    /// Keeps distinct niches alive instead of letting the fittest genome take over the population
+    fn create_crowding_generation(&mut self) {
+        let mut offspring = self.breed_offspring(self.population_size);
+
+        self.evaluate_individuals(&mut offspring);
+
+        for child in offspring {
+            let closest = self.most_similar_index(&child);
+            if child.fitness >= self.population[closest].fitness {
+                self.population[closest] = child;
+            }
+        }
+    }
+
+    /// Breeds offspring from crowded binary tournaments and keeps the best of parents and offspring (NSGA-II)
+    /// Survivors are chosen by Pareto rank, then by crowding distance to keep the front spread out,
+    /// and sorted by fitness so the fittest individual stays first
+    fn create_nsga2_generation(&mut self) {
+        let (rank, crowding) = crowded_ranking(&self.population);
+        let variation = self.variation();
+        let pairs: Vec<(&Individual, &Individual)> = (0..self.population_size.div_ceil(2))
+            .map(|_| {
+                let parent1 = Self::crowded_tournament(&rank, &crowding, &mut self.rng);
+                let parent2 = Self::crowded_tournament(&rank, &crowding, &mut self.rng);
+                (&self.population[parent1], &self.population[parent2])
+            })
+            .collect();
+        let mut offspring = variation.breed_pairs(&pairs, self.population_size, self.rng.gen());
+
+        self.evaluate_individuals(&mut offspring);
+
+        let mut combined = std::mem::take(&mut self.population);
+        combined.extend(offspring);
+        let (rank, crowding) = crowded_ranking(&combined);
+        let mut order: Vec<usize> = (0..combined.len()).collect();
+        order.sort_by(|&a, &b| rank[a].cmp(&rank[b]).then(crowding[b].partial_cmp(&crowding[a]).unwrap_or(Ordering::Equal)));
+        let mut survivors = vec![false; combined.len()];
+        for &i in order.iter().take(self.population_size) {
+            survivors[i] = true;
+        }
+
+        self.population = combined.into_iter().zip(survivors).filter(|(_, survives)| *survives).map(|(individual, _)| individual).collect();
+        self.population.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+    }
+
+    /// Picks the better of two random individuals: the lower Pareto rank wins, then the larger crowding distance
+    fn crowded_tournament(rank: &[usize], crowding: &[f64], rng: &mut StdRng) -> usize {
+        let a = rng.gen_range(0..rank.len());
+        let b = rng.gen_range(0..rank.len());
+        if rank[b] < rank[a] || (rank[b] == rank[a] && crowding[b] > crowding[a]) {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// Breeds `count` children from parents chosen across the whole population by the selection strategy
+    /// Under fitness sharing, parents are chosen by their shared fitness
+    fn breed_offspring(&mut self, count: usize) -> Vec<Individual> {
+        let variation = self.variation();
+        let shared: Vec<Individual> = if self.shared_fitness.len() == self.population.len() {
+            self.population.iter().zip(&self.shared_fitness).map(|(individual, &fitness)| Individual { fitness, ..individual.clone() }).collect()
+        } else {
+            Vec::new()
+        };
+        let everyone: Vec<&Individual> = if shared.is_empty() { self.population.iter().collect() } else { shared.iter().collect() };
+        let parents = self.selection.select(&everyone, count.div_ceil(2) * 2, self.tournament_size, &mut self.rng);
+        let pairs: Vec<(&Individual, &Individual)> = parents.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+
+        variation.breed_pairs(&pairs, count, self.rng.gen())
+    }
+
+    /// Returns the index of the population member with the smallest Hamming distance to the given individual, the
+    /// first one on ties
+    /// The scan runs in parallel on the instance's pool when called from inside `in_pool`
+    fn most_similar_index(&self, individual: &Individual) -> usize {
+        self.population
+            .par_iter()
+            .enumerate()
+            .min_by_key(|(_, candidate)| candidate.hamming_distance(individual))
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// Builds the next generation island by island, each keeping its size and breeding only from itself on its own
+    /// thread and random stream; migration between islands happens first on generations that are due for it
+    fn create_island_generation(&mut self, islands: IslandConfig, next_generation: u32) {
+        let variation = self.variation();
+        let (selection, tournament_size) = (self.selection, self.tournament_size);
+        let elite_fraction = self.elite_size as f64 / self.population_size as f64;
+
+        // The population is sorted, so every island's members come out best first
+        let mut members: Vec<Vec<Individual>> = vec![Vec::new(); islands.count];
+        for individual in self.population.drain(..) {
+            members[individual.island % islands.count].push(individual);
+        }
+        if next_generation.is_multiple_of(islands.migration_interval) {
+            migrate(&mut members, islands.migrants);
+        }
+
+        let base_seed: u64 = self.rng.gen();
+        self.population = members
+            .into_par_iter()
+            .enumerate()
+            .flat_map_iter(|(index, members)| {
+                if members.is_empty() {
+                    return members;
+                }
+                let mut rng = task_rng(base_seed, index);
+                let elites = ((members.len() as f64 * elite_fraction) as usize).clamp(1, members.len());
+                let pool: Vec<&Individual> = members.iter().collect();
+                let parents = selection.select(&pool, (members.len() - elites).div_ceil(2) * 2, tournament_size, &mut rng);
+
+                let mut next: Vec<Individual> = members[..elites].to_vec();
+                for pair in parents.chunks(2) {
+                    let (child1, child2) = variation.breed(pair[0], pair[1], &mut rng);
+                    for child in [child1, child2] {
+                        if next.len() < members.len() {
+                            next.push(Individual { island: members[0].island, ..child });
+                        }
                     }
-                } else if ascii_is_lit {
-                    // Step 11: Penalize when ASCII is lit but target is background
-                    score -= 0.005; // Small penalty for false positive
                 }
+                next
+            })
+            .collect();
+    }
+
+    /// Builds the next generation layer by layer using the age-layered population structure
+    /// Each layer breeds only from itself and the layer below; the bottom layer is reseeded every age gap
+    fn create_alps_generation(&mut self, alps: AlpsConfig, next_generation: u32) {
+        let individual_size = (self.width * self.height) as usize;
+        let variation = self.variation();
+        let selection = (self.selection, self.tournament_size);
+        let layer_slots = self.population_size / alps.layers;
+        let reseed = next_generation.is_multiple_of(alps.age_gap);
+        let layer_of: Vec<usize> = self.population
+            .iter()
+            .map(|individual| Self::alps_layer_for_age(alps, individual.age))
+            .collect();
+
+        let mut new_population = Vec::with_capacity(self.population_size);
+
+        for layer in 0..alps.layers {
+            let slots = if layer + 1 == alps.layers {
+                self.population_size - layer_slots * (alps.layers - 1)
+            } else {
+                layer_slots
+            };
+
+            if layer == 0 && reseed {
+                for _ in 0..slots {
+                    let mut immigrant = Self::create_random_individual(individual_size, self.init_char, self.background_prob, self.ascii_generator.charset(), &mut self.rng);
+                    if let Some(locks) = self.locks {
+                        locks.apply(&mut immigrant);
+                    }
+                    new_population.push(immigrant);
+                }
+                continue;
             }
-        }
 
-        // Step 12: Return fitness as percentage based on non-background pixels
-        // Clamp to 0.0 minimum to avoid negative fitness
-        (score / total_non_background_pixels).max(0.0)
-    }
+            // Population is sorted by fitness, so layer members come out best-first
+            let members: Vec<&Individual> = self.population
+                .iter()
+                .zip(layer_of.iter())
+                .filter(|(_, &l)| l == layer)
+                .map(|(individual, _)| individual)
+                .collect();
+            let pool: Vec<&Individual> = self.population
+                .iter()
+                .zip(layer_of.iter())
+                .filter(|(_, &l)| l == layer || l + 1 == layer)
+                .map(|(individual, _)| individual)
+                .collect();
+
+            if pool.is_empty() {
+                // Upper layers stay empty until individuals are old enough to reach them
+                continue;
+            }
 
-    /// Creates a new generation using selection, crossover, and mutation
-    fn create_new_generation(&mut self) {
-        let mut new_population = Vec::with_capacity(self.population_size);
+            let target_len = new_population.len() + slots;
+            for elite in members.iter().take((slots / 10).max(1)) {
+                let mut elite = (*elite).clone();
+                elite.age += 1;
+                new_population.push(elite);
+            }
 
-        // Keep elite individuals
-        for i in 0..self.elite_size {
-            new_population.push(self.population[i].clone());
+            while new_population.len() < target_len {
+                for child in Self::breed_aged_offspring(&pool, variation, selection, &mut self.rng) {
+                    if new_population.len() < target_len {
+                        new_population.push(child);
+                    }
+                }
+            }
         }
 
-        // Generate offspring to fill the rest of the population
+        // Slots of empty upper layers are filled by breeding across the whole population
+        let everyone: Vec<&Individual> = self.population.iter().collect();
         while new_population.len() < self.population_size {
-            let parent1 = self.tournament_selection();
-            let parent2 = self.tournament_selection();
-
-            let (mut child1, mut child2) = parent1.crossover(&parent2, self.crossover_rate);
-
-            child1.mutate_with_background_prob(self.mutation_rate, self.background_prob);
-            child2.mutate_with_background_prob(self.mutation_rate, self.background_prob);
-
-            new_population.push(child1);
-            if new_population.len() < self.population_size {
-                new_population.push(child2);
+            for child in Self::breed_aged_offspring(&everyone, variation, selection, &mut self.rng) {
+                if new_population.len() < self.population_size {
+                    new_population.push(child);
+                }
             }
         }
 
         self.population = new_population;
     }
 
-    /// Performs tournament selection to choose a parent for reproduction
-    fn tournament_selection(&self) -> Individual {
-        let mut rng = thread_rng();
-        let tournament_size = 3;
+    /// Breeds two children from a selection pool; children inherit the age of their oldest parent plus one
+    /// `selection` is the selection strategy and tournament size
+    fn breed_aged_offspring(pool: &[&Individual], variation: Variation<'_>, selection: (SelectionStrategy, usize), rng: &mut StdRng) -> [Individual; 2] {
+        let (strategy, tournament_size) = selection;
+        let parents = strategy.select(pool, 2, tournament_size, rng);
+        let (parent1, parent2) = (parents[0], parents[1]);
+        let age = parent1.age.max(parent2.age) + 1;
 
-        let mut best_individual = &self.population[rng.gen_range(0..self.population.len())];
+        let (mut child1, mut child2) = variation.breed(parent1, parent2, rng);
+        child1.age = age;
+        child2.age = age;
 
-        for _ in 1..tournament_size {
-            let candidate = &self.population[rng.gen_range(0..self.population.len())];
-            if candidate.fitness > best_individual.fitness {
-                best_individual = candidate;
-            }
-        }
+        [child1, child2]
+    }
 
-        best_individual.clone()
+    /// Maximum age allowed in an ALPS layer using the polynomial scheme (1, 2, 4, 9, 16, ... times the age gap)
+    fn alps_max_age(alps: AlpsConfig, layer: usize) -> u32 {
+        let factor = if layer < 2 { layer + 1 } else { layer * layer } as u32;
+        alps.age_gap.saturating_mul(factor)
+    }
+
+    /// Returns the lowest layer whose age limit admits the given age (the top layer has no limit)
+    fn alps_layer_for_age(alps: AlpsConfig, age: u32) -> usize {
+        (0..alps.layers)
+            .find(|&layer| age <= Self::alps_max_age(alps, layer))
+            .unwrap_or(alps.layers - 1)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::charset::ALLOWED_CHARS;
+    use crate::progress::NoProgress;
     use image::ImageBuffer;
 
     fn create_test_ascii_generator() -> AsciiGenerator {
         AsciiGenerator::new()
     }
 
+    #[test]
+    fn test_individual_from_art() {
+        let charset = Charset::parse("#. ").unwrap();
+        let (individual, width, height) = Individual::from_art("#.#\n.\n\n", &charset).unwrap();
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(individual.chars, charset.encode("#.#.  ").unwrap());
+
+        assert!(Individual::from_art("#x", &charset).unwrap_err().contains("'x'"));
+        assert!(Individual::from_art("##\n#", &Charset::parse("#.").unwrap()).is_err());
+        assert_eq!(Individual::from_art("#\n \n", &charset).unwrap().2, 2);
+        assert!(Individual::from_art("\n\n", &charset).is_err());
+    }
+
     fn create_test_target_image() -> ImageBuffer<Luma<u8>, Vec<u8>> {
         ImageBuffer::new(20, 20)
     }
@@ -505,9 +2208,9 @@ mod tests {
         assert_eq!(individual.chars.len(), 100);
         assert_eq!(individual.fitness, 0.0);
 
-        // Check that all characters are in valid ASCII range
+        // Check that all codes index into the default character set
         for &ch in &individual.chars {
-            assert!(ch >= 0x20 && ch <= 0x7F);
+            assert!((ch as usize) < ALLOWED_CHARS.len());
         }
     }
 
@@ -516,7 +2219,7 @@ mod tests {
         let parent1 = Individual::new(vec![b'A'; 10]);
         let parent2 = Individual::new(vec![b'B'; 10]);
 
-        let (child1, child2) = parent1.crossover(&parent2, 1.0); // 100% crossover rate
+        let (child1, child2) = parent1.crossover(&parent2, CrossoverOperator::Uniform, 1.0, 3, &mut rand::thread_rng()); // 100% crossover rate
 
         assert_eq!(child1.chars.len(), 10);
         assert_eq!(child2.chars.len(), 10);
@@ -526,6 +2229,56 @@ mod tests {
         assert_eq!(child2.chars, vec![b'A'; 10]);
     }
 
+    #[test]
+    fn test_segment_crossover_operators() {
+        let parent1 = Individual::new((0..20).collect());
+        let parent2 = Individual::new((100..120).collect());
+        let mut rng = StdRng::seed_from_u64(4);
+
+        // Two-point crossover exchanges one contiguous segment, and the children stay complementary
+        let (child1, child2) = parent1.crossover(&parent2, CrossoverOperator::TwoPoint, 1.0, 5, &mut rng);
+        let taken: Vec<usize> = (0..20).filter(|&i| child1.chars[i] >= 100).collect();
+        assert!(taken.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        assert!((0..20).all(|i| child1.chars[i].min(child2.chars[i]) == i as u8 && child1.chars[i] != child2.chars[i]));
+
+        // Block crossover swaps whole blocks; the short last block counts as one
+        let (child1, _) = parent1.crossover(&parent2, CrossoverOperator::Block, 0.5, 5, &mut rng);
+        for block in child1.chars.chunks(CROSSOVER_BLOCK_LEN) {
+            assert!(block.iter().all(|&c| c >= 100) || block.iter().all(|&c| c < 100));
+        }
+        assert_eq!(parent1.crossover(&parent2, CrossoverOperator::Block, 1.0, 5, &mut rng).0.chars, parent2.chars);
+        assert_eq!(parent1.crossover(&parent2, CrossoverOperator::TwoPoint, 0.0, 5, &mut rng).0.chars, parent1.chars);
+
+        // Rectangle crossover exchanges cells spanning an aligned run of columns in a run of rows of the 5x4 grid
+        for _ in 0..20 {
+            let (child1, child2) = parent1.crossover(&parent2, CrossoverOperator::Rectangle, 1.0, 5, &mut rng);
+            let taken: Vec<(usize, usize)> = (0..20).filter(|&i| child1.chars[i] >= 100).map(|i| (i % 5, i / 5)).collect();
+            if let (Some(&(x0, y0)), Some(&(x1, y1))) = (taken.first(), taken.last()) {
+                assert_eq!(taken.len(), (x1 - x0 + 1) * (y1 - y0 + 1));
+                assert!(taken.iter().all(|&(x, y)| (x0..=x1).contains(&x) && (y0..=y1).contains(&y)));
+            }
+            assert!((0..20).all(|i| child1.chars[i].min(child2.chars[i]) == i as u8));
+        }
+
+        // Row and column crossover keep every line whole: each comes entirely from one parent, at its own position
+        let (child1, child2) = parent1.crossover(&parent2, CrossoverOperator::Rows, 0.5, 5, &mut rng);
+        for (row1, row2) in child1.chars.chunks(5).zip(child2.chars.chunks(5)) {
+            assert!(row1.iter().all(|&c| c >= 100) || row1.iter().all(|&c| c < 100));
+            assert!(row1.iter().zip(row2).all(|(&a, &b)| a.min(b) + 100 == a.max(b)));
+        }
+        let (child1, child2) = parent1.crossover(&parent2, CrossoverOperator::Columns, 0.5, 5, &mut rng);
+        for col in 0..5 {
+            let column: Vec<u8> = (0..4).map(|row| child1.chars[row * 5 + col]).collect();
+            assert!(column.iter().all(|&c| c >= 100) || column.iter().all(|&c| c < 100));
+        }
+        assert!((0..20).all(|i| child1.chars[i].min(child2.chars[i]) == i as u8 && child1.chars[i] != child2.chars[i]));
+        assert_eq!(parent1.crossover(&parent2, CrossoverOperator::Columns, 1.0, 5, &mut rng).0.chars, parent2.chars);
+
+        assert_eq!("two-point".parse::<CrossoverOperator>(), Ok(CrossoverOperator::TwoPoint));
+        assert_eq!("columns".parse::<CrossoverOperator>(), Ok(CrossoverOperator::Columns));
+        assert_eq!(CrossoverOperator::Block.to_string(), "block");
+    }
+
     #[test]
     fn test_individual_mutation() {
         let mut individual = Individual::new(vec![b'A'; 100]);
@@ -538,8 +2291,92 @@ mod tests {
 
         // But they should still be from allowed character set
         for &ch in &individual.chars {
-            assert!(ALLOWED_CHARS.contains(&ch), "Character {} (0x{:02X}) not in allowed character set", ch as char, ch);
+            assert!((ch as usize) < ALLOWED_CHARS.len(), "Code {} not in allowed character set", ch);
+        }
+    }
+
+    #[test]
+    fn test_targeted_mutation() {
+        let charset = Charset::default();
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut individual = Individual::new(vec![0; 100]);
+        individual.cell_errors = (0..100).map(|i| if i < 10 { 1.0 } else { 0.0 }).collect();
+
+        // At full bias only the cells with error mutate, ten times as often as the uniform rate
+        let mut mutated = [0usize; 100];
+        for _ in 0..200 {
+            let mut child = individual.clone();
+            child.mutate_targeted(0.05, 1.0, 0.0, &charset, &mut rng);
+            for (count, &c) in mutated.iter_mut().zip(&child.chars) {
+                *count += (c != 0) as usize;
+            }
         }
+        assert!(mutated[10..].iter().all(|&count| count == 0));
+        assert!(mutated[..10].iter().sum::<usize>() > 800);
+
+        // Crossover carries each cell's error along with its character
+        let other = Individual { cell_errors: vec![5.0; 100], ..Individual::new(vec![1; 100]) };
+        let (child, _) = individual.crossover(&other, CrossoverOperator::Uniform, 0.5, 10, &mut rng);
+        for ((&c, &error), i) in child.chars.iter().zip(&child.cell_errors).zip(0..) {
+            assert_eq!(error, if c == 1 { 5.0 } else { individual.cell_errors[i] });
+        }
+
+        // Evaluation measures each cell against its best character: here '#' on the lit cell and space on the dark one
+        let ascii_gen = AsciiGenerator::with_charset(Charset::parse(" #").unwrap());
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target_img = ImageBuffer::from_fn(2 * char_width, char_height, |x, _| Luma([if x < char_width { 255u8 } else { 0 }]));
+        let mut ga = GeneticAlgorithm::new(2, 1, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_targeted_mutation(1.0);
+        let mut scored = [Individual::new(vec![1, 0]), Individual::new(vec![0, 1])];
+        ga.evaluate_individuals(&mut scored);
+        assert_eq!(scored[0].cell_errors, vec![0.0, 0.0]);
+        assert!(scored[1].cell_errors[0] > 0.0 && scored[1].cell_errors[1] > 0.0);
+    }
+
+    #[test]
+    fn test_structural_mutations() {
+        // A 4x3 grid
+        let mut individual = Individual::new((0..12).collect());
+        individual.swap_cells(0, 11);
+        assert_eq!(individual.chars, vec![11, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0]);
+        individual.shift_row(5, 4, true);
+        assert_eq!(individual.chars[4..8], [7, 4, 5, 6]);
+        individual.shift_row(5, 4, false);
+        assert_eq!(individual.chars[4..8], [4, 5, 6, 7]);
+
+        // Blocks are clipped at the grid's edge and may overlap their source
+        individual.copy_block(0, 5, (2, 2), 4);
+        assert_eq!(individual.chars, vec![11, 1, 2, 3, 4, 11, 1, 7, 8, 4, 5, 0]);
+        individual.copy_block(0, 3, (3, 3), 4);
+        assert_eq!(individual.chars, vec![11, 1, 2, 11, 4, 11, 1, 4, 8, 4, 5, 8]);
+
+        // Swap mutation only rearranges characters, and every operator keeps the grid's size
+        let charset = Charset::default();
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut variation = Variation {
+            crossover: CrossoverOperator::Uniform,
+            width: 4,
+            crossover_rate: 0.0,
+            mutation: MutationOperator::Swap,
+            mutation_rate: 0.5,
+            targeted_mutation: 0.0,
+            background_prob: 0.0,
+            charset: &charset,
+            locks: None,
+        };
+        let mut swapped = Individual::new((0..12).collect());
+        variation.mutate(&mut swapped, &mut rng);
+        assert_ne!(swapped.chars, (0..12).collect::<Vec<u8>>());
+        swapped.chars.sort_unstable();
+        assert_eq!(swapped.chars, (0..12).collect::<Vec<u8>>());
+        for operator in ["point", "shift", "block-copy", "mixed"] {
+            variation.mutation = operator.parse().unwrap();
+            let mut mutated = Individual::new((0..12).collect());
+            variation.mutate(&mut mutated, &mut rng);
+            assert_eq!(mutated.chars.len(), 12);
+            assert!(mutated.chars.iter().all(|&c| (c as usize) < charset.len()));
+        }
+        assert_eq!(MutationOperator::BlockCopy.to_string(), "block-copy");
     }
 
     #[test]
@@ -549,7 +2386,7 @@ mod tests {
 
             // All characters should be from allowed character set
             for &c in &individual.chars {
-                assert!(ALLOWED_CHARS.contains(&c), "Character {} (0x{:02X}) not in allowed character set", c as char, c);
+                assert!((c as usize) < ALLOWED_CHARS.len(), "Code {} not in allowed character set", c);
             }
         }
     }
@@ -557,11 +2394,11 @@ mod tests {
     #[test]
     fn test_individual_background_prob_chars_valid() {
         for _ in 0..10 {  // Run multiple times to catch any random issues
-            let individual = Individual::new_random_with_background_prob(50, 0.5);
+            let individual = Individual::new_random_with_background_prob(50, 0.5, &Charset::default(), &mut rand::thread_rng());
 
             // All characters should be from allowed character set
             for &c in &individual.chars {
-                assert!(ALLOWED_CHARS.contains(&c), "Character {} (0x{:02X}) not in allowed character set", c as char, c);
+                assert!((c as usize) < ALLOWED_CHARS.len(), "Code {} not in allowed character set", c);
             }
         }
     }
@@ -570,11 +2407,11 @@ mod tests {
     fn test_mutation_with_background_prob_chars_valid() {
         for _ in 0..10 {  // Run multiple times to catch any random issues
             let mut individual = Individual::new(vec![b' '; 50]);
-            individual.mutate_with_background_prob(1.0, 0.3); // 100% mutation rate
+            individual.mutate_with_background_prob(1.0, 0.3, &Charset::default(), &mut rand::thread_rng()); // 100% mutation rate
 
             // All characters should be from allowed character set
             for &c in &individual.chars {
-                assert!(ALLOWED_CHARS.contains(&c), "Character {} (0x{:02X}) not in allowed character set", c as char, c);
+                assert!((c as usize) < ALLOWED_CHARS.len(), "Code {} not in allowed character set", c);
             }
         }
     }
@@ -590,7 +2427,7 @@ mod tests {
 
         // Check for unexpected digits
         let digits: Vec<u8> = ALLOWED_CHARS.iter()
-            .filter(|&&c| c >= b'0' && c <= b'9')
+            .filter(|c| c.is_ascii_digit())
             .copied()
             .collect();
         println!("Digits in ALLOWED_CHARS: {:?}", digits.iter().map(|&c| c as char).collect::<Vec<_>>());
@@ -603,15 +2440,16 @@ mod tests {
     fn test_debug_character_generation_stress() {
         // Generate many individuals and check for any invalid characters
         for trial in 0..100 {
-            let individual = Individual::new_random_with_background_prob(100, 0.5);
+            let individual = Individual::new_random_with_background_prob(100, 0.5, &Charset::default(), &mut rand::thread_rng());
 
-            for (pos, &c) in individual.chars.iter().enumerate() {
-                if !ALLOWED_CHARS.contains(&c) {
-                    panic!("Trial {}, Position {}: Invalid character {} (0x{:02X}) found!", trial, pos, c as char, c);
+            for (pos, &code) in individual.chars.iter().enumerate() {
+                if code as usize >= ALLOWED_CHARS.len() {
+                    panic!("Trial {}, Position {}: Invalid code {} found!", trial, pos, code);
                 }
 
                 // Specifically check for digits other than '8'
-                if (c >= b'0' && c <= b'9') && c != b'8' {
+                let c = ALLOWED_CHARS[code as usize];
+                if c.is_ascii_digit() && c != b'8' {
                     panic!("Trial {}, Position {}: Unexpected digit {} found!", trial, pos, c as char);
                 }
             }
@@ -623,12 +2461,12 @@ mod tests {
     fn test_ascii_art_with_percent_characters() {
         // Create an individual with % characters that could cause format string issues
         // Using only characters from ALLOWED_CHARS: % @ # $ O X
-        let chars_with_percent = vec![b'%', b'%', b'%', b'@', b'#', b'%', b'$', b'%', b'O'];
+        let chars_with_percent = Charset::default().encode("%%%@#%$%O").unwrap();
         let individual = Individual::new(chars_with_percent);
 
         // Verify all characters are valid
         for &c in &individual.chars {
-            assert!(ALLOWED_CHARS.contains(&c), "Character {} (0x{:02X}) not in allowed character set", c as char, c);
+            assert!((c as usize) < ALLOWED_CHARS.len(), "Code {} not in allowed character set", c);
         }
 
         // Test that converting to string works properly
@@ -665,10 +2503,44 @@ mod tests {
         let target_img = create_test_target_image();
 
         let ga = GeneticAlgorithm::new(2, 2, 10, &ascii_gen, &target_img, 1, None, false);
-        let individual = Individual::new(vec![b' ', b' ', b' ', b' ']); // All spaces
+        let individual = Individual::new(vec![0; 4]); // All spaces (code 0 in the default set)
 
         let fitness = ga.calculate_fitness(&individual);
-        assert!(fitness >= 0.0 && fitness <= 1.0);
+        assert!((0.0..=1.0).contains(&fitness));
+    }
+
+    #[test]
+    fn test_edge_weight_rewards_matching_outlines() {
+        let ascii_gen = create_test_ascii_generator();
+        let chars = ascii_gen.charset().encode("#O.+").unwrap();
+        let target = ascii_gen.generate_ascii_image(&chars, 2, 2);
+
+        let mut ga = GeneticAlgorithm::new(2, 2, 10, &ascii_gen, &target, 1, None, false);
+        ga.set_edge_weight(1.0);
+        // Rendering the target's own characters agrees on both pixels and edges
+        assert_eq!(ga.calculate_fitness(&Individual::new(chars)), 1.0);
+
+        let blank = ascii_gen.charset().blank_code().unwrap();
+        assert_eq!(ga.calculate_fitness(&Individual::new(vec![blank; 4])), 0.0);
+    }
+
+    #[test]
+    fn test_rates_control_variation() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let mut ga = GeneticAlgorithm::new(2, 2, 10, &ascii_gen, &target_img, 1, None, false);
+        ga.set_crossover_rate(0.0);
+        ga.set_mutation_rate(0.0);
+
+        // Without crossover or mutation, children are exact copies of their parents
+        let parent1 = Individual::new(vec![1, 2, 3, 4]);
+        let parent2 = Individual::new(vec![5, 6, 7, 8]);
+        let (child1, child2) = ga.variation().breed(&parent1, &parent2, &mut ga.rng);
+        assert_eq!((child1.chars, child2.chars), (parent1.chars.clone(), parent2.chars.clone()));
+
+        ga.set_crossover_rate(1.0);
+        let (child1, _) = ga.variation().breed(&parent1, &parent2, &mut ga.rng);
+        assert_eq!(child1.chars, parent2.chars);
     }
 
     #[test]
@@ -682,19 +2554,729 @@ mod tests {
         ga.population[0].fitness = 0.9;
         ga.population[1].fitness = 0.1;
 
-        let selected = ga.tournament_selection();
-        assert!(selected.fitness >= 0.0);
+        let everyone: Vec<&Individual> = ga.population.iter().collect();
+        let selected = SelectionStrategy::Tournament.select(&everyone, 1, DEFAULT_TOURNAMENT_SIZE, &mut ga.rng);
+        assert!(selected[0].fitness >= 0.0);
+
+        // A tournament as large as the population almost always finds the best; size 1 picks at random
+        let mut rng = StdRng::seed_from_u64(2);
+        let wins = (0..20).filter(|_| tournament(&everyone, 50, &mut rng).fitness == 0.9).count();
+        assert_eq!(wins, 20);
+        let wins = (0..200).filter(|_| tournament(&everyone, 1, &mut rng).fitness == 0.9).count();
+        assert!(wins < 60, "{} random picks were the best of 10", wins);
+    }
+
+    #[test]
+    fn test_selection_strategies() {
+        let scored = |fitness: f64| Individual { fitness, ..Individual::new(vec![]) };
+        let individuals = [scored(0.0), scored(0.5), scored(0.0), scored(0.5)];
+        let pool: Vec<&Individual> = individuals.iter().collect();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Fitness-proportionate strategies never pick zero-fitness individuals
+        for strategy in [SelectionStrategy::Roulette, SelectionStrategy::Sus] {
+            assert!(strategy.select(&pool, 50, DEFAULT_TOURNAMENT_SIZE, &mut rng).iter().all(|parent| parent.fitness == 0.5));
+        }
+        // SUS gives the two equally fit individuals exactly equal shares
+        let parents = SelectionStrategy::Sus.select(&pool, 8, DEFAULT_TOURNAMENT_SIZE, &mut rng);
+        assert_eq!(parents.iter().filter(|&&parent| std::ptr::eq(parent, pool[1])).count(), 4);
+
+        // Rank weights are 1 to 4 whatever the fitness values, so the worst still gets picked sometimes
+        assert_eq!(SelectionStrategy::Rank.cumulative_weights(&pool), vec![1.0, 4.0, 6.0, 10.0]);
+        let zeros = [scored(0.0), scored(0.0)];
+        assert_eq!(SelectionStrategy::Roulette.cumulative_weights(&zeros.iter().collect::<Vec<_>>()), vec![1.0, 2.0]);
+
+        for name in ["tournament", "roulette", "rank", "sus"] {
+            assert_eq!(name.parse::<SelectionStrategy>().unwrap().to_string(), name);
+        }
+        assert!("random".parse::<SelectionStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let a = Individual::new(vec![b'A', b'B', b'C', b'D']);
+        let b = Individual::new(vec![b'A', b'X', b'C', b'Y']);
+        assert_eq!(a.hamming_distance(&b), 2);
+        assert_eq!(a.hamming_distance(&a), 0);
+    }
+
+    #[test]
+    fn test_replacement_strategy_from_str() {
+        assert_eq!("crowding".parse::<ReplacementStrategy>(), Ok(ReplacementStrategy::Crowding));
+        assert_eq!("Generational".parse::<ReplacementStrategy>(), Ok(ReplacementStrategy::Generational));
+        assert!("elitist".parse::<ReplacementStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_crowding_generation_keeps_best() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+
+        let mut ga = GeneticAlgorithm::new(2, 2, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_replacement_strategy(ReplacementStrategy::Crowding);
+        ga.evaluate_population();
+        let best_before = ga.population[0].fitness;
+
+        ga.create_crowding_generation();
+        ga.evaluate_population();
+
+        // Offspring only replace individuals they beat, so the best fitness can never drop
+        assert_eq!(ga.population.len(), 20);
+        assert!(ga.population[0].fitness >= best_before);
+    }
+
+    #[cfg(feature = "gpu")]
+    #[test]
+    fn test_gpu_fitness_matches_cpu() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target_img = ImageBuffer::from_fn(5 * char_width, 3 * char_height, |x, y| Luma([((x * 37 + y * 11) % 256) as u8]));
+        let weights = PixelWeights::center_weighted(target_img.width(), target_img.height(), 2.0);
+
+        let mut ga = GeneticAlgorithm::new(5, 3, 30, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(8);
+        if let Err(e) = ga.enable_gpu() {
+            eprintln!("skipping GPU parity check: {}", e);
+            return;
+        }
+
+        let check = |ga: &mut GeneticAlgorithm| {
+            let mut population = ga.population.clone();
+            ga.evaluate_individuals(&mut population);
+            assert!(ga.gpu_adapter().is_some(), "the GPU evaluated the population");
+            for individual in &population {
+                assert_eq!(individual.fitness, ga.calculate_fitness(individual));
+            }
+        };
+        check(&mut ga);
+        // Changed scoring settings are uploaded again
+        ga.set_overlap_params(OverlapParams { tolerance: 12, false_positive_penalty: 0.02 });
+        check(&mut ga);
+        ga.set_pixel_weights(&weights);
+        check(&mut ga);
+    }
+
+    #[test]
+    fn test_crowding_is_reproducible_across_thread_counts() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+
+        let run = |threads: usize| {
+            let mut ga = GeneticAlgorithm::new(2, 2, 30, &ascii_gen, &target_img, threads, None, false);
+            ga.set_seed(11);
+            ga.set_replacement_strategy(ReplacementStrategy::Crowding);
+            for _ in 0..5 {
+                ga.step();
+            }
+            ga.population.iter().map(|individual| individual.chars.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(run(1), run(4));
+
+        // Ties go to the first of the nearest individuals, as a serial scan would pick
+        let mut ga = GeneticAlgorithm::new(2, 2, 20, &ascii_gen, &target_img, 4, None, false);
+        for (i, individual) in ga.population.iter_mut().enumerate() {
+            individual.chars = if i == 3 || i == 7 { vec![1, 1, 1, 2] } else { vec![0; 4] };
+        }
+        let probe = Individual::new(vec![1, 1, 1, 1]);
+        assert_eq!(ga.in_pool(|ga| ga.most_similar_index(&probe)), 3);
+    }
+
+    #[test]
+    fn test_fitness_cache() {
+        let scored = |fitness: f64| Individual { fitness, ..Individual::new(vec![]) };
+        let mut cache = FitnessCache::new(2);
+        cache.insert(1, &scored(0.1));
+        cache.insert(2, &scored(0.2));
+        let mut individual = Individual::new(vec![]);
+        assert!(cache.restore(1, &mut individual));
+        assert_eq!(individual.fitness, 0.1);
+
+        // The least recently used entry goes first once the capacity is exceeded
+        cache.insert(3, &scored(0.3));
+        cache.evict();
+        assert!(!cache.restore(2, &mut individual));
+        assert!(cache.restore(1, &mut individual) && cache.restore(3, &mut individual));
+
+        // Evaluation takes cached scores instead of rendering the genome again
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let mut ga = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(2);
+        ga.evaluate_population();
+        let fitness = ga.population[0].fitness;
+        let hash = FitnessCache::genome_hash(&ga.population[0].chars);
+        ga.fitness_cache.entries.get_mut(&hash).unwrap().fitness = 5.0;
+        ga.evaluate_population();
+        assert_eq!(ga.population[0].fitness, 5.0);
+
+        // Changing how genomes score discards the cache
+        ga.set_fitness_metric(FitnessMetric::Overlap);
+        ga.evaluate_population();
+        assert_eq!(ga.best().fitness, fitness);
+    }
+
+    #[test]
+    fn test_scratch_pool_reuses_buffers() {
+        let pool = ScratchPool::default();
+        {
+            let mut first = pool.lend();
+            let _second = pool.lend();
+            first.buffers.render = ImageBuffer::new(4, 3);
+        }
+        assert_eq!(pool.0.lock().unwrap().len(), 2);
+
+        // A returned set is lent again with its allocation intact instead of a new one being made
+        let rendered = (0..2).map(|_| pool.lend().buffers.render.dimensions()).collect::<Vec<_>>();
+        assert!(rendered.contains(&(4, 3)));
+        assert_eq!(pool.0.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_fitness_sharing() {
+        // Three copies of one genome crowd their niche; the distant fourth has it to itself
+        let population = [vec![0; 10], vec![0; 10], vec![0; 10], vec![1; 10]].map(Individual::new);
+        assert_eq!(niche_counts(&population, &[0, 1, 2, 3], 5.0), vec![3.0, 3.0, 3.0, 1.0]);
+        // A sample is scaled up to the rest of the population
+        assert_eq!(niche_counts(&population, &[0, 3], 5.0), vec![1.0, 2.5, 2.5, 1.0]);
+
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let mut ga = GeneticAlgorithm::new(2, 2, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_fitness_sharing(0.5);
+        ga.evaluate_population();
+        assert_eq!(ga.shared_fitness.len(), 20);
+        assert!(ga.population.iter().zip(&ga.shared_fitness).all(|(individual, &shared)| shared <= individual.fitness));
+        assert_eq!(ga.breed_offspring(20).len(), 20);
+    }
+
+    #[test]
+    fn test_crowded_ranking() {
+        let scored = |fitness: f64, objective: f64| Individual { fitness, objective, ..Individual::new(vec![]) };
+        let individuals = vec![scored(0.9, 0.1), scored(0.5, 0.5), scored(0.4, 0.4), scored(0.1, 0.9), scored(0.6, 0.45)];
+
+        let fronts = non_dominated_fronts(&individuals);
+        assert_eq!(fronts.len(), 2);
+        assert_eq!(fronts[1], vec![2]);
+
+        let (rank, crowding) = crowded_ranking(&individuals);
+        assert_eq!(rank, vec![0, 0, 1, 0, 0]);
+        // Front extremes are always kept; (0.6, 0.45) sits in a tighter gap than (0.5, 0.5)
+        assert!(crowding[0].is_infinite() && crowding[3].is_infinite());
+        assert!(crowding[1] > crowding[4]);
+    }
+
+    #[test]
+    fn test_nsga2_finds_ink_trade_offs() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target_img = ImageBuffer::from_fn(4 * char_width, 2 * char_height, |x, _| Luma([if x < 2 * char_width { 255u8 } else { 0 }]));
+
+        let mut ga = GeneticAlgorithm::new(4, 2, 30, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(11);
+        ga.set_pareto_objective(ParetoObjective::Ink);
+        for _ in 0..20 {
+            ga.step();
+        }
+
+        let front = ga.pareto_front();
+        assert!(front.len() > 1);
+        assert_eq!(front[0].fitness, ga.best().fitness);
+        // Along the front, giving up fitness must buy sparser art
+        for pair in front.windows(2) {
+            assert!(pair[0].fitness > pair[1].fitness && pair[0].objective < pair[1].objective);
+        }
+        assert_eq!(ga.population.len(), 30);
+        assert_eq!("Edge".parse::<ParetoObjective>(), Ok(ParetoObjective::Edge));
+    }
+
+    #[test]
+    fn test_local_search_refines_elites() {
+        // '#' belongs on the lit left cell and space on the dark right one
+        let ascii_gen = AsciiGenerator::with_charset(Charset::parse(" #").unwrap());
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target_img = ImageBuffer::from_fn(2 * char_width, char_height, |x, _| Luma([if x < char_width { 255u8 } else { 0 }]));
+        let mut ga = GeneticAlgorithm::new(2, 1, 20, &ascii_gen, &target_img, 1, None, false);
+        for individual in &mut ga.population {
+            individual.chars = vec![0, 1];
+        }
+        ga.set_local_search(2);
+        ga.evaluate_population();
+        ga.refine_elites();
+
+        // Only the elites are searched
+        assert_eq!(ga.population[..2].iter().map(|individual| individual.chars.clone()).collect::<Vec<_>>(), vec![vec![1, 0]; 2]);
+        assert!(ga.population[2..].iter().all(|individual| individual.chars == [0, 1]));
+
+        // Locked cells are left alone
+        let locks = LockMask::new(vec![Some(0), None]);
+        ga.set_locks(&locks);
+        ga.population[0].chars = vec![0, 1];
+        ga.refine_elites();
+        assert_eq!(ga.population[0].chars, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_island_model() {
+        let scored = |fitness: f64, island: usize| Individual { fitness, island, ..Individual::new(vec![]) };
+        let mut islands = vec![vec![scored(0.9, 0), scored(0.5, 0), scored(0.1, 0)], vec![scored(0.4, 1), scored(0.3, 1), scored(0.2, 1)]];
+
+        // Each island's best replace the worst of the next one, in a ring
+        migrate(&mut islands, 2);
+        let fitnesses = |members: &[Individual]| members.iter().map(|individual| individual.fitness).collect::<Vec<_>>();
+        assert_eq!(fitnesses(&islands[0]), vec![0.9, 0.4, 0.3]);
+        assert_eq!(fitnesses(&islands[1]), vec![0.9, 0.5, 0.4]);
+        assert!(islands.iter().enumerate().all(|(i, members)| members.iter().all(|individual| individual.island == i)));
+
+        // Islands keep their sizes through breeding and migration
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let mut ga = GeneticAlgorithm::new(2, 2, 22, &ascii_gen, &target_img, 1, None, false);
+        ga.set_islands(4, 2, 1);
+        // Reseeding regenerates the population without losing the island assignment
+        ga.set_seed(7);
+        for generation in 1..=4 {
+            ga.evaluate_population();
+            ga.create_island_generation(ga.islands.unwrap(), generation);
+            let sizes: Vec<usize> = (0..4).map(|island| ga.population.iter().filter(|individual| individual.island == island).count()).collect();
+            assert_eq!(sizes, vec![6, 6, 5, 5]);
+        }
+    }
+
+    #[test]
+    fn test_alps_layer_for_age() {
+        let alps = AlpsConfig { layers: 4, age_gap: 5 };
+
+        // Layer limits are 5, 10, 20 and unbounded for the top layer
+        assert_eq!(GeneticAlgorithm::alps_layer_for_age(alps, 0), 0);
+        assert_eq!(GeneticAlgorithm::alps_layer_for_age(alps, 5), 0);
+        assert_eq!(GeneticAlgorithm::alps_layer_for_age(alps, 6), 1);
+        assert_eq!(GeneticAlgorithm::alps_layer_for_age(alps, 20), 2);
+        assert_eq!(GeneticAlgorithm::alps_layer_for_age(alps, 1000), 3);
+    }
+
+    #[test]
+    fn test_alps_generation_reseeds_bottom_layer() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+
+        let mut ga = GeneticAlgorithm::new(2, 2, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_alps(2, 3);
+        let alps = ga.alps.unwrap();
+        for individual in &mut ga.population {
+            individual.age = 7;
+        }
+
+        ga.create_alps_generation(alps, 3);
+
+        // Bottom layer slots are fresh random genomes, the top layer keeps aging
+        assert_eq!(ga.population.len(), 20);
+        assert_eq!(ga.population.iter().filter(|i| i.age == 0).count(), 10);
+        assert!(ga.population.iter().filter(|i| i.age > 0).all(|i| i.age == 8));
+    }
+
+    #[test]
+    fn test_same_seed_gives_identical_runs() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target_img = ImageBuffer::from_fn(3 * char_width, 2 * char_height, |x, y| Luma([((x * 7 + y * 13) % 256) as u8]));
+
+        let run = |seed: u64, threads: usize| {
+            let mut ga = GeneticAlgorithm::new(3, 2, 20, &ascii_gen, &target_img, threads, None, false);
+            ga.set_seed(seed);
+            ga.evolve(5, 0.0, &mut NoProgress).0
+        };
+
+        let first = run(42, 1);
+        let second = run(42, 4);
+        assert_eq!(first.chars, second.chars);
+        assert_eq!(first.fitness, second.fitness);
+
+        // Local search, islands and NSGA-II breeding all run in parallel, each task on its own seeded stream
+        let run_parallel = |threads: usize, configure: &dyn Fn(&mut GeneticAlgorithm)| {
+            let mut ga = GeneticAlgorithm::new(3, 2, 20, &ascii_gen, &target_img, threads, None, false);
+            ga.set_seed(42);
+            configure(&mut ga);
+            for _ in 0..5 {
+                ga.step();
+            }
+            ga.population.iter().map(|individual| individual.chars.clone()).collect::<Vec<_>>()
+        };
+        let configurations: [&dyn Fn(&mut GeneticAlgorithm); 3] = [
+            &|ga| ga.set_local_search(3),
+            &|ga| ga.set_islands(3, 2, 1),
+            &|ga| ga.set_pareto_objective(ParetoObjective::Ink),
+        ];
+        for configure in configurations {
+            assert_eq!(run_parallel(1, configure), run_parallel(3, configure));
+        }
+    }
+
+    #[test]
+    fn test_custom_charset_respected_by_population_and_mutation() {
+        let charset = Charset::parse("@#. ").unwrap();
+        let ascii_gen = AsciiGenerator::with_charset(charset.clone());
+        let target_img = create_test_target_image();
+
+        let mut ga = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(3);
+        ga.mutation_rate = 1.0;
+        ga.create_new_generation();
+
+        for individual in &ga.population {
+            assert!(individual.chars.iter().all(|&c| (c as usize) < charset.len()));
+        }
+    }
+
+    #[test]
+    fn test_locked_cells_survive_evolution() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let locks = LockMask::new(vec![Some(4), None, None, None, Some(7), None, None, None, None]);
+
+        let mut ga = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(3);
+        ga.set_locks(&locks);
+        ga.set_alps(2, 1);
+        ga.mutation_rate = 1.0;
+        for _ in 0..3 {
+            ga.step();
+        }
+
+        for individual in &ga.population {
+            assert_eq!((individual.chars[0], individual.chars[4]), (4, 7));
+        }
+    }
+
+    #[test]
+    fn test_max_ink_repairs_population() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let blank = ascii_gen.charset().blank_code().unwrap();
+
+        let mut ga = GeneticAlgorithm::new(4, 5, 20, &ascii_gen, &target_img, 1, Some('#'), false);
+        ga.set_seed(8);
+        ga.set_max_ink(0.25);
+        ga.step();
+        ga.step();
+
+        for individual in &ga.population {
+            assert!(individual.chars.iter().filter(|&&c| c != blank).count() <= 5);
+        }
+    }
+
+    #[test]
+    fn test_instances_own_their_thread_pools() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+
+        // Instances with different thread counts coexist, each running its parallel work on its own pool
+        let mut three = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target, 3, None, false);
+        let mut five = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target, 5, None, false);
+        assert_eq!(three.in_pool(|_| rayon::current_num_threads()), 3);
+        assert_eq!(five.in_pool(|_| rayon::current_num_threads()), 5);
+        five.step();
+        three.step();
+
+        // Zero threads means one per logical core
+        let mut auto = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target, 0, None, false);
+        assert_eq!(auto.thread_count(), available_threads());
+        assert_eq!(auto.in_pool(|_| rayon::current_num_threads()), available_threads());
+    }
+
+    #[test]
+    fn test_step_matches_evolve() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+
+        let mut stepped = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        stepped.set_seed(3);
+        for _ in 0..4 {
+            stepped.step();
+        }
+        assert_eq!(stepped.generations_run(), 4);
+
+        let mut evolved = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        evolved.set_seed(3);
+        let (best, _) = evolved.evolve(4, 1.0, &mut NoProgress);
+        assert_eq!(stepped.best().chars, best.chars);
+    }
+
+    #[test]
+    fn test_stall_generations_stops_evolution() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        ga.set_seed(3);
+        ga.evolve(6, 1.0, &mut NoProgress);
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (6, StopReason::GenerationLimit));
+
+        // No gain can exceed an epsilon of 1, so the run stalls once the window has passed, even in continuous mode
+        ga.set_stall_generations(3, 1.0);
+        ga.evolve(0, 1.0, &mut NoProgress);
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (3, StopReason::Stalled));
+
+        // A window longer than the run never triggers
+        ga.set_stall_generations(10, 1.0);
+        ga.evolve(4, 1.0, &mut NoProgress);
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (4, StopReason::GenerationLimit));
+    }
+
+    #[test]
+    fn test_target_fitness_stops_evolution() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+
+        // Any population meets a target of 0 before the first generation is bred
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        ga.set_seed(3);
+        ga.set_target_fitness(Some(0.0));
+        ga.evolve(0, 1.0, &mut NoProgress);
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (0, StopReason::TargetReached));
+
+        // An unreachable target leaves the generation limit in charge
+        ga.set_target_fitness(Some(1.5));
+        let (best, _) = ga.evolve(5, 1.0, &mut NoProgress);
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (5, StopReason::GenerationLimit));
+
+        // A reachable target stops the run at the first generation meeting it
+        let goal = best.fitness;
+        ga.set_seed(3);
+        ga.set_target_fitness(Some(goal));
+        let (reached, _) = ga.evolve(0, 1.0, &mut NoProgress);
+        assert_eq!(ga.stop_reason(), StopReason::TargetReached);
+        assert!(reached.fitness >= goal && ga.generations_run() <= 5);
+    }
+
+    #[test]
+    fn test_progress_sink_can_stop_evolution() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+
+        // With no status interval every generation is reported, and the sink stops the run at the third
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        ga.set_seed(3);
+        let mut steps = Vec::new();
+        ga.evolve(10, 0.0, &mut |event: &ProgressEvent| {
+            assert_eq!((event.kind, event.total_steps, event.width, event.height), (ProgressKind::Generation, 10, 5, 5));
+            assert!(event.best_fitness.is_some() && event.ascii_art.is_some());
+            let stats = event.fitness_stats.unwrap();
+            assert!(stats.best >= stats.median && stats.median >= stats.worst);
+            assert!(stats.best >= stats.mean && stats.mean >= stats.worst);
+            steps.push(event.step);
+            event.step < 2
+        });
+        assert_eq!(steps, vec![0, 1, 2]);
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (2, StopReason::Interrupted));
+    }
+
+    #[test]
+    fn test_record_receives_every_generation() {
+        struct Recorder(Vec<ProgressEvent>, Option<u32>);
+        impl ProgressSink for Recorder {
+            fn report(&mut self, _event: &ProgressEvent) -> bool {
+                true
+            }
+            fn records(&self) -> bool {
+                true
+            }
+            fn record(&mut self, event: &ProgressEvent) {
+                self.0.push(event.clone());
+            }
+            fn records_art(&self, step: u32) -> bool {
+                self.1.is_some_and(|every| step.is_multiple_of(every))
+            }
+        }
+
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = ImageBuffer::from_fn(5 * char_width, 5 * char_height, |x, y| Luma([((x * 7 + y * 13) % 256) as u8]));
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        ga.set_seed(3);
+
+        // A status interval longer than the run still records each generation, and the final population once
+        let mut recorder = Recorder(Vec::new(), None);
+        ga.evolve(4, 60.0, &mut recorder);
+        assert_eq!(recorder.0.iter().map(|event| event.step).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert!(recorder.0.iter().all(|event| event.ascii_art.is_none() && event.mutation_rate == Some(ga.mutation_rate())));
+        assert!(recorder.0.iter().all(|event| event.diversity.is_some_and(|diversity| diversity > 0.0 && diversity <= 1.0)));
+
+        // Art is only rendered for the generations the sink asks for
+        let mut recorder = Recorder(Vec::new(), Some(2));
+        ga.evolve(4, 60.0, &mut recorder);
+        let with_art: Vec<u32> = recorder.0.iter().filter(|event| event.ascii_art.is_some()).map(|event| event.step).collect();
+        assert_eq!(with_art, vec![0, 2, 4]);
+
+        // A population of clones has no diversity
+        let clone = ga.population[0].clone();
+        ga.population.fill(clone);
+        assert_eq!(ga.diversity(), 0.0);
+    }
+
+    #[test]
+    fn test_cell_quality_rates_cells_against_their_best() {
+        let ascii_gen = create_test_ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = ImageBuffer::from_fn(4 * char_width, 3 * char_height, |x, y| Luma([((x * 7 + y * 13) % 256) as u8]));
+        let mut ga = GeneticAlgorithm::new(4, 3, 20, &ascii_gen, &target, 1, None, false);
+        ga.set_seed(4);
+        ga.evolve(1, 0.0, &mut NoProgress);
+
+        let quality = ga.cell_quality();
+        assert_eq!(quality.len(), 12);
+        assert!(quality.iter().all(|&q| (0.0..=1.0).contains(&q)));
+        assert!(quality.contains(&0.0));
+
+        // Blank art on a blank target cannot be bettered anywhere
+        let blank_target = ImageBuffer::new(5 * char_width, 5 * char_height);
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &blank_target, 1, None, false);
+        ga.population[0] = Individual::new(vec![ascii_gen.density_ramp()[0]; 25]);
+        ga.best_ever = None;
+        assert_eq!(ga.cell_quality(), vec![1.0; 25]);
+    }
+
+    #[test]
+    fn test_tuning_from_sink_applies_before_breeding() {
+        struct Tuner(Vec<ProgressEvent>);
+        impl ProgressSink for Tuner {
+            fn report(&mut self, event: &ProgressEvent) -> bool {
+                self.0.push(event.clone());
+                true
+            }
+            fn tune(&mut self) -> Option<Tuning> {
+                // Out-of-range values are clamped; a long status interval stops the reports
+                match self.0.len() {
+                    1 => Some(Tuning { mutation_rate: Some(2.0), crossover_rate: Some(-1.0), elite_size: Some(100), status_interval: None }),
+                    2 => Some(Tuning { status_interval: Some(1e9), ..Tuning::default() }),
+                    _ => None,
+                }
+            }
+        }
+
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 1, None, false);
+        ga.set_seed(8);
+
+        let mut tuner = Tuner(Vec::new());
+        ga.evolve(5, 0.0, &mut tuner);
+        assert_eq!((ga.mutation_rate(), ga.crossover_rate(), ga.elite_size()), (1.0, 0.0, 19));
+        assert_eq!(tuner.0[0].elite_size, Some(2));
+        assert_eq!((tuner.0[1].mutation_rate, tuner.0[1].elite_size, tuner.0[1].status_interval), (Some(1.0), Some(19), Some(0.0)));
+        assert_eq!(tuner.0.len(), 2);
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_generation_count() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 1, None, false);
+        ga.set_seed(4);
+        ga.set_mutation_rate(0.2);
+        let mut steps = 0;
+        ga.evolve(10, 0.0, &mut |_: &ProgressEvent| {
+            steps += 1;
+            steps < 4
+        });
+        assert_eq!(ga.generations_run(), 3);
+        let checkpoint = ga.checkpoint();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        assert_eq!(serde_json::from_str::<Checkpoint>(&json).unwrap(), checkpoint);
+
+        let mut resumed = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 1, None, false);
+        resumed.resume(&checkpoint).unwrap();
+        assert_eq!(resumed.mutation_rate(), 0.2);
+        assert_eq!(resumed.population.iter().map(|individual| &individual.chars).collect::<Vec<_>>(),
+                   ga.population.iter().map(|individual| &individual.chars).collect::<Vec<_>>());
+        let mut first_step = None;
+        resumed.evolve(10, 0.0, &mut |event: &ProgressEvent| {
+            first_step.get_or_insert(event.step);
+            true
+        });
+        assert_eq!((first_step, resumed.generations_run()), (Some(3), 10));
+
+        let mut larger = GeneticAlgorithm::new(5, 5, 30, &ascii_gen, &target, 1, None, false);
+        assert!(larger.resume(&checkpoint).is_err());
+    }
+
+    #[test]
+    fn test_restart_on_stall_keeps_best_ever() {
+        let ascii_gen = create_test_ascii_generator();
+        let target = create_test_target_image();
+
+        // Restarts every 3 generations use up the budget instead of stopping the run
+        let mut ga = GeneticAlgorithm::new(5, 5, 20, &ascii_gen, &target, 2, None, false);
+        ga.set_seed(3);
+        ga.set_stall_generations(3, 1.0);
+        ga.set_restart_on_stall(true);
+        let (best, _) = ga.evolve(10, 1.0, &mut NoProgress);
+        assert_eq!((ga.generations_run(), ga.stop_reason(), ga.restarts()), (10, StopReason::GenerationLimit, 3));
+        let archived = ga.best_ever.as_ref().unwrap().fitness;
+        assert_eq!(best.fitness, archived.max(ga.population[0].fitness));
+
+        // An archived individual outscoring every later population is the one returned
+        let mut champion = ga.population[0].clone();
+        champion.fitness = 2.0;
+        ga.population[0] = champion.clone();
+        ga.restart();
+        ga.evaluate_population();
+        assert_eq!(ga.best().chars, champion.chars);
+        assert_eq!(ga.restarts(), 4);
+
+        // Reseeding starts a new run with an empty archive
+        ga.set_seed(3);
+        assert!(ga.best_ever.is_none() && ga.restarts() == 0);
+    }
+
+    #[test]
+    fn test_warm_start_seeds_population_from_previous_best() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let previous = Individual::new(vec![3; 9]);
+
+        let mut ga = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(5);
+        ga.warm_start(&previous);
+
+        assert_eq!(ga.population.len(), 20);
+        assert_eq!(ga.population[0].chars, previous.chars);
+        // Mutated copies stay close to the previous solution (about 0.45 changed cells each on average)
+        let total_distance: usize = ga.population.iter().map(|i| i.hamming_distance(&previous)).sum();
+        assert!(total_distance < 20 * 3);
+
+        // A mismatched size leaves the population untouched
+        let before: Vec<Vec<u8>> = ga.population.iter().map(|i| i.chars.clone()).collect();
+        ga.warm_start(&Individual::new(vec![0; 4]));
+        assert!(ga.population.iter().zip(&before).all(|(i, b)| &i.chars == b));
+    }
+
+    #[test]
+    fn test_seed_population_replaces_fraction() {
+        let ascii_gen = create_test_ascii_generator();
+        let target_img = create_test_target_image();
+        let art = Individual::new(vec![3; 9]);
+
+        let mut ga = GeneticAlgorithm::new(3, 3, 20, &ascii_gen, &target_img, 1, None, false);
+        ga.set_seed(5);
+        assert_eq!(ga.seed_population(&art, 0.25), 5);
+        assert_eq!(ga.population[0].chars, art.chars);
+        assert_eq!(ga.population.len(), 20);
+
+        // The exact copy is always seeded
+        assert_eq!(ga.seed_population(&art, 0.0), 1);
+        assert_eq!(ga.seed_population(&Individual::new(vec![3; 4]), 0.5), 0);
     }
 
     #[test]
     fn test_individual_with_init_char() {
         // Use 'O' which is in our allowed character set
-        let individual = Individual::new_with_init_char(100, 'O');
+        let individual = Individual::new_with_init_char(100, 'O', &Charset::default(), &mut rand::thread_rng());
         assert_eq!(individual.chars.len(), 100);
 
         // Count how many characters are 'O' (should be around 95%)
-        let o_count = individual.chars.iter().filter(|&&c| c == b'O').count();
-        let random_count = individual.chars.iter().filter(|&&c| c != b'O').count();
+        let o_code = Charset::default().index_of('O').unwrap();
+        let o_count = individual.chars.iter().filter(|&&c| c == o_code).count();
+        let random_count = individual.chars.iter().filter(|&&c| c != o_code).count();
 
         // Should be approximately 95% 'O' and 5% random (with some variance)
         assert!(o_count >= 90); // At least 90% should be 'O'
@@ -703,7 +3285,7 @@ mod tests {
 
         // All characters should be from allowed character set
         for &c in &individual.chars {
-            assert!(ALLOWED_CHARS.contains(&c), "Character {} (0x{:02X}) not in allowed character set", c as char, c);
+            assert!((c as usize) < ALLOWED_CHARS.len(), "Code {} not in allowed character set", c);
         }
     }
 
@@ -716,7 +3298,8 @@ mod tests {
 
         // Check that all individuals in population use the init character
         for individual in &ga.population {
-            let hash_count = individual.chars.iter().filter(|&&c| c == b'#').count();
+            let hash_code = ascii_gen.charset().index_of('#').unwrap();
+            let hash_count = individual.chars.iter().filter(|&&c| c == hash_code).count();
             let total_count = individual.chars.len();
 
             // Should be around 95% '#' characters, but with small sample size (9 chars)
@@ -0,0 +1,321 @@
+//! Overlap fitness evaluated on the GPU with a wgpu compute shader
+//!
+//! [`GpuEvaluator`] uploads the glyph atlas, the target and its pixel weights once, then scores a whole batch of
+//! genomes per dispatch: one workgroup per genome renders each pixel straight from the atlas and tallies matches and
+//! false positives, so only the character codes travel to the GPU each generation. The tallies are the same
+//! [`OverlapCounts`] the CPU path produces, so fitness comes out identical.
+
+use crate::ascii_generator::AsciiGenerator;
+use crate::fitness::{OverlapCounts, Scorer};
+use std::borrow::Cow;
+use wgpu::util::DeviceExt;
+
+/// Threads per workgroup; each one tallies every `LANES`th pixel of its genome into its own pair of counters
+const LANES: u32 = 64;
+
+/// Largest number of workgroups along one dispatch dimension
+const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65_535;
+
+const SHADER: &str = r#"
+struct Params {
+    grid_width: u32,
+    cell_width: u32,
+    cell_height: u32,
+    width: u32,
+    height: u32,
+    target_stride: u32,
+    glyph_count: u32,
+    cells: u32,
+    threshold: u32,
+    tolerance: u32,
+    weighted: u32,
+    count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> atlas: array<u32>;
+@group(0) @binding(2) var<storage, read> target_pixels: array<u32>;
+@group(0) @binding(3) var<storage, read> weights: array<u32>;
+@group(0) @binding(4) var<storage, read> codes: array<u32>;
+@group(0) @binding(5) var<storage, read_write> partials: array<u32>;
+
+const LANES: u32 = 64u;
+
+fn unpack(word: u32, index: u32) -> u32 {
+    return (word >> ((index % 4u) * 8u)) & 255u;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(workgroup_id) group: vec3<u32>, @builtin(local_invocation_index) lane: u32) {
+    let genome = group.x + group.y * 65535u;
+    if (genome >= params.count) {
+        return;
+    }
+    let glyph_size = params.cell_width * params.cell_height;
+    let first_code = genome * params.cells;
+    var matches = 0u;
+    var false_positives = 0u;
+    for (var i = lane; i < params.width * params.height; i += LANES) {
+        let x = i % params.width;
+        let y = i / params.width;
+        let code_index = first_code + (y / params.cell_height) * params.grid_width + x / params.cell_width;
+        let code = unpack(codes[code_index / 4u], code_index);
+        var ascii = 0u;
+        if (code < params.glyph_count) {
+            let atlas_index = code * glyph_size + (y % params.cell_height) * params.cell_width + x % params.cell_width;
+            ascii = unpack(atlas[atlas_index / 4u], atlas_index);
+        }
+        let target_index = y * params.target_stride + x;
+        let target_pixel = unpack(target_pixels[target_index / 4u], target_index);
+        var weight = 1u;
+        if (params.weighted != 0u) {
+            weight = unpack(weights[target_index / 4u], target_index);
+        }
+        let target_is_lit = target_pixel > params.threshold;
+        let close = max(ascii, target_pixel) - min(ascii, target_pixel) < params.tolerance;
+        matches += select(0u, weight, target_is_lit && close);
+        false_positives += select(0u, weight, !target_is_lit && ascii > params.threshold);
+    }
+    let slot = (genome * LANES + lane) * 2u;
+    partials[slot] = matches;
+    partials[slot + 1u] = false_positives;
+}
+"#;
+
+/// Mirrors `Params` in the shader
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    grid_width: u32,
+    cell_width: u32,
+    cell_height: u32,
+    width: u32,
+    height: u32,
+    target_stride: u32,
+    glyph_count: u32,
+    cells: u32,
+    threshold: u32,
+    tolerance: u32,
+    weighted: u32,
+    count: u32,
+}
+
+/// Buffers sized for a batch of genomes, reused until a larger batch arrives
+struct BatchBuffers {
+    capacity: usize,
+    codes: wgpu::Buffer,
+    partials: wgpu::Buffer,
+    readback: wgpu::Buffer,
+}
+
+/// What a batch is scored against: the glyph atlas, the target and its weights, uploaded once per grid
+struct Scene {
+    atlas: wgpu::Buffer,
+    target: wgpu::Buffer,
+    weights: wgpu::Buffer,
+    params: Params,
+    /// Lit target pixels in the compared area (their summed weights with pixel weights), the same for every genome
+    relevant: u64,
+}
+
+/// A GPU device holding the glyph atlas and target of one grid, ready to score batches of genomes
+pub struct GpuEvaluator {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    adapter_name: String,
+    scene: Scene,
+    batch: Option<BatchBuffers>,
+}
+
+impl GpuEvaluator {
+    /// Opens the first GPU adapter that runs compute shaders and uploads the glyphs of `ascii_generator` and the
+    /// scorer's target and pixel weights for a `width` x `height` grid
+    /// Fails when no such adapter exists, so callers can fall back to the CPU
+    pub fn new(ascii_generator: &AsciiGenerator, scorer: &Scorer, width: u32, height: u32) -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .ok_or("no GPU adapter found")?;
+        if !adapter.get_downlevel_capabilities().flags.contains(wgpu::DownlevelFlags::COMPUTE_SHADERS) {
+            return Err(format!("{} does not support compute shaders", adapter.get_info().name));
+        }
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("asciigen fitness"),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            },
+            None,
+        ))
+        .map_err(|e| format!("cannot open {}: {}", adapter.get_info().name, e))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("overlap fitness"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("overlap fitness"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let scene = Self::upload_scene(&device, ascii_generator, scorer, width, height);
+        Ok(Self { adapter_name: adapter.get_info().name, device, queue, pipeline, scene, batch: None })
+    }
+
+    /// Name of the GPU the evaluator runs on
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    /// Uploads the glyphs, target, pixel weights and overlap settings again, after any of them changed
+    pub fn reload(&mut self, ascii_generator: &AsciiGenerator, scorer: &Scorer, width: u32, height: u32) {
+        self.scene = Self::upload_scene(&self.device, ascii_generator, scorer, width, height);
+        self.batch = None;
+    }
+
+    fn upload_scene(device: &wgpu::Device, ascii_generator: &AsciiGenerator, scorer: &Scorer, width: u32, height: u32) -> Scene {
+        let glyph_count = ascii_generator.charset().len();
+        let atlas: Vec<u8> = (0..glyph_count)
+            .flat_map(|code| ascii_generator.generate_ascii_image(&[code as u8], 1, 1).into_raw())
+            .collect();
+        let target_image = scorer.target_image();
+        let weights = scorer.pixel_weights().map(|weights| weights.as_raw().to_vec()).unwrap_or_default();
+        let upload = |label: &str, bytes: &[u8]| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: &padded(bytes),
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+        };
+
+        let (cell_width, cell_height) = ascii_generator.char_dimensions();
+        let params = Params {
+            grid_width: width,
+            cell_width,
+            cell_height,
+            width: (width * cell_width).min(target_image.width()),
+            height: (height * cell_height).min(target_image.height()),
+            target_stride: target_image.width(),
+            glyph_count: glyph_count as u32,
+            cells: width * height,
+            threshold: scorer.background_threshold() as u32,
+            tolerance: scorer.overlap_params().tolerance as u32,
+            weighted: scorer.weights_pixels() as u32,
+            count: 0,
+        };
+        let relevant = (0..params.height)
+            .flat_map(|y| (0..params.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| target_image.get_pixel(x, y)[0] as u32 > params.threshold)
+            .map(|(x, y)| scorer.pixel_weights().map_or(1, |weights| weights.weight(x, y) as u64))
+            .sum();
+        Scene {
+            atlas: upload("glyph atlas", &atlas),
+            target: upload("target", target_image.as_raw()),
+            weights: upload("pixel weights", &weights),
+            params,
+            relevant,
+        }
+    }
+
+    /// Tallies the overlap of every genome (one character code per cell, row-major) with the target
+    pub fn overlap_counts(&mut self, genomes: &[&[u8]]) -> Result<Vec<OverlapCounts>, String> {
+        if genomes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let cells = self.scene.params.cells as usize;
+        if let Some(genome) = genomes.iter().find(|genome| genome.len() != cells) {
+            return Err(format!("genome has {} cells, the grid {}", genome.len(), cells));
+        }
+        self.reserve(genomes.len());
+        let batch = self.batch.as_ref().expect("batch buffers were reserved");
+
+        let codes: Vec<u8> = genomes.iter().flat_map(|genome| genome.iter().copied()).collect();
+        self.queue.write_buffer(&batch.codes, 0, &padded(&codes));
+        let scene = &self.scene;
+        let params = Params { count: genomes.len() as u32, ..scene.params };
+        let params = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("fitness params"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("fitness bindings"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[&params, &scene.atlas, &scene.target, &scene.weights, &batch.codes, &batch.partials]
+                .iter()
+                .enumerate()
+                .map(|(binding, buffer)| wgpu::BindGroupEntry { binding: binding as u32, resource: buffer.as_entire_binding() })
+                .collect::<Vec<_>>(),
+        });
+
+        let partials_size = (genomes.len() * LANES as usize * 2 * 4) as u64;
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("fitness") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("fitness"), timestamp_writes: None });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let count = genomes.len() as u32;
+            pass.dispatch_workgroups(count.min(MAX_WORKGROUPS_PER_DIMENSION), count.div_ceil(MAX_WORKGROUPS_PER_DIMENSION), 1);
+        }
+        encoder.copy_buffer_to_buffer(&batch.partials, 0, &batch.readback, 0, partials_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = batch.readback.slice(..partials_size);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("cannot read the GPU results: {}", e))?;
+
+        let counts = {
+            let mapped = slice.get_mapped_range();
+            let partials: &[u32] = bytemuck::cast_slice(&mapped);
+            partials
+                .chunks_exact(LANES as usize * 2)
+                .map(|lanes| {
+                    let (matches, false_positives) =
+                        lanes.chunks_exact(2).fold((0u64, 0u64), |(m, f), pair| (m + pair[0] as u64, f + pair[1] as u64));
+                    OverlapCounts { matches, false_positives, relevant: scene.relevant }
+                })
+                .collect()
+        };
+        batch.readback.unmap();
+        Ok(counts)
+    }
+
+    /// Makes sure the batch buffers hold at least `count` genomes
+    fn reserve(&mut self, count: usize) {
+        if self.batch.as_ref().is_some_and(|batch| batch.capacity >= count) {
+            return;
+        }
+        let buffer = |label: &str, size: usize, usage: wgpu::BufferUsages| {
+            self.device.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size: size as u64, usage, mapped_at_creation: false })
+        };
+        let codes_size = (count * self.scene.params.cells as usize).next_multiple_of(4);
+        let partials_size = count * LANES as usize * 2 * 4;
+        self.batch = Some(BatchBuffers {
+            capacity: count,
+            codes: buffer("genomes", codes_size, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST),
+            partials: buffer("partial tallies", partials_size, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC),
+            readback: buffer("readback", partials_size, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST),
+        });
+    }
+}
+
+/// Pads bytes to whole 32-bit words, at least one, as storage buffers are read word by word and may not be empty
+fn padded(bytes: &[u8]) -> Vec<u8> {
+    let mut words = bytes.to_vec();
+    words.resize(bytes.len().next_multiple_of(4).max(4), 0);
+    words
+}
@@ -0,0 +1,75 @@
+use image::RgbImage;
+use std::fmt::Write;
+
+/// Upper half block: the foreground color paints the top pixel and the background color the bottom one
+pub const UPPER_HALF_BLOCK: char = '▀';
+
+/// Converts an RGB image into ANSI truecolor half-block art
+/// Each character cell covers two vertically stacked pixels, so the image should be twice as tall as the
+/// desired number of rows; an odd final row leaves the terminal's default background below it
+pub fn render(img: &RgbImage) -> String {
+    let (width, height) = img.dimensions();
+    let mut out = String::new();
+
+    for y in (0..height).step_by(2) {
+        let mut current_fg = None;
+        let mut current_bg = None;
+
+        for x in 0..width {
+            let top = img.get_pixel(x, y).0;
+            let bottom = (y + 1 < height).then(|| img.get_pixel(x, y + 1).0);
+
+            // Only emit escape sequences when a color actually changes
+            if current_fg != Some(top) {
+                let _ = write!(out, "\x1b[38;2;{};{};{}m", top[0], top[1], top[2]);
+                current_fg = Some(top);
+            }
+            if current_bg != Some(bottom) {
+                match bottom {
+                    Some(rgb) => { let _ = write!(out, "\x1b[48;2;{};{};{}m", rgb[0], rgb[1], rgb[2]); }
+                    None => out.push_str("\x1b[49m"),
+                }
+                current_bg = Some(bottom);
+            }
+
+            out.push(UPPER_HALF_BLOCK);
+        }
+
+        out.push_str("\x1b[0m");
+        if y + 2 < height {
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn test_render_pairs_rows_into_cells() {
+        let mut img = RgbImage::new(2, 2);
+        img.put_pixel(0, 0, Rgb([255, 0, 0]));
+        img.put_pixel(1, 0, Rgb([255, 0, 0]));
+        img.put_pixel(0, 1, Rgb([0, 0, 255]));
+        img.put_pixel(1, 1, Rgb([0, 255, 0]));
+
+        // The repeated red foreground is emitted once; the changed background is emitted again
+        assert_eq!(
+            render(&img),
+            "\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀\x1b[48;2;0;255;0m▀\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_render_odd_height_uses_default_background() {
+        let img = RgbImage::from_pixel(1, 3, Rgb([10, 20, 30]));
+        let art = render(&img);
+
+        assert_eq!(art.lines().count(), 2);
+        assert!(art.lines().last().unwrap().contains("\x1b[49m"));
+    }
+}
@@ -1,22 +1,301 @@
-use image::{DynamicImage, ImageBuffer, Luma, ImageError};
+use image::codecs::gif::GifDecoder;
+use image::{AnimationDecoder, DynamicImage, ImageBuffer, ImageFormat, ImageReader, Luma, RgbImage};
+use std::fs::File;
+use std::io::{BufReader, Read};
 use fast_image_resize as fir;
 use fast_image_resize::images::Image;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub struct ImageProcessor;
+/// Fraction of the pixels at each end of the histogram that `--auto-contrast` lets clip, so a few stray pixels do not
+/// hold the stretch back
+const AUTO_CONTRAST_CLIP: f64 = 0.005;
+
+/// A levels remap: `black` and everything darker becomes 0, `white` and everything brighter 255, and the tones between
+/// are spread over the full range through a gamma curve
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Levels {
+    pub black: u8,
+    pub white: u8,
+    /// Above 1 lifts the midtones, below 1 darkens them
+    pub gamma: f64,
+}
+
+impl Levels {
+    /// Lookup table of the remap
+    fn curve(&self) -> Vec<u8> {
+        let range = self.white.saturating_sub(self.black).max(1) as f64;
+        (0..=255u8)
+            .map(|level| {
+                let position = ((level as f64 - self.black as f64) / range).clamp(0.0, 1.0);
+                (255.0 * position.powf(1.0 / self.gamma)).round() as u8
+            })
+            .collect()
+    }
+}
+
+/// Settings of contrast-limited adaptive histogram equalization (CLAHE), which equalizes each tile of the image on
+/// its own and blends neighbouring tiles, so dark and bright regions both keep their detail
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Clahe {
+    /// Tiles along each side of the image
+    pub tiles: u32,
+    /// Highest count a gray level may reach in a tile's histogram, as a multiple of the tile's average count per level;
+    /// the excess is spread over all levels, which limits how far noise in flat regions is amplified
+    pub clip_limit: f64,
+}
+
+impl Default for Clahe {
+    fn default() -> Self {
+        Self { tiles: 8, clip_limit: 2.0 }
+    }
+}
+
+/// Loads source images and prepares the grayscale target that generated art is scored against
+/// Tone adjustments set on the processor are applied to every target it prepares
+#[derive(Clone, Debug)]
+pub struct ImageProcessor {
+    /// Remap a chosen input range onto 0-255
+    levels: Option<Levels>,
+    /// Stretch the darkest and brightest tones of the target to 0 and 255
+    auto_contrast: bool,
+    /// Spread the target's tones evenly over 0-255 by histogram equalization
+    equalize: bool,
+    /// Equalize the target tile by tile instead
+    clahe: Option<Clahe>,
+    /// Exponent of the tone curve, above 1 lifting dark tones and below 1 deepening them
+    gamma: f64,
+    /// Number of evenly spaced gray levels the target is quantized to
+    posterize: Option<u32>,
+}
+
+impl Default for ImageProcessor {
+    fn default() -> Self {
+        Self { levels: None, auto_contrast: false, equalize: false, clahe: None, gamma: 1.0, posterize: None }
+    }
+}
+
+/// Matches a file name against a pattern where `*` matches any run of characters and `?` any single character
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None; // Pattern index after the last `*` and the name index it is currently matched up to
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` absorb one more character and retry
+                Some((star_p, star_n)) => {
+                    backtrack = Some((star_p, star_n + 1));
+                    p = star_p;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
 
 impl ImageProcessor {
     /// Creates a new ImageProcessor instance
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Sets the gamma applied to prepared targets: each pixel becomes 255 * (value / 255)^(1 / gamma), so values above
+    /// 1 bring detail out of dark images before the background threshold swallows it; 1 leaves the target unchanged
+    pub fn set_gamma(&mut self, gamma: f64) {
+        self.gamma = gamma;
+    }
+
+    /// Sets the levels remap applied to prepared targets before any other tone adjustment, None for none
+    pub fn set_levels(&mut self, levels: Option<Levels>) {
+        self.levels = levels;
+    }
+
+    /// Sets whether prepared targets are stretched linearly so their darkest tones become 0 and their brightest 255,
+    /// ignoring the outermost half percent of pixels at each end
+    pub fn set_auto_contrast(&mut self, auto_contrast: bool) {
+        self.auto_contrast = auto_contrast;
+    }
+
+    /// Sets whether prepared targets are histogram-equalized, which spreads their tones evenly over 0-255 and brings
+    /// out detail in crowded tone ranges at the cost of a harsher look
+    pub fn set_equalize(&mut self, equalize: bool) {
+        self.equalize = equalize;
+    }
+
+    /// Sets the number of gray levels (2-256) prepared targets are quantized to after every other tone adjustment,
+    /// None for no quantization; fewer levels turn noise and soft gradients into flat areas
+    pub fn set_posterize(&mut self, levels: Option<u32>) {
+        self.posterize = levels;
+    }
+
+    /// Sets the adaptive equalization applied to prepared targets after any global equalization, None for none
+    pub fn set_clahe(&mut self, clahe: Option<Clahe>) {
+        self.clahe = clahe;
     }
 
     /// Loads an image from the specified file path
-    pub fn load_image<P: AsRef<Path>>(&self, path: P) -> Result<DynamicImage, ImageError> {
-        image::open(path)
+    /// The format is detected from the file contents, falling back to the extension
+    pub fn load_image<P: AsRef<Path>>(&self, path: P) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let reader = ImageReader::open(path)?.with_guessed_format()?;
+
+        reader.decode().map_err(|e| {
+            format!(
+                "Failed to decode {:?}: {}. Supported formats: {}",
+                path,
+                e,
+                Self::supported_formats().join(", ")
+            ).into()
+        })
+    }
+
+    /// Loads an image from a reader such as stdin, detecting the format from the data itself
+    pub fn load_image_from_reader<R: Read>(&self, mut reader: R) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        if bytes.is_empty() {
+            return Err("no image data was read".into());
+        }
+        self.load_image_from_memory(&bytes)
+    }
+
+    /// Downloads and decodes an image, detecting the format from the response body
+    #[cfg(feature = "http")]
+    pub fn load_image_from_url(&self, url: &str) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+        /// Largest response body accepted, to keep a wrong URL from exhausting memory
+        const MAX_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+
+        let mut response = ureq::get(url).call().map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        let bytes = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_DOWNLOAD_BYTES)
+            .read_to_vec()
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        self.load_image_from_memory(&bytes)
+    }
+
+    /// Downloads and decodes an image; this build has no HTTP support and always fails
+    #[cfg(not(feature = "http"))]
+    pub fn load_image_from_url(&self, _url: &str) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+        Err("URL input requires building with `--features http`".into())
+    }
+
+    /// Decodes an image from encoded bytes, such as a file read by a browser
+    pub fn load_image_from_memory(&self, bytes: &[u8]) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+        image::load_from_memory(bytes).map_err(|e| {
+            format!("Failed to decode image: {}. Supported formats: {}", e, Self::supported_formats().join(", ")).into()
+        })
+    }
+
+    /// Loads every frame of an animated GIF, composited onto the full canvas
+    /// Returns None for other formats and for GIFs with a single frame, which load as still images
+    pub fn load_animation_frames<P: AsRef<Path>>(&self, path: P) -> Result<Option<Vec<DynamicImage>>, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        if ImageReader::open(path)?.with_guessed_format()?.format() != Some(ImageFormat::Gif) {
+            return Ok(None);
+        }
+
+        let decoder = GifDecoder::new(BufReader::new(File::open(path)?))?;
+        let frames = decoder
+            .into_frames()
+            .map(|frame| frame.map(|f| DynamicImage::ImageRgba8(f.into_buffer())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to decode GIF frames from {:?}: {}", path, e))?;
+
+        Ok((frames.len() > 1).then_some(frames))
+    }
+
+    /// Returns the names of the image formats this build can decode
+    pub fn supported_formats() -> Vec<&'static str> {
+        #[allow(unused_mut)]
+        let mut formats = vec!["PNG", "JPEG", "GIF", "BMP", "TIFF", "PNM", "TGA", "ICO", "QOI"];
+
+        #[cfg(feature = "webp")]
+        formats.push("WebP");
+        #[cfg(feature = "avif")]
+        formats.push("AVIF");
+
+        formats
+    }
+
+    /// Returns true if the input names an http:// or https:// URL rather than a local file
+    pub fn is_url(path: &Path) -> bool {
+        path.to_str().is_some_and(|s| {
+            let lower = s.to_ascii_lowercase();
+            lower.starts_with("http://") || lower.starts_with("https://")
+        })
+    }
+
+    /// Returns true if the path is `-`, which stands for image data piped to stdin
+    pub fn is_stdin_path(path: &Path) -> bool {
+        path.as_os_str() == "-"
+    }
+
+    /// Returns true if the path has an extension of a still image format this build may decode
+    pub fn is_image_path(path: &Path) -> bool {
+        const IMAGE_EXTENSIONS: &[&str] = &[
+            "png", "jpg", "jpeg", "gif", "bmp", "tif", "tiff", "pnm", "pbm", "pgm", "ppm", "tga", "ico", "qoi", "webp", "avif",
+        ];
+
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+    }
+
+    /// Expands a batch input into the image files it names, sorted by path
+    /// A directory yields every image file directly inside it; a file name containing `*` or `?` is matched
+    /// against the files in its directory. Returns None for any other path, which is processed as a single input
+    pub fn batch_inputs(path: &Path) -> Result<Option<Vec<PathBuf>>, Box<dyn std::error::Error>> {
+        let (dir, pattern) = if path.is_dir() {
+            (path, "*")
+        } else {
+            match path.file_name().and_then(|name| name.to_str()) {
+                Some(name) if name.contains(['*', '?']) => (path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new(".")), name),
+                _ => return Ok(None),
+            }
+        };
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let file = entry?.path();
+            let matches = file.file_name().and_then(|name| name.to_str()).is_some_and(|name| wildcard_match(pattern, name));
+            if matches && file.is_file() && Self::is_image_path(&file) {
+                files.push(file);
+            }
+        }
+        files.sort();
+
+        if files.is_empty() {
+            return Err(format!("no image files match {:?}", path).into());
+        }
+        Ok(Some(files))
+    }
+
+    /// Returns true if the path has a video file extension
+    /// Video decoding itself needs the `video` feature; this check also lets builds without it explain why
+    pub fn is_video_path(path: &Path) -> bool {
+        const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mkv", "webm", "mov", "avi", "mpg", "mpeg"];
+
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
     }
 
-    /// Prepares target image with optional inversion, resizing and converting to grayscale
+    /// Prepares target image with optional inversion, resizing and converting to grayscale, then applies the tone
+    /// adjustments
     /// This creates the reference image that the genetic algorithm will try to match
     pub fn prepare_target_image_with_inversion(
         &self,
@@ -31,10 +310,44 @@ impl ImageProcessor {
         if invert {
             self.invert_image(&mut grayscale);
         }
+        self.adjust_tones(&mut grayscale);
         
         Ok(grayscale)
     }
 
+    /// Resizes an image to the given dimensions keeping its colors, optionally inverting them
+    /// Used by renderers that convert pixels directly instead of optimizing characters
+    pub fn prepare_color_image(
+        &self,
+        img: &DynamicImage,
+        target_width: u32,
+        target_height: u32,
+        invert: bool,
+    ) -> Result<RgbImage, Box<dyn std::error::Error>> {
+        let mut rgb = self.resize_image(img, target_width, target_height)?.to_rgb8();
+
+        if invert {
+            for value in rgb.iter_mut() {
+                *value = 255 - *value;
+            }
+        }
+
+        Ok(rgb)
+    }
+
+    /// Crops the largest centered region with the given width:height `aspect` out of an image
+    /// Used to fill a fixed grid without distortion, at the cost of the image's edges
+    pub fn crop_to_aspect(&self, img: &DynamicImage, aspect: f32) -> DynamicImage {
+        let (width, height) = (img.width(), img.height());
+        if width as f32 / height as f32 > aspect {
+            let cropped_width = ((height as f32 * aspect).round() as u32).clamp(1, width);
+            img.crop_imm((width - cropped_width) / 2, 0, cropped_width, height)
+        } else {
+            let cropped_height = ((width as f32 / aspect).round() as u32).clamp(1, height);
+            img.crop_imm(0, (height - cropped_height) / 2, width, cropped_height)
+        }
+    }
+
     /// Resizes an image to the specified dimensions using high-quality Lanczos3 filtering
     fn resize_image(
         &self,
@@ -72,6 +385,36 @@ impl ImageProcessor {
         img.to_luma8()
     }
 
+    /// Applies the enabled tone adjustments in order: levels, contrast stretch, equalization, adaptive equalization,
+    /// gamma, posterization
+    fn adjust_tones(&self, img: &mut ImageBuffer<Luma<u8>, Vec<u8>>) {
+        if let Some(levels) = self.levels {
+            remap(img, &levels.curve());
+        }
+        if self.auto_contrast {
+            if let Some(curve) = contrast_stretch_curve(&histogram(img), AUTO_CONTRAST_CLIP) {
+                remap(img, &curve);
+            }
+        }
+        if self.equalize {
+            if let Some(curve) = equalization_curve(&histogram(img)) {
+                remap(img, &curve);
+            }
+        }
+        if let Some(clahe) = self.clahe {
+            equalize_adaptively(img, clahe);
+        }
+        if self.gamma != 1.0 {
+            let curve: Vec<u8> = (0..=255u8).map(|value| (255.0 * (value as f64 / 255.0).powf(1.0 / self.gamma)).round() as u8).collect();
+            remap(img, &curve);
+        }
+        if let Some(levels) = self.posterize {
+            let steps = (levels.clamp(2, 256) - 1) as f64;
+            let curve: Vec<u8> = (0..=255u8).map(|value| ((value as f64 / 255.0 * steps).round() / steps * 255.0).round() as u8).collect();
+            remap(img, &curve);
+        }
+    }
+
     /// Inverts a grayscale image (255 - pixel_value for each pixel)
     fn invert_image(&self, img: &mut ImageBuffer<Luma<u8>, Vec<u8>>) {
         for pixel in img.pixels_mut() {
@@ -80,11 +423,149 @@ impl ImageProcessor {
     }
 }
 
+/// Counts the pixels of each gray level
+fn histogram(img: &ImageBuffer<Luma<u8>, Vec<u8>>) -> [u32; 256] {
+    let mut counts = [0; 256];
+    for value in img.iter() {
+        counts[*value as usize] += 1;
+    }
+    counts
+}
+
+/// Replaces every pixel by its entry in a 256-value lookup table
+fn remap(img: &mut ImageBuffer<Luma<u8>, Vec<u8>>, curve: &[u8]) {
+    for value in img.iter_mut() {
+        *value = curve[*value as usize];
+    }
+}
+
+/// Linear curve taking the levels that cut `clip` of the pixels off each end of the histogram to 0 and 255
+/// Returns None for a flat image, which has no range to stretch
+fn contrast_stretch_curve(histogram: &[u32; 256], clip: f64) -> Option<Vec<u8>> {
+    let total: u32 = histogram.iter().sum();
+    let clipped = (total as f64 * clip) as u32;
+    let low = level_past(histogram, clipped, 0..256)?;
+    let high = level_past(histogram, clipped, (0..256).rev())?;
+    if high <= low {
+        return None;
+    }
+    let scale = 255.0 / (high - low) as f64;
+    Some((0..256).map(|level| ((level as f64 - low as f64) * scale).round().clamp(0.0, 255.0) as u8).collect())
+}
+
+/// First of `levels` by which more than `clipped` pixels have been passed
+fn level_past(histogram: &[u32; 256], clipped: u32, mut levels: impl Iterator<Item = usize>) -> Option<usize> {
+    let mut seen = 0;
+    levels.find(|&level| {
+        seen += histogram[level];
+        seen > clipped
+    })
+}
+
+/// Histogram equalization curve: each level maps to its share of the pixels at or below it, rescaled so the darkest
+/// level present becomes 0 and the brightest 255
+/// Returns None for a flat image
+fn equalization_curve(histogram: &[u32; 256]) -> Option<Vec<u8>> {
+    let total: u32 = histogram.iter().sum();
+    let darkest = histogram.iter().copied().find(|&count| count > 0)?;
+    if darkest == total {
+        return None;
+    }
+    let mut cumulative = 0;
+    Some(
+        histogram
+            .iter()
+            .map(|&count| {
+                cumulative += count;
+                (cumulative.saturating_sub(darkest) as f64 / (total - darkest) as f64 * 255.0).round() as u8
+            })
+            .collect(),
+    )
+}
+
+/// Contrast-limited adaptive histogram equalization: builds a clipped equalization curve per tile and maps every pixel
+/// through the curves of the four nearest tile centers, weighted by distance, so tile borders do not show
+fn equalize_adaptively(img: &mut ImageBuffer<Luma<u8>, Vec<u8>>, clahe: Clahe) {
+    let (width, height) = img.dimensions();
+    let tiles_x = clahe.tiles.clamp(1, width.max(1));
+    let tiles_y = clahe.tiles.clamp(1, height.max(1));
+    let tile_width = width as f64 / tiles_x as f64;
+    let tile_height = height as f64 / tiles_y as f64;
+
+    let mut curves = Vec::with_capacity((tiles_x * tiles_y) as usize);
+    for tile_y in 0..tiles_y {
+        let (top, bottom) = ((tile_y as f64 * tile_height) as u32, ((tile_y + 1) as f64 * tile_height) as u32);
+        for tile_x in 0..tiles_x {
+            let (left, right) = ((tile_x as f64 * tile_width) as u32, ((tile_x + 1) as f64 * tile_width) as u32);
+            let mut histogram = [0u32; 256];
+            for y in top..bottom {
+                for x in left..right {
+                    histogram[img.get_pixel(x, y)[0] as usize] += 1;
+                }
+            }
+            curves.push(clipped_equalization_curve(&mut histogram, clahe.clip_limit));
+        }
+    }
+
+    // Position of a pixel in tile-center coordinates: the two nearest tiles along one axis and the second one's weight
+    let neighbours = |position: u32, tile_size: f64, tiles: u32| {
+        let center = ((position as f64 + 0.5) / tile_size - 0.5).clamp(0.0, (tiles - 1) as f64);
+        let first = center.floor() as u32;
+        (first, (first + 1).min(tiles - 1), center - first as f64)
+    };
+    for y in 0..height {
+        let (row0, row1, wy) = neighbours(y, tile_height, tiles_y);
+        for x in 0..width {
+            let (col0, col1, wx) = neighbours(x, tile_width, tiles_x);
+            let value = img.get_pixel(x, y)[0] as usize;
+            let curve = |row: u32, col: u32| curves[(row * tiles_x + col) as usize][value] as f64;
+            let top = curve(row0, col0) * (1.0 - wx) + curve(row0, col1) * wx;
+            let bottom = curve(row1, col0) * (1.0 - wx) + curve(row1, col1) * wx;
+            img.put_pixel(x, y, Luma([(top * (1.0 - wy) + bottom * wy).round() as u8]));
+        }
+    }
+}
+
+/// Equalization curve of one CLAHE tile: levels above `clip_limit` times the average count are cut down and the excess
+/// spread evenly over all levels before the cumulative counts are scaled to 0-255
+fn clipped_equalization_curve(histogram: &mut [u32; 256], clip_limit: f64) -> [u8; 256] {
+    let total: u32 = histogram.iter().sum();
+    let limit = ((clip_limit * total as f64 / 256.0) as u32).max(1);
+    let excess: u32 = histogram.iter_mut().map(|count| {
+        let cut = count.saturating_sub(limit);
+        *count -= cut;
+        cut
+    }).sum();
+    for (level, count) in histogram.iter_mut().enumerate() {
+        *count += excess / 256 + u32::from((level as u32) < excess % 256);
+    }
+
+    let mut curve = [0; 256];
+    let mut cumulative = 0;
+    for (level, &count) in histogram.iter().enumerate() {
+        cumulative += count;
+        curve[level] = (cumulative as f64 / total.max(1) as f64 * 255.0).round() as u8;
+    }
+    curve
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use image::{RgbImage, DynamicImage};
 
+    #[test]
+    fn test_crop_to_aspect_keeps_the_center() {
+        let processor = ImageProcessor::new();
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(100, 50, |x, _| image::Rgb([x as u8, 0, 0])));
+
+        let wide = processor.crop_to_aspect(&img, 4.0);
+        assert_eq!((wide.width(), wide.height()), (100, 25));
+        let tall = processor.crop_to_aspect(&img, 1.0);
+        assert_eq!((tall.width(), tall.height()), (50, 50));
+        assert_eq!(tall.to_rgb8().get_pixel(0, 0)[0], 25);
+    }
+
     #[test]
     fn test_convert_to_grayscale() {
         let processor = ImageProcessor::new();
@@ -96,6 +577,95 @@ mod tests {
         assert_eq!(gray_img.height(), 10);
     }
 
+    #[test]
+    fn test_is_video_path() {
+        assert!(ImageProcessor::is_video_path(Path::new("clip.MP4")));
+        assert!(ImageProcessor::is_video_path(Path::new("dir/clip.webm")));
+        assert!(!ImageProcessor::is_video_path(Path::new("photo.png")));
+        assert!(!ImageProcessor::is_video_path(Path::new("mp4")));
+    }
+
+    #[test]
+    fn test_load_animation_frames() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Frame, Rgba, RgbaImage};
+
+        let processor = ImageProcessor::new();
+        let path = std::env::temp_dir().join(format!("asciigen_animation_{}.gif", std::process::id()));
+        {
+            let mut encoder = GifEncoder::new(File::create(&path).unwrap());
+            for shade in [0, 255] {
+                let frame = Frame::new(RgbaImage::from_pixel(4, 3, Rgba([shade, shade, shade, 255])));
+                encoder.encode_frame(frame).unwrap();
+            }
+        }
+
+        let frames = processor.load_animation_frames(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let frames = frames.unwrap().expect("two-frame GIF is animated");
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[1].width(), frames[1].height()), (4, 3));
+        assert_eq!(frames[1].to_luma8().get_pixel(0, 0).0, [255]);
+    }
+
+    #[test]
+    fn test_load_image_from_reader_sniffs_format() {
+        let processor = ImageProcessor::new();
+        let mut bmp = Vec::new();
+        RgbImage::new(3, 2).write_to(&mut std::io::Cursor::new(&mut bmp), image::ImageFormat::Bmp).unwrap();
+
+        let img = processor.load_image_from_reader(bmp.as_slice()).unwrap();
+        assert_eq!((img.width(), img.height()), (3, 2));
+        assert!(processor.load_image_from_reader(std::io::empty()).is_err());
+        assert!(ImageProcessor::is_stdin_path(Path::new("-")));
+        assert!(ImageProcessor::is_url(Path::new("HTTPS://example.com/cat.png?size=2")));
+        assert!(!ImageProcessor::is_url(Path::new("http.png")));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.png", "duck.png"));
+        assert!(wildcard_match("frame_??.jpg", "frame_07.jpg"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("*.png", "duck.png.txt"));
+        assert!(!wildcard_match("frame_?.jpg", "frame_07.jpg"));
+    }
+
+    #[test]
+    fn test_batch_inputs() {
+        let dir = std::env::temp_dir().join(format!("asciigen_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["b.png", "a.JPG", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let all = ImageProcessor::batch_inputs(&dir).unwrap().unwrap();
+        let globbed = ImageProcessor::batch_inputs(&dir.join("*.png")).unwrap().unwrap();
+        let single = ImageProcessor::batch_inputs(&dir.join("b.png")).unwrap();
+        let missing = ImageProcessor::batch_inputs(&dir.join("*.gif"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(all, vec![dir.join("a.JPG"), dir.join("b.png")]);
+        assert_eq!(globbed, vec![dir.join("b.png")]);
+        assert!(single.is_none());
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_load_image_reports_supported_formats() {
+        let processor = ImageProcessor::new();
+        let path = std::env::temp_dir().join(format!("asciigen_not_an_image_{}.png", std::process::id()));
+        std::fs::write(&path, b"definitely not image data").unwrap();
+
+        let result = processor.load_image(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("Supported formats"), "unexpected error: {}", message);
+        assert!(message.contains("PNG"));
+    }
+
     #[test]
     fn test_resize_image() {
         let processor = ImageProcessor::new();
@@ -128,6 +698,101 @@ mod tests {
         assert_eq!(img.get_pixel(1, 1)[0], 0);   // 255 - 255
     }
 
+    #[test]
+    fn test_gamma_lifts_dark_tones() {
+        let mut processor = ImageProcessor::new();
+        let dark = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, image::Rgb([64, 64, 64])));
+        assert_eq!(processor.prepare_target_image_with_inversion(&dark, 2, 2, false).unwrap().get_pixel(0, 0)[0], 64);
+
+        processor.set_gamma(2.0);
+        let lifted = processor.prepare_target_image_with_inversion(&dark, 2, 2, false).unwrap();
+        assert_eq!(lifted.get_pixel(0, 0)[0], 128); // 255 * sqrt(64 / 255)
+        // Black and white stay put, and inversion comes first
+        let white = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, image::Rgb([255, 255, 255])));
+        assert_eq!(processor.prepare_target_image_with_inversion(&white, 2, 2, true).unwrap().get_pixel(0, 0)[0], 0);
+    }
+
+    #[test]
+    fn test_auto_contrast_and_equalize_use_the_full_range() {
+        // A dull gradient between 100 and 140
+        let dull = DynamicImage::ImageLuma8(ImageBuffer::from_fn(40, 10, |x, _| Luma([100 + x as u8])));
+        let range = |img: &ImageBuffer<Luma<u8>, Vec<u8>>| (*img.iter().min().unwrap(), *img.iter().max().unwrap());
+        let mut processor = ImageProcessor::new();
+        assert_eq!(range(&processor.prepare_target_image_with_inversion(&dull, 40, 10, false).unwrap()), (100, 139));
+
+        processor.set_auto_contrast(true);
+        let stretched = processor.prepare_target_image_with_inversion(&dull, 40, 10, false).unwrap();
+        assert_eq!(range(&stretched), (0, 255));
+        assert!(stretched.get_pixel(10, 0)[0] < stretched.get_pixel(30, 0)[0]);
+
+        // Equalizing a bimodal image spreads both clusters apart
+        let mut processor = ImageProcessor::new();
+        processor.set_equalize(true);
+        let bimodal = DynamicImage::ImageLuma8(ImageBuffer::from_fn(4, 4, |x, _| Luma([if x < 2 { 60 } else { 70 }])));
+        let equalized = processor.prepare_target_image_with_inversion(&bimodal, 4, 4, false).unwrap();
+        assert_eq!((equalized.get_pixel(0, 0)[0], equalized.get_pixel(3, 0)[0]), (0, 255));
+
+        // Flat images are left alone
+        assert_eq!(equalization_curve(&histogram(&ImageBuffer::from_pixel(3, 3, Luma([90])))), None);
+        assert_eq!(contrast_stretch_curve(&histogram(&ImageBuffer::from_pixel(3, 3, Luma([90]))), AUTO_CONTRAST_CLIP), None);
+    }
+
+    #[test]
+    fn test_levels_remap_the_chosen_range() {
+        let ramp = DynamicImage::ImageLuma8(ImageBuffer::from_fn(256, 1, |x, _| Luma([x as u8])));
+        let mut processor = ImageProcessor::new();
+        processor.set_levels(Some(Levels { black: 50, white: 150, gamma: 1.0 }));
+        let remapped = processor.prepare_target_image_with_inversion(&ramp, 256, 1, false).unwrap();
+        let at = |x: u32| remapped.get_pixel(x, 0)[0];
+        assert_eq!((at(0), at(50), at(100), at(150), at(255)), (0, 0, 128, 255, 255));
+
+        processor.set_levels(Some(Levels { black: 50, white: 150, gamma: 2.0 }));
+        let lifted = processor.prepare_target_image_with_inversion(&ramp, 256, 1, false).unwrap();
+        assert_eq!((lifted.get_pixel(50, 0)[0], lifted.get_pixel(75, 0)[0], lifted.get_pixel(150, 0)[0]), (0, 128, 255));
+    }
+
+    #[test]
+    fn test_posterize_quantizes_to_evenly_spaced_levels() {
+        let ramp = DynamicImage::ImageLuma8(ImageBuffer::from_fn(256, 1, |x, _| Luma([x as u8])));
+        let mut processor = ImageProcessor::new();
+        processor.set_posterize(Some(3));
+        let posterized = processor.prepare_target_image_with_inversion(&ramp, 256, 1, false).unwrap();
+        let mut levels: Vec<u8> = posterized.iter().copied().collect();
+        levels.dedup();
+        assert_eq!(levels, vec![0, 128, 255]);
+
+        processor.set_posterize(Some(256));
+        assert_eq!(processor.prepare_target_image_with_inversion(&ramp, 256, 1, false).unwrap().get_pixel(77, 0)[0], 77);
+    }
+
+    #[test]
+    fn test_clahe_brings_out_detail_in_dark_and_bright_regions() {
+        // Left half: a dark gradient 0-31; right half: a bright gradient 224-255
+        let img = DynamicImage::ImageLuma8(ImageBuffer::from_fn(64, 32, |x, y| Luma([if x < 32 { y as u8 } else { 224 + y as u8 }])));
+        let spread = |img: &ImageBuffer<Luma<u8>, Vec<u8>>, x: u32| img.get_pixel(x, 31)[0] as i32 - img.get_pixel(x, 0)[0] as i32;
+
+        let mut processor = ImageProcessor::new();
+        let plain = processor.prepare_target_image_with_inversion(&img, 64, 32, false).unwrap();
+        processor.set_clahe(Some(Clahe { tiles: 2, clip_limit: 4.0 }));
+        let equalized = processor.prepare_target_image_with_inversion(&img, 64, 32, false).unwrap();
+
+        // Both halves gain contrast within themselves and stay ordered
+        assert!(spread(&equalized, 8) > 2 * spread(&plain, 8));
+        assert!(spread(&equalized, 56) > 2 * spread(&plain, 56));
+        assert!(equalized.get_pixel(8, 31)[0] > equalized.get_pixel(8, 16)[0]);
+    }
+
+    #[test]
+    fn test_clipped_equalization_curve_limits_the_slope() {
+        // Every pixel at one level: unclipped equalization would jump from 0 to 255 there
+        let mut histogram = [0; 256];
+        histogram[100] = 256;
+        let curve = clipped_equalization_curve(&mut histogram, 2.0);
+        assert_eq!((curve[0], curve[255]), (1, 255));
+        assert!(curve[100] - curve[99] < 5);
+        assert!(curve.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
     #[test]
     fn test_prepare_target_image_with_inversion() {
         let processor = ImageProcessor::new();
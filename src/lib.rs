@@ -0,0 +1,58 @@
+//! ASCIIGen library: generate ASCII art from images using a genetic algorithm or brute-force search.
+//!
+//! The typical pipeline loads an image with [`ImageProcessor`], prepares a grayscale target sized to the
+//! character grid rendered by [`AsciiGenerator`], and then optimizes the characters with either
+//! [`GeneticAlgorithm`] or [`BruteForceGenerator`]. The core types never print; progress is reported
+//! as [`ProgressEvent`]s to the [`ProgressSink`] passed to `evolve` and `generate`.
+//!
+//! ```
+//! use asciigen::{AsciiGenerator, BruteForceGenerator, NoProgress};
+//! use image::{ImageBuffer, Luma};
+//!
+//! let ascii_gen = AsciiGenerator::new();
+//! let (char_width, char_height) = ascii_gen.char_dimensions();
+//!
+//! // A 4x2 character target with a bright left half
+//! let target = ImageBuffer::from_fn(4 * char_width, 2 * char_height, |x, _| {
+//!     Luma([if x < 2 * char_width { 255u8 } else { 0u8 }])
+//! });
+//!
+//! let generator = BruteForceGenerator::new(4, 2, &ascii_gen, &target, false);
+//! let (best, _elapsed) = generator.generate(&mut NoProgress);
+//! println!("{}", ascii_gen.individual_to_string(&best, 4));
+//! ```
+
+pub mod ascii_generator;
+pub mod brute_force;
+pub mod charset;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fitness;
+pub mod genetic_algorithm;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod halfblock;
+pub mod image_processor;
+pub mod luminance;
+pub mod progress;
+pub mod session;
+pub mod template;
+#[cfg(feature = "video")]
+pub mod video;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use ascii_generator::AsciiGenerator;
+pub use brute_force::{BruteForceGenerator, TraversalOrder};
+pub use charset::{Charset, CharsetPreset, ALLOWED_CHARS};
+pub use fitness::{FitnessMetric, OverlapParams, PixelWeights, Scorer};
+pub use genetic_algorithm::{
+    Checkpoint, CrossoverOperator, GeneticAlgorithm, Individual, MutationOperator, ParetoObjective, ReplacementStrategy, SavedIndividual,
+    SelectionStrategy, StopReason,
+};
+pub use image_processor::{Clahe, ImageProcessor, Levels};
+pub use luminance::LuminanceMapper;
+pub use progress::{CancelHandle, ChannelSink, FitnessStats, GenerationUpdate, NoProgress, ProgressEvent, ProgressKind, ProgressSink, Tuning};
+pub use session::{EvolutionSession, SessionConfig};
+pub use template::LockMask;
@@ -0,0 +1,116 @@
+use crate::ascii_generator::AsciiGenerator;
+use crate::fitness::Scorer;
+use crate::genetic_algorithm::Individual;
+use image::{ImageBuffer, Luma};
+
+/// Instant converter that maps each cell's average brightness to the character with the closest glyph coverage
+/// Quality is below the optimizers because glyph shapes are ignored, but a whole image takes one pass over the pixels
+pub struct LuminanceMapper<'a> {
+    width: u32,
+    height: u32,
+    ascii_generator: &'a AsciiGenerator,
+    target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>,
+    white_background: bool,
+}
+
+impl<'a> LuminanceMapper<'a> {
+    /// Creates a mapper for a target rendered at `width`x`height` characters
+    pub fn new(
+        width: u32,
+        height: u32,
+        ascii_generator: &'a AsciiGenerator,
+        target_image: &'a ImageBuffer<Luma<u8>, Vec<u8>>,
+        white_background: bool,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            ascii_generator,
+            target_image,
+            white_background,
+        }
+    }
+
+    /// Picks a character for every cell and returns the result with its fitness and the elapsed time in seconds
+    pub fn generate(&self) -> (Individual, f64) {
+        let start_time = web_time::Instant::now();
+
+        // Coverage of the ramp from the glyph cache, lightest first; cell brightness is stretched onto the
+        // densest glyph's coverage since no character fills its whole cell
+        let ramp = self.ascii_generator.density_ramp();
+        let coverages: Vec<f64> = ramp.iter().map(|&c| self.ascii_generator.glyph_coverage(c)).collect();
+        let max_coverage = coverages.last().copied().unwrap_or(0.0);
+
+        let chars = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                let ink = self.cell_ink(col, row) * max_coverage;
+                let next = coverages.partition_point(|&coverage| coverage < ink);
+                let closest = match next {
+                    0 => 0,
+                    n if n == coverages.len() => n - 1,
+                    n if ink - coverages[n - 1] <= coverages[n] - ink => n - 1,
+                    n => n,
+                };
+                ramp[closest]
+            })
+            .collect();
+
+        let mut individual = Individual::new(chars);
+        individual.fitness = self.calculate_fitness(&individual);
+        (individual, start_time.elapsed().as_secs_f64())
+    }
+
+    /// Returns the fraction of a cell the target wants inked: brightness on black, darkness on white backgrounds
+    fn cell_ink(&self, col: u32, row: u32) -> f64 {
+        let (char_width, char_height) = self.ascii_generator.char_dimensions();
+        let x_end = ((col + 1) * char_width).min(self.target_image.width());
+        let y_end = ((row + 1) * char_height).min(self.target_image.height());
+
+        let mut sum = 0u64;
+        let mut count = 0u64;
+        for y in row * char_height..y_end {
+            for x in col * char_width..x_end {
+                sum += self.target_image.get_pixel(x, y)[0] as u64;
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return 0.0;
+        }
+
+        let brightness = sum as f64 / (count as f64 * 255.0);
+        if self.white_background { 1.0 - brightness } else { brightness }
+    }
+
+    /// Scores the result with the fitness the optimizers use, so conversions can be compared directly
+    fn calculate_fitness(&self, individual: &Individual) -> f64 {
+        let ascii_image = self.ascii_generator.generate_ascii_image(&individual.chars, self.width, self.height);
+        Scorer::new(self.target_image, self.white_background).score(&ascii_image, &mut ImageBuffer::new(0, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maps_brightness_to_density() {
+        let ascii_gen = AsciiGenerator::new();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        // Black, mid gray and white cells
+        let target = ImageBuffer::from_fn(3 * char_width, char_height, |x, _| Luma([(x / char_width * 127).min(255) as u8]));
+
+        let (best, _) = LuminanceMapper::new(3, 1, &ascii_gen, &target, false).generate();
+        let ramp = ascii_gen.density_ramp();
+        let rank = |c: u8| ramp.iter().position(|&r| r == c).unwrap();
+        assert_eq!(best.chars[0], ramp[0]);
+        assert_eq!(best.chars[2], ramp[ramp.len() - 1]);
+        assert!(rank(best.chars[0]) < rank(best.chars[1]) && rank(best.chars[1]) < rank(best.chars[2]));
+
+        // On a white background the same target inverts: the white cell needs no ink
+        let (inverted, _) = LuminanceMapper::new(3, 1, &ascii_gen, &target, true).generate();
+        assert_eq!(inverted.chars[2], ramp[0]);
+        assert_eq!(inverted.chars[0], ramp[ramp.len() - 1]);
+    }
+}
@@ -1,293 +1,3450 @@
-mod image_processor;
-mod ascii_generator;
-mod genetic_algorithm;
-mod brute_force;
-mod ncurses_ui;
+mod terminal_ui;
 
-use clap::Parser;
+use asciigen::{
+    ascii_generator, brute_force, export, fitness, genetic_algorithm, halfblock, image_processor, luminance, Charset, CharsetPreset, LockMask, NoProgress,
+    PixelWeights, ProgressEvent, ProgressKind, ProgressSink, Tuning,
+};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use image::GenericImageView;
 
+/// Set when machine-readable output goes to stdout, so human-oriented status lines move to stderr
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Prints a status line to stdout, or to stderr while stdout is reserved for machine-readable output
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if STATUS_TO_STDERR.load(Ordering::Relaxed) {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
 #[derive(Parser)]
 #[command(name = "asciigen")]
 #[command(about = "Generate ASCII art from images using genetic algorithms")]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Without a subcommand the arguments are those of `generate`
+    #[command(flatten)]
+    generate: Args,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Optimize art with the genetic algorithm (the default when no subcommand is given)
+    Generate(Box<Args>),
+    /// Convert an image without evolution, from brightness alone or by picking the best character for each cell
+    Convert(ConvertArgs),
+    /// Score an existing text art file against an image with the optimizers' fitness
+    Score(ScoreArgs),
+    /// Time the genetic algorithm and the single-pass converters on an image
+    Bench(BenchArgs),
+    /// Play back an animation written by asciigen (numbered text frames or an .ans file)
+    Play(PlayArgs),
+    /// Print the active character set ranked by measured glyph coverage
+    CharsetInfo(GlyphArgs),
+    /// Save the character set rendered at the fitness font size as a contact-sheet PNG
+    Atlas(AtlasArgs),
+}
+
+/// Character set and font options shared by every command that renders glyphs
+#[derive(clap::Args)]
+struct GlyphArgs {
+    #[arg(long, help = "Custom character set to draw with, e.g. \"@#%*+=-:. \" (2-256 printable characters, default is the built-in set)")]
+    charset: Option<Charset>,
+
+    #[arg(long, conflicts_with = "charset", help = "Built-in character set: default, minimal, blocks, lines, full or braille")]
+    charset_preset: Option<CharsetPreset>,
+
+    #[arg(long, default_value = "12", help = "Font size in pixels for fitness rendering (4-64; larger captures more detail, smaller is faster)")]
+    font_size: f32,
+}
+
+impl GlyphArgs {
+    /// Returns the character set selected by --charset or --charset-preset
+    fn charset(&self) -> Charset {
+        match (&self.charset, self.charset_preset) {
+            (Some(charset), _) => charset.clone(),
+            (None, Some(preset)) => preset.charset(),
+            (None, None) => Charset::default(),
+        }
+    }
+
+    /// Builds the glyph renderer for the selected character set and font size
+    fn ascii_generator(&self) -> ascii_generator::AsciiGenerator {
+        let mut ascii_gen = ascii_generator::AsciiGenerator::with_charset(self.charset());
+        ascii_gen.set_font_size(self.font_size);
+        ascii_gen
+    }
+}
+
+/// Overlap metric options shared by `generate` and `score`
+#[derive(clap::Args, Clone, Copy)]
+struct OverlapArgs {
+    #[arg(long, value_name = "LEVELS", default_value = "30", value_parser = clap::value_parser!(u8).range(1..), help = "Intensity difference (1-255) below which a lit pixel counts as matched; higher favors coverage, lower favors sharpness")]
+    tolerance: u8,
+
+    #[arg(long, value_name = "PENALTY", default_value = "0.005", help = "Fitness lost per stray lit pixel, relative to one matched pixel; higher keeps the background cleaner")]
+    fp_penalty: f64,
+}
+
+impl OverlapArgs {
+    /// Returns the overlap metric constants, or an error for a negative or non-finite penalty
+    fn params(&self) -> Result<fitness::OverlapParams, String> {
+        if !(self.fp_penalty >= 0.0 && self.fp_penalty.is_finite()) {
+            return Err("--fp-penalty must be zero or a positive number".to_string());
+        }
+        Ok(fitness::OverlapParams {
+            tolerance: self.tolerance,
+            false_positive_penalty: self.fp_penalty,
+        })
+    }
+}
+
+/// Tone adjustments of the grayscale target shared by `generate`, `convert` and `score`
+#[derive(clap::Args)]
+struct ToneArgs {
+    #[arg(long, value_name = "VALUE", default_value = "1.0", help = "Gamma applied to the grayscale target before scoring: above 1 brings out detail in dark images, below 1 in washed-out ones")]
+    gamma: f64,
+
+    #[arg(long, value_name = "BLACK,WHITE[,GAMMA]", value_parser = parse_levels, help = "Remap the grayscale target so BLACK and darker become black, WHITE and brighter white, with GAMMA (default 1) bending the tones between, e.g. 30,220,1.2")]
+    levels: Option<image_processor::Levels>,
+
+    #[arg(long, conflicts_with_all = ["equalize", "levels"], help = "Stretch the grayscale target so its darkest tones become black and its brightest white")]
+    auto_contrast: bool,
+
+    #[arg(long, help = "Histogram-equalize the grayscale target, spreading its tones evenly over the full range")]
+    equalize: bool,
+
+    #[arg(long, value_name = "CLIP", num_args = 0..=1, default_missing_value = "2.0", conflicts_with = "equalize", help = "Equalize the grayscale target tile by tile (CLAHE), keeping detail in both dark and bright regions; CLIP (at least 1) limits how much contrast is added (bare --clahe means 2.0)")]
+    clahe: Option<f64>,
+
+    #[arg(long, value_name = "N", default_value = "8", requires = "clahe", help = "Tiles along each side of the image for --clahe (1-64)")]
+    clahe_tiles: u32,
+
+    #[arg(long, value_name = "N", help = "Quantize the grayscale target to N evenly spaced gray levels (2-256) after the other adjustments, which flattens noise so the art comes out cleaner")]
+    posterize: Option<u32>,
+}
+
+impl ToneArgs {
+    /// Returns an error for a tone setting out of range
+    fn validate(&self) -> Result<(), String> {
+        if !(self.gamma > 0.0 && self.gamma.is_finite()) {
+            return Err("--gamma must be a positive number".to_string());
+        }
+        if self.clahe.is_some_and(|clip_limit| !(clip_limit >= 1.0 && clip_limit.is_finite())) {
+            return Err("--clahe clip limit must be at least 1".to_string());
+        }
+        if !(1..=64).contains(&self.clahe_tiles) {
+            return Err("--clahe-tiles must be between 1 and 64".to_string());
+        }
+        if self.posterize.is_some_and(|levels| !(2..=256).contains(&levels)) {
+            return Err("--posterize must be between 2 and 256 gray levels".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether any tone adjustment of the target is enabled
+    fn adjusts_tones(&self) -> bool {
+        self.gamma != 1.0 || self.levels.is_some() || self.auto_contrast || self.equalize || self.clahe.is_some() || self.posterize.is_some()
+    }
+
+    /// Builds the image processor with the tone adjustments applied to the target
+    fn image_processor(&self) -> image_processor::ImageProcessor {
+        let mut processor = image_processor::ImageProcessor::new();
+        processor.set_levels(self.levels);
+        processor.set_auto_contrast(self.auto_contrast);
+        processor.set_equalize(self.equalize);
+        processor.set_clahe(self.clahe.map(|clip_limit| image_processor::Clahe { tiles: self.clahe_tiles, clip_limit }));
+        processor.set_gamma(self.gamma);
+        processor.set_posterize(self.posterize);
+        processor
+    }
+}
+
+/// Input, sizing and output options shared by `generate` and `convert`
+#[derive(clap::Args)]
+struct CommonArgs {
+    #[arg(help = "Input image file path, http(s) URL (with the http feature), or - to read image data from stdin")]
+    input: Option<PathBuf>,
+
+    #[arg(short, long, help = "Width in characters")]
+    width: Option<u32>,
+
+    #[arg(short = 'H', long, help = "Height in characters")]
+    height: Option<u32>,
+
+    #[arg(long, value_name = "WxH", value_parser = parse_size, conflicts_with_all = ["width", "height"], help = "Width and height in characters at once, e.g. 120x40 (same as -w 120 -H 40)")]
+    size: Option<(u32, u32)>,
+
+    #[arg(long, value_name = "RATIO", default_value = "0.5", help = "Width:height ratio of a character cell in the font the art will be shown in, which keeps the image's proportions (e.g. 0.45 for narrow fonts)")]
+    cell_aspect: f32,
+
+    #[arg(long, value_enum, default_value = "contain", help = "With both --width and --height: contain (the largest grid inside WxH that keeps the image's proportions), cover (exactly WxH, cropping the image's edges) or stretch (exactly WxH, distorting the image)")]
+    fit: Fit,
+
+    #[arg(short, long, help = "Output file path (optional)")]
+    output: Option<PathBuf>,
+
+    #[arg(long, default_value = "text", help = "Output format: text, ans (CP437 ANSI art with SAUCE record) or json (art plus run metadata; printed to stdout without --output)")]
+    format: export::OutputFormat,
+
+    #[arg(short = 'd', long, help = "Save debug images (converted input, final ASCII art and per-cell fitness heatmap as PNG files)")]
+    debug: bool,
+
+    #[arg(short = 'v', long, help = "Verbose output: display fittest ASCII art after each progress update")]
+    verbose: bool,
+
+    #[arg(short = 'W', long, help = "Use white background (default is black background with white characters)")]
+    white_background: bool,
+
+    #[arg(short = 'I', long, help = "Invert source image colors (useful for negative images)")]
+    invert_source: bool,
+
+    #[command(flatten)]
+    tone: ToneArgs,
+
+    #[arg(long, help = "Disable the interactive terminal UI and use console output instead")]
+    no_ui: bool,
+
+    #[arg(short = 'q', long, conflicts_with_all = ["log", "verbose"], help = "With --no-ui, show no progress bar or progress lines")]
+    quiet: bool,
+
+    #[arg(long, help = "With --no-ui, print one plain progress line per status update instead of the progress bar (the default when stderr is not a terminal)")]
+    log: bool,
+
+    #[arg(long, help = "Report how often each character of the set appears in the final art (and in every update with --verbose --no-ui)")]
+    char_usage: bool,
+
+    #[command(flatten)]
+    glyphs: GlyphArgs,
+}
+
+impl CommonArgs {
+    /// Builds the glyph renderer for the selected character set and font size
+    fn ascii_generator(&self) -> ascii_generator::AsciiGenerator {
+        self.glyphs.ascii_generator()
+    }
+
+    /// Whether any tone adjustment of the target is enabled
+    fn adjusts_tones(&self) -> bool {
+        self.tone.adjusts_tones()
+    }
+
+    /// Builds the image processor with the tone adjustments applied to the target
+    fn image_processor(&self) -> image_processor::ImageProcessor {
+        self.tone.image_processor()
+    }
+}
+
+/// Arguments of `generate`
+#[derive(clap::Args)]
 struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[arg(short, long, default_value = "100", help = "Number of generations (0 = continuous mode)")]
+    generations: u32,
+
+    #[arg(short, long, default_value = "0", help = "Number of threads for parallel fitness evaluation (0 = one per logical core)")]
+    jobs: usize,
+
+    #[arg(long, help = "Evaluate the overlap fitness on the GPU, falling back to the CPU when no GPU is found (needs the gpu feature)")]
+    gpu: bool,
+
+    #[arg(short = 'i', long, help = "Character to initialize art buffers with (95% of characters, 5% random)")]
+    init_char: Option<char>,
+
+    #[arg(short = 's', long, default_value = "1.0", help = "Status update interval in seconds")]
+    status_interval: f64,
+
+    #[arg(short = 'p', long, default_value = "80", help = "Population size (20-1000)")]
+    population: usize,
+
+    #[arg(long, conflicts_with = "webcam", help = "Re-run whenever the input file changes, rewriting the output file in place")]
+    watch: bool,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "webcam", help = "Write one CSV row per generation to FILE: best, mean, median and worst fitness, diversity, mutation rate and elapsed seconds")]
+    stats_csv: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "webcam", help = "Write a JSON Lines run log to FILE: timestamped start (with parameters), generation, stagnation, restart and finish events")]
+    log_jsonl: Option<PathBuf>,
+
+    #[arg(long, value_name = "SECONDS", conflicts_with = "webcam", help = "Every SECONDS, replace --output with the best art so far (atomic rename), so a crash or power loss keeps the progress")]
+    autosave: Option<f64>,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "webcam", help = "Re-read FILE when it changes during the run and apply its `name = value` lines: status_interval, mutation_rate, crossover_rate, elite_size")]
+    control_file: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "webcam", help = "Where a run stopped by SIGTERM saves its population for --resume [default: checkpoint_<input>.json]")]
+    checkpoint: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["webcam", "init_char", "seed_art", "hybrid", "smart_init"], help = "Continue the run saved in a SIGTERM checkpoint; pass the same options, -g includes the generations already run")]
+    resume: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR", conflicts_with = "webcam", help = "Save the best art rendered as DIR/frame_<generation>.png every --frame-every generations, for assembling timelapses")]
+    frames_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "N", default_value = "10", help = "Generations between the frames saved by --frames-dir")]
+    frame_every: u32,
+
+    #[arg(long, default_value = "1", help = "Images converted concurrently when the input is a directory or wildcard pattern")]
+    batch_jobs: usize,
+
+    #[arg(long, default_value = "10", help = "Frames per second sampled from video input")]
+    fps: f64,
+
+    #[arg(long, value_name = "DEVICE", conflicts_with = "input", help = "Live ASCII mirror from a capture device via ffmpeg (default /dev/video0 on Linux, 0 on macOS)")]
+    webcam: Option<Option<String>>,
+
+    #[arg(long, value_enum, default_value = "ascii", help = "Output mode: ascii (optimized characters), beam (beam search over characters) or halfblock (ANSI truecolor, two pixels per cell)")]
+    mode: Mode,
+
+    #[arg(long, value_name = "K", default_value_t = brute_force::DEFAULT_BEAM_WIDTH, help = "Partial grids kept at every cell by --mode beam (1 is a greedy search)")]
+    beam_width: usize,
+
+    #[arg(short = 'b', long, help = "Use brute-force mode instead of genetic algorithm (the convert command does this with its own options)")]
+    brute_force: bool,
+
+    #[arg(long, value_name = "CANDIDATES", num_args = 0..=1, default_missing_value = "6", help = "With --brute-force, choose each 2x2 block of cells jointly from every combination of each cell's CANDIDATES best characters (bare --block-search means 6)")]
+    block_search: Option<usize>,
+
+    #[arg(long, help = "With --brute-force, pass each cell's brightness error on to the cells not yet chosen (Floyd-Steinberg), rendering smooth gradients as ink density")]
+    error_diffusion: bool,
+
+    #[arg(long, value_name = "ORDER", default_value = "raster", help = "With --brute-force, the order cells are chosen in: raster, random (seeded by --seed), center-out or detail (most detailed target first); only matters under edge scoring, where cells are judged with their neighbours")]
+    traversal: brute_force::TraversalOrder,
+
+    #[arg(long, value_enum, help = "Quality preset: fast (brute force, small font), balanced (the defaults) or best (large population, many generations); explicit options override it")]
+    preset: Option<Preset>,
+
+    #[arg(long, default_value = "0.01", help = "Per-character mutation probability (0-1)")]
+    mutation_rate: f64,
+
+    #[arg(long, default_value = "point", help = "Mutation operator: point (random character), swap (two cells), shift (rotate a row by one), block-copy (copy a small block elsewhere) or mixed")]
+    mutation: genetic_algorithm::MutationOperator,
+
+    #[arg(long, value_name = "BIAS", default_value = "0", help = "Bias mutation toward the cells that match the target worst, from 0 (uniform) to 1 (proportional to each cell's error)")]
+    targeted_mutation: f64,
+
+    #[arg(long, default_value = "0.8", help = "Crossover probability (0-1): per character for uniform, per block for block, per pair of parents for two-point and rectangle, per line for rows and columns")]
+    crossover_rate: f64,
+
+    #[arg(long, default_value = "uniform", help = "Crossover operator: uniform (per character), two-point (one contiguous segment), block (runs of 8 characters), rectangle (a sub-grid), rows or columns")]
+    crossover: genetic_algorithm::CrossoverOperator,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "webcam", help = "Text art to refine: part of the initial population starts from it instead of noise (its grid sets the size unless -w/-H is given)")]
+    seed_art: Option<PathBuf>,
+
+    #[arg(long, default_value = "0.5", help = "Fraction of the initial population seeded from --seed-art (0-1)")]
+    seed_art_fraction: f64,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "webcam", help = "Template text file: its characters are locked in place and only the free cells are optimized (its grid sets the size unless -w/-H is given)")]
+    template: Option<PathBuf>,
+
+    #[arg(long, default_value = " ", help = "Character marking free cells in --template")]
+    template_free: char,
+
+    #[arg(long, value_name = "IMAGE", conflicts_with = "webcam", help = "Image marking regions excluded from fitness, such as watermarks or borders: white pixels are ignored, black pixels are scored")]
+    ignore_mask: Option<PathBuf>,
+
+    #[arg(long, value_name = "STRENGTH", help = "Weight fitness toward the image center: corner pixels count 1/(1+STRENGTH) as much as the center (0 = uniform)")]
+    center_weight: Option<f64>,
+
+    #[arg(long, value_name = "WEIGHT", default_value = "0", help = "Weight of a Sobel edge-agreement term relative to pixel overlap, so outlines are kept (0 = off, 1 = equal share)")]
+    edge_weight: f64,
+
+    #[arg(long = "fitness", value_name = "METRIC", default_value = "overlap", help = "Fitness metric: overlap (pixel matches, fastest), mse (root mean squared error), ssim (structural similarity) or edge (Sobel edge agreement)")]
+    fitness_metric: fitness::FitnessMetric,
+
+    #[command(flatten)]
+    overlap: OverlapArgs,
+
+    #[arg(long, value_name = "PERCENT", help = "Maximum percentage of cells holding a non-space character (0-100); surplus ink is removed before scoring")]
+    max_ink: Option<f64>,
+
+    #[arg(long, default_value = "generational", help = "Population replacement strategy: generational or crowding")]
+    replacement: genetic_algorithm::ReplacementStrategy,
+
+    #[arg(long, default_value = "tournament", help = "Parent selection: tournament, roulette (fitness-proportionate), rank (linear ranking) or sus (stochastic universal sampling)")]
+    selection: genetic_algorithm::SelectionStrategy,
+
+    #[arg(long, value_name = "N", default_value = "3", help = "Individuals drawn per tournament in tournament selection (1 to the population size); larger means stronger selection pressure")]
+    tournament_size: usize,
+
+    #[arg(long, value_name = "OBJECTIVE", help = "Multi-objective evolution (NSGA-II) trading fitness against ink (sparser art) or edge (outline fidelity); pick from the Pareto front in the UI")]
+    pareto: Option<genetic_algorithm::ParetoObjective>,
+
+    #[arg(long, help = "Random seed for reproducible runs (a random seed is chosen and printed if omitted)")]
+    seed: Option<u64>,
+
+    #[arg(long, default_value = "0", help = "Number of ALPS age layers (0 = disabled, otherwise 2-10)")]
+    alps_layers: usize,
+
+    #[arg(long, default_value = "10", help = "Generations between fresh random individuals entering the bottom ALPS layer")]
+    alps_age_gap: u32,
+
+    #[arg(long, value_name = "SWEEPS", num_args = 0..=1, default_value = "0", default_missing_value = "1", help = "After evolution, polish the best art with up to this many brute-force sweeps over its cells (bare --refine means 1)")]
+    refine: usize,
+
+    #[arg(long, value_name = "FRACTION", num_args = 0..=1, default_missing_value = "0.5", conflicts_with = "seed_art", help = "Run a quick brute-force pass first and seed this fraction of the initial population with noisy copies of its result (bare --hybrid means 0.5)")]
+    hybrid: Option<f64>,
+
+    #[arg(long, value_name = "FRACTION", num_args = 0..=1, default_missing_value = "0.5", conflicts_with_all = ["init_char", "seed_art", "hybrid"], help = "Seed this fraction of the initial population from a brightness-to-character mapping of the target instead of noise (bare --smart-init means 0.5)")]
+    smart_init: Option<f64>,
+
+    #[arg(long, value_name = "N", default_value = "0", help = "Stop evolution early once the best fitness has not improved by more than --stall-epsilon in N generations (0 = never)")]
+    stall_generations: u32,
+
+    #[arg(long, value_name = "EPSILON", default_value = "0.0001", help = "Smallest gain in best fitness (0-1 scale) that counts as progress for --stall-generations")]
+    stall_epsilon: f64,
+
+    #[arg(long, help = "When --stall-generations detects a stall, restart from a fresh random population instead of stopping, keeping the best art found so far, until the generation budget runs out")]
+    restart_on_stall: bool,
+
+    #[arg(long, value_name = "FITNESS", help = "Stop evolution as soon as the best fitness reaches this value (0-1, e.g. 0.85)")]
+    target_fitness: Option<f64>,
+
+    #[arg(long, value_name = "CELLS", default_value = "0", help = "Memetic local search: random cells of each elite set to their best-scoring character every generation (0 = disabled)")]
+    local_search: usize,
+
+    #[arg(long, value_name = "N", default_value = "0", help = "Number of islands: sub-populations that breed separately in parallel (0 = disabled, otherwise 2 or more)")]
+    islands: usize,
+
+    #[arg(long, value_name = "N", default_value = "10", help = "Generations between migrations of each island's best individuals to the next island")]
+    migration_interval: u32,
+
+    #[arg(long, value_name = "N", default_value = "2", help = "Individuals copied from each island to the next at every migration")]
+    migrants: usize,
+
+    #[arg(long, value_name = "FRACTION", help = "Fitness sharing: parents are selected on fitness divided among individuals differing in fewer than this fraction of cells (0-1)")]
+    sharing_radius: Option<f64>,
+}
+
+impl Args {
+    /// Whether the art comes from a search over characters (brute force or beam) rather than the genetic algorithm
+    fn searches(&self) -> bool {
+        self.brute_force || self.mode == Mode::Beam
+    }
+
+    /// Fills the options left at their defaults from `--preset`; options given on the command line win
+    fn apply_preset(&mut self, matches: &ArgMatches) {
+        let Some(preset) = self.preset else { return };
+        let settings = preset.settings();
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+
+        if unset("brute_force") {
+            self.brute_force = settings.brute_force;
+        }
+        if unset("population") {
+            self.population = settings.population;
+        }
+        if unset("generations") {
+            self.generations = settings.generations;
+        }
+        if unset("mutation_rate") {
+            self.mutation_rate = settings.mutation_rate;
+        }
+        if unset("font_size") {
+            self.common.glyphs.font_size = settings.font_size;
+        }
+    }
+}
+
+/// Coherent optimizer settings for users who would rather not tune the genetic algorithm themselves
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Preset {
+    /// Single brute-force pass with small glyphs
+    Fast,
+    /// The default genetic algorithm settings
+    Balanced,
+    /// Large population, many generations, fine mutation and large glyphs
+    Best,
+}
+
+/// Values a `Preset` assigns
+struct PresetSettings {
+    brute_force: bool,
+    population: usize,
+    generations: u32,
+    mutation_rate: f64,
+    font_size: f32,
+}
+
+impl Preset {
+    fn settings(self) -> PresetSettings {
+        match self {
+            Preset::Fast => PresetSettings { brute_force: true, population: 40, generations: 30, mutation_rate: 0.02, font_size: 8.0 },
+            Preset::Balanced => PresetSettings { brute_force: false, population: 80, generations: 100, mutation_rate: 0.01, font_size: 12.0 },
+            Preset::Best => PresetSettings { brute_force: false, population: 200, generations: 1000, mutation_rate: 0.005, font_size: 16.0 },
+        }
+    }
+}
+
+/// Arguments of `convert`
+#[derive(clap::Args)]
+struct ConvertArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[arg(long, value_enum, default_value = "luminance", help = "Conversion method: luminance (instant, matches cell brightness to glyph coverage) or brute-force (best character per cell)")]
+    method: ConvertMethod,
+}
+
+/// How `convert` chooses each cell's character
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ConvertMethod {
+    /// Match each cell's average brightness to the glyph with the closest ink coverage
+    Luminance,
+    /// Try every character at each position and keep the best fitting one
+    BruteForce,
+}
+
+/// Arguments of `score`
+#[derive(clap::Args)]
+struct ScoreArgs {
+    #[arg(help = "Text art file to score")]
+    art: PathBuf,
+
+    #[arg(help = "Image the art should depict")]
+    image: PathBuf,
+
+    #[arg(short = 'W', long, help = "Score as dark characters on a white background")]
+    white_background: bool,
+
+    #[arg(short = 'I', long, help = "Invert source image colors before scoring")]
+    invert_source: bool,
+
+    #[command(flatten)]
+    tone: ToneArgs,
+
+    #[arg(long, help = "Print the metrics as JSON")]
+    json: bool,
+
+    #[command(flatten)]
+    overlap: OverlapArgs,
+
+    /// The character set defaults to the characters the art uses
+    #[command(flatten)]
+    glyphs: GlyphArgs,
+}
+
+/// Arguments of `bench`
+#[derive(clap::Args)]
+struct BenchArgs {
     #[arg(help = "Input image file path")]
     input: PathBuf,
 
-    #[arg(short, long, help = "Width in characters")]
-    width: Option<u32>,
+    #[arg(short, long, default_value = "40", help = "Width in characters")]
+    width: u32,
+
+    #[arg(short, long, default_value = "20", help = "Generations to time")]
+    generations: u32,
+
+    #[arg(short = 'p', long, default_value = "80", help = "Population size (20-1000)")]
+    population: usize,
+
+    #[arg(short, long, default_value = "0", help = "Number of threads for parallel fitness evaluation (0 = one per logical core)")]
+    jobs: usize,
+
+    #[arg(long, default_value = "1", help = "Random seed, fixed so repeated runs do the same work")]
+    seed: u64,
+
+    #[arg(long, help = "Also time the genetic algorithm with fitness evaluated on the GPU (needs the gpu feature)")]
+    gpu: bool,
+
+    #[command(flatten)]
+    glyphs: GlyphArgs,
+}
+
+/// Arguments of `play`
+#[derive(clap::Args)]
+struct PlayArgs {
+    #[arg(help = "An .ans animation, or a text frame series given by its output path (art.txt) or any frame (art_0001.txt)")]
+    input: PathBuf,
+
+    #[arg(long, default_value = "10", help = "Frames per second")]
+    fps: f64,
+
+    #[arg(long = "loop", help = "Repeat until interrupted with Ctrl-C")]
+    repeat: bool,
+}
+
+/// Arguments of `atlas`
+#[derive(clap::Args)]
+struct AtlasArgs {
+    #[arg(short, long, default_value = "atlas.png", help = "PNG file to write")]
+    output: PathBuf,
+
+    #[arg(long, default_value = "16", help = "Glyphs per row")]
+    columns: u32,
+
+    #[arg(short = 'W', long, help = "Draw dark characters on a white background")]
+    white_background: bool,
+
+    #[command(flatten)]
+    glyphs: GlyphArgs,
+}
+
+/// Parses a `--size` of the form `<width>x<height>` in characters, both at least 1
+fn parse_size(spec: &str) -> Result<(u32, u32), String> {
+    let (width, height) = spec.split_once(['x', 'X']).ok_or_else(|| format!("expected WIDTHxHEIGHT, e.g. 120x40, got {:?}", spec))?;
+    let side = |value: &str, name: &str| match value.trim().parse::<u32>() {
+        Ok(0) => Err(format!("{} must be at least 1", name)),
+        Ok(value) => Ok(value),
+        Err(_) => Err(format!("invalid {} {:?}", name, value)),
+    };
+    Ok((side(width, "width")?, side(height, "height")?))
+}
+
+/// Parses `--levels` of the form `<black>,<white>[,<gamma>]`: black below white, both 0-255, and a positive gamma
+fn parse_levels(spec: &str) -> Result<image_processor::Levels, String> {
+    let parts: Vec<&str> = spec.split(',').map(str::trim).collect();
+    if !(2..=3).contains(&parts.len()) {
+        return Err(format!("expected BLACK,WHITE or BLACK,WHITE,GAMMA, e.g. 30,220,1.2, got {:?}", spec));
+    }
+    let level = |value: &str, name: &str| value.parse::<u8>().map_err(|_| format!("{} must be a level from 0 to 255, got {:?}", name, value));
+    let (black, white) = (level(parts[0], "black")?, level(parts[1], "white")?);
+    if black >= white {
+        return Err(format!("black ({}) must be below white ({})", black, white));
+    }
+    let gamma = match parts.get(2) {
+        Some(value) => value.parse::<f64>().ok().filter(|gamma| *gamma > 0.0 && gamma.is_finite()).ok_or_else(|| format!("gamma must be a positive number, got {:?}", value))?,
+        None => 1.0,
+    };
+    Ok(image_processor::Levels { black, white, gamma })
+}
+
+/// How the image is fitted to a grid given by both --width and --height
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Fit {
+    /// Shrink one side of the grid so the whole image keeps its proportions
+    Contain,
+    /// Fill the grid exactly, cropping the image to its proportions
+    Cover,
+    /// Fill the grid exactly, scaling the image unevenly
+    Stretch,
+}
+
+/// How the input image is turned into text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    /// Optimize characters against the image with the genetic algorithm or brute force
+    Ascii,
+    /// Fill characters in raster order with a beam search, a middle ground between brute force and evolution
+    Beam,
+    /// Convert pixels directly into colored upper half blocks
+    Halfblock,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Parsed in two steps so presets can tell explicit options from defaults
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    match cli.command {
+        None => {
+            let mut args = cli.generate;
+            args.apply_preset(&matches);
+            run_generate(args)
+        }
+        Some(Command::Generate(mut args)) => {
+            args.apply_preset(matches.subcommand_matches("generate").expect("generate matches"));
+            run_generate(*args)
+        }
+        Some(Command::Convert(args)) => run_convert(args),
+        Some(Command::Score(args)) => run_score(&args),
+        Some(Command::Bench(args)) => run_bench(&args),
+        Some(Command::Play(args)) => run_play(&args),
+        Some(Command::CharsetInfo(glyphs)) => {
+            print_charset_info(&glyphs.ascii_generator());
+            Ok(())
+        }
+        Some(Command::Atlas(args)) => run_atlas(&args),
+    }
+}
+
+/// Checks the options shared by `generate` and `convert`, exiting with an error message on invalid combinations
+/// JSON output to stdout also moves status lines to stderr and disables the interactive UI, as does output that is not
+/// a terminal
+/// `size_required` is false when the size can come from elsewhere, such as the grid of `--seed-art`; otherwise a missing
+/// size is fitted to the terminal, or falls back to `DEFAULT_WIDTH` columns
+fn validate_common(common: &mut CommonArgs, input_required: bool, size_required: bool) {
+    if let Some((width, height)) = common.size {
+        (common.width, common.height) = (Some(width), Some(height));
+    }
+
+    if input_required && common.input.is_none() {
+        eprintln!("Error: An input image is required");
+        std::process::exit(1);
+    }
+
+    if !(common.cell_aspect > 0.0 && common.cell_aspect.is_finite()) {
+        eprintln!("Error: --cell-aspect must be a positive number");
+        std::process::exit(1);
+    }
+
+    if let Err(e) = common.tone.validate() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    if common.format == export::OutputFormat::Ans {
+        if common.output.is_none() {
+            eprintln!("Error: --format ans requires --output");
+            std::process::exit(1);
+        }
+        if common.glyphs.charset().chars().iter().any(|&c| export::to_cp437(c).is_none()) {
+            eprintln!("Error: --format ans requires a character set that CP437 can represent");
+            std::process::exit(1);
+        }
+    }
+
+    if common.format == export::OutputFormat::Json && common.output.is_none() {
+        // Keep stdout parseable: status goes to stderr and the interactive UI is disabled
+        STATUS_TO_STDERR.store(true, Ordering::Relaxed);
+        common.no_ui = true;
+    }
+
+    if !std::io::stdout().is_terminal() || !std::io::stderr().is_terminal() {
+        // Pipes and CI logs get console output instead of the UI's escape sequences
+        common.no_ui = true;
+    }
+
+    if size_required && common.width.is_none() && common.height.is_none() {
+        match terminal_art_size(!common.no_ui) {
+            Some((width, height)) => {
+                status!("No size given; fitting the art to the terminal ({}x{} characters)", width, height);
+                (common.width, common.height) = (Some(width), Some(height));
+            }
+            None => {
+                status!("No size given and stdout is not a terminal; using a width of {} characters", DEFAULT_WIDTH);
+                common.width = Some(DEFAULT_WIDTH);
+            }
+        }
+    }
+
+    if common.fit != Fit::Contain && (common.width.is_none() || common.height.is_none()) {
+        eprintln!("Error: --fit only applies when both --width and --height are given");
+        std::process::exit(1);
+    }
+
+    if !(4.0..=64.0).contains(&common.glyphs.font_size) {
+        eprintln!("Error: Font size must be between 4 and 64");
+        std::process::exit(1);
+    }
+}
+
+/// Width:height ratio of a character cell assumed by `--cell-aspect` and the `bench` command: twice as tall as wide
+const DEFAULT_CELL_ASPECT: f32 = 0.5;
+
+/// Art width when no size is given and stdout is not a terminal
+const DEFAULT_WIDTH: u32 = 80;
+
+/// The art size that fits the terminal on stdout, leaving room for the interactive UI's statistics and footer when
+/// `ui` is set, or for the title line above the art and the prompt below it otherwise; None if stdout is not a terminal
+fn terminal_art_size(ui: bool) -> Option<(u32, u32)> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let (columns, rows) = crossterm::terminal::size().ok().filter(|&(columns, rows)| columns > 0 && rows > 0)?;
+    Some(if ui { terminal_ui::art_area(columns, rows) } else { (columns as u32, (rows as u32).saturating_sub(2).max(1)) })
+}
+
+/// Runs the `generate` command: genetic algorithm (or brute force) optimization of images, video, or a webcam
+fn run_generate(mut args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    validate_common(&mut args.common, args.webcam.is_none(), args.seed_art.is_none() && args.template.is_none());
+    let auto_jobs = args.jobs == 0;
+    if auto_jobs {
+        args.jobs = genetic_algorithm::available_threads();
+        if !args.searches() {
+            status!("Using {} thread(s), one per logical core", args.jobs);
+        }
+    }
+
+    if let Some(max_ink) = args.max_ink {
+        if !(0.0..=100.0).contains(&max_ink) {
+            eprintln!("Error: --max-ink must be a percentage between 0 and 100");
+            std::process::exit(1);
+        }
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --max-ink is enforced by the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+        if !args.common.glyphs.charset().has_space() {
+            eprintln!("Error: --max-ink needs a character set with a space to blank surplus cells");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(candidates) = args.block_search {
+        if !args.brute_force || args.mode != Mode::Ascii {
+            eprintln!("Error: --block-search is a brute-force search; it needs --brute-force in ascii mode");
+            std::process::exit(1);
+        }
+        if candidates == 0 {
+            eprintln!("Error: --block-search needs at least 1 candidate per cell");
+            std::process::exit(1);
+        }
+    }
+
+    if args.error_diffusion && (!args.brute_force || args.mode != Mode::Ascii || args.block_search.is_some()) {
+        eprintln!("Error: --error-diffusion needs --brute-force in ascii mode and cannot be combined with --block-search");
+        std::process::exit(1);
+    }
+
+    if args.traversal != brute_force::TraversalOrder::Raster && (!args.brute_force || args.mode != Mode::Ascii || args.error_diffusion) {
+        eprintln!("Error: --traversal needs --brute-force in ascii mode and cannot be combined with --error-diffusion, which runs in raster order");
+        std::process::exit(1);
+    }
+
+    if args.brute_force && args.mode == Mode::Beam {
+        eprintln!("Error: --brute-force and --mode beam are alternative searches; pick one");
+        std::process::exit(1);
+    }
+    if args.beam_width == 0 {
+        eprintln!("Error: --beam-width must be at least 1");
+        std::process::exit(1);
+    }
+    if args.beam_width != brute_force::DEFAULT_BEAM_WIDTH && args.mode != Mode::Beam {
+        eprintln!("Error: --beam-width only applies to --mode beam");
+        std::process::exit(1);
+    }
+
+    if args.refine != 0 && (args.searches() || args.mode == Mode::Halfblock || args.pareto.is_some() || args.max_ink.is_some()) {
+        eprintln!("Error: --refine polishes evolved ascii art; it cannot be combined with --brute-force, --mode beam, --mode halfblock, --pareto or --max-ink");
+        std::process::exit(1);
+    }
+
+    if args.template.is_some() && args.mode == Mode::Halfblock {
+        eprintln!("Error: --template is only supported in ascii mode");
+        std::process::exit(1);
+    }
+
+    if args.ignore_mask.is_some() && args.mode == Mode::Halfblock {
+        eprintln!("Error: --ignore-mask is only supported in ascii mode");
+        std::process::exit(1);
+    }
+
+    if args.common.char_usage && args.mode == Mode::Halfblock {
+        eprintln!("Error: --char-usage is only supported in ascii mode");
+        std::process::exit(1);
+    }
+
+    if args.common.adjusts_tones() && args.mode == Mode::Halfblock {
+        eprintln!("Error: --gamma, --levels, --auto-contrast, --equalize, --clahe and --posterize are only supported in ascii mode");
+        std::process::exit(1);
+    }
+
+    if !(args.edge_weight >= 0.0 && args.edge_weight.is_finite()) {
+        eprintln!("Error: --edge-weight must be zero or a positive number");
+        std::process::exit(1);
+    }
+    if args.edge_weight > 0.0 && args.mode == Mode::Halfblock {
+        eprintln!("Error: --edge-weight is only supported in ascii mode");
+        std::process::exit(1);
+    }
+    if let Err(e) = args.overlap.params() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Some(center_weight) = args.center_weight {
+        if !(center_weight >= 0.0 && center_weight.is_finite()) {
+            eprintln!("Error: --center-weight must be zero or a positive number");
+            std::process::exit(1);
+        }
+        if args.mode == Mode::Halfblock {
+            eprintln!("Error: --center-weight is only supported in ascii mode");
+            std::process::exit(1);
+        }
+    }
+
+    if args.seed_art.is_some() {
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --seed-art refines art with the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+        if !(0.0..=1.0).contains(&args.seed_art_fraction) {
+            eprintln!("Error: --seed-art-fraction must be between 0 and 1");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(fraction) = args.hybrid {
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --hybrid seeds the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            eprintln!("Error: --hybrid fraction must be greater than 0 and at most 1");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(fraction) = args.smart_init {
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --smart-init seeds the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            eprintln!("Error: --smart-init fraction must be greater than 0 and at most 1");
+            std::process::exit(1);
+        }
+    }
+
+    if !(args.stall_epsilon >= 0.0 && args.stall_epsilon.is_finite()) {
+        eprintln!("Error: --stall-epsilon must be zero or a positive number");
+        std::process::exit(1);
+    }
+    if args.stall_generations > 0 && (args.searches() || args.mode == Mode::Halfblock) {
+        eprintln!("Error: --stall-generations stops the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+        std::process::exit(1);
+    }
+
+    if args.restart_on_stall && (args.stall_generations == 0 || args.pareto.is_some()) {
+        eprintln!("Error: --restart-on-stall needs --stall-generations and cannot be combined with --pareto");
+        std::process::exit(1);
+    }
+
+    if let Some(target) = args.target_fitness {
+        if !(0.0..=1.0).contains(&target) {
+            eprintln!("Error: --target-fitness must be between 0 and 1");
+            std::process::exit(1);
+        }
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --target-fitness stops the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(flag) = [
+        (args.stats_csv.is_some(), "--stats-csv"),
+        (args.log_jsonl.is_some(), "--log-jsonl"),
+        (args.checkpoint.is_some(), "--checkpoint"),
+        (args.resume.is_some(), "--resume"),
+        (args.control_file.is_some(), "--control-file"),
+        (args.frames_dir.is_some(), "--frames-dir"),
+        (args.gpu, "--gpu"),
+    ]
+        .into_iter()
+        .find_map(|(set, flag)| set.then_some(flag))
+    {
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: {} needs the genetic algorithm; it cannot be combined with --brute-force, --mode beam or --mode halfblock", flag);
+            std::process::exit(1);
+        }
+    }
+
+    if args.gpu {
+        if !cfg!(feature = "gpu") {
+            eprintln!("Error: --gpu requires building with `--features gpu`");
+            std::process::exit(1);
+        }
+        if args.fitness_metric != fitness::FitnessMetric::Overlap
+            || args.edge_weight > 0.0
+            || args.targeted_mutation > 0.0
+            || args.pareto == Some(genetic_algorithm::ParetoObjective::Edge)
+        {
+            eprintln!("Error: --gpu evaluates the overlap fitness only; it cannot be combined with --fitness mse/ssim/edge, --edge-weight, --targeted-mutation or --pareto edge");
+            std::process::exit(1);
+        }
+    }
+
+    if args.frame_every == 0 {
+        eprintln!("Error: --frame-every must be at least 1");
+        std::process::exit(1);
+    }
+
+    if let Some(seconds) = args.autosave {
+        if !(seconds > 0.0 && seconds.is_finite()) {
+            eprintln!("Error: --autosave must be a positive number of seconds");
+            std::process::exit(1);
+        }
+        if args.common.output.is_none() {
+            eprintln!("Error: --autosave writes to the --output file; specify one");
+            std::process::exit(1);
+        }
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --autosave saves the genetic algorithm's best art; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+        if args.common.format == export::OutputFormat::Json {
+            eprintln!("Error: --autosave writes text or ans output; it cannot be combined with --format json");
+            std::process::exit(1);
+        }
+    }
+
+    if args.population < 20 || args.population > 1000 {
+        eprintln!("Error: Population size must be between 20 and 1000");
+        std::process::exit(1);
+    }
+
+    if !(0.0..=1.0).contains(&args.mutation_rate) {
+        eprintln!("Error: Mutation rate must be between 0 and 1");
+        std::process::exit(1);
+    }
+
+    if !(0.0..=1.0).contains(&args.targeted_mutation) {
+        eprintln!("Error: Targeted mutation bias must be between 0 and 1");
+        std::process::exit(1);
+    }
+
+    if !(0.0..=1.0).contains(&args.crossover_rate) {
+        eprintln!("Error: Crossover rate must be between 0 and 1");
+        std::process::exit(1);
+    }
+
+    if args.tournament_size == 0 || args.tournament_size > args.population {
+        eprintln!("Error: Tournament size must be between 1 and the population size");
+        std::process::exit(1);
+    }
+    if args.tournament_size != genetic_algorithm::DEFAULT_TOURNAMENT_SIZE && args.selection != genetic_algorithm::SelectionStrategy::Tournament {
+        eprintln!("Error: --tournament-size only applies to --selection tournament");
+        std::process::exit(1);
+    }
+
+    if args.mode == Mode::Halfblock && args.common.format != export::OutputFormat::Text {
+        eprintln!("Error: --format {} is only supported in ascii mode", if args.common.format == export::OutputFormat::Ans { "ans" } else { "json" });
+        std::process::exit(1);
+    }
+
+    if args.watch && args.generations == 0 && args.common.no_ui {
+        eprintln!("Error: --watch with continuous mode (-g 0) needs the interactive UI to stop each run");
+        std::process::exit(1);
+    }
+
+    if args.pareto.is_some() {
+        if args.searches() || args.mode == Mode::Halfblock {
+            eprintln!("Error: --pareto evolves a population; it cannot be combined with --brute-force, --mode beam or --mode halfblock");
+            std::process::exit(1);
+        }
+        if args.alps_layers != 0
+            || args.replacement != genetic_algorithm::ReplacementStrategy::Generational
+            || args.selection != genetic_algorithm::SelectionStrategy::Tournament
+        {
+            eprintln!("Error: --pareto selects parents and survivors by Pareto rank; it cannot be combined with ALPS, crowding replacement or --selection");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(radius) = args.sharing_radius {
+        if !(radius > 0.0 && radius <= 1.0) {
+            eprintln!("Error: --sharing-radius must be greater than 0 and at most 1");
+            std::process::exit(1);
+        }
+        if args.alps_layers != 0 || args.pareto.is_some() {
+            eprintln!("Error: --sharing-radius cannot be combined with ALPS or --pareto, which select parents their own way");
+            std::process::exit(1);
+        }
+    }
+
+    if args.local_search != 0 && args.pareto.is_some() {
+        eprintln!("Error: --local-search improves fitness alone; it cannot be combined with --pareto");
+        std::process::exit(1);
+    }
+
+    if args.islands != 0 {
+        if args.islands < 2 || args.population / args.islands < 5 {
+            eprintln!("Error: --islands must be at least 2 and leave at least 5 individuals per island");
+            std::process::exit(1);
+        }
+        if args.migration_interval == 0 || args.migrants >= args.population / args.islands {
+            eprintln!("Error: --migration-interval must be positive and --migrants smaller than an island");
+            std::process::exit(1);
+        }
+        if args.alps_layers != 0
+            || args.pareto.is_some()
+            || args.sharing_radius.is_some()
+            || args.replacement != genetic_algorithm::ReplacementStrategy::Generational
+        {
+            eprintln!("Error: --islands breeds each island generationally; it cannot be combined with ALPS, --pareto, --sharing-radius or crowding replacement");
+            std::process::exit(1);
+        }
+    }
+
+    if args.alps_layers != 0 {
+        if !(2..=10).contains(&args.alps_layers) {
+            eprintln!("Error: ALPS layer count must be between 2 and 10");
+            std::process::exit(1);
+        }
+        if args.population / args.alps_layers < 5 {
+            eprintln!("Error: Population size must provide at least 5 individuals per ALPS layer");
+            std::process::exit(1);
+        }
+        if args.replacement == genetic_algorithm::ReplacementStrategy::Crowding {
+            eprintln!("Error: ALPS cannot be combined with crowding replacement");
+            std::process::exit(1);
+        }
+    }
+
+    let processor = args.common.image_processor();
+
+    if let Some(device) = &args.webcam {
+        if args.mode == Mode::Halfblock || args.common.output.is_some() || args.common.format != export::OutputFormat::Text {
+            eprintln!("Error: --webcam only displays ascii mode art; --mode, --output and --format are not supported");
+            std::process::exit(1);
+        }
+        if args.fps <= 0.0 {
+            eprintln!("Error: --fps must be greater than 0");
+            std::process::exit(1);
+        }
+
+        #[cfg(feature = "video")]
+        {
+            let device = device.as_deref().unwrap_or(asciigen::video::DEFAULT_WEBCAM_DEVICE);
+            status!("Capturing from {} at {} fps", device, args.fps);
+            let frames = asciigen::video::VideoFrames::open_webcam(device, args.fps)?;
+            return run_webcam(&args, &processor, frames);
+        }
+        #[cfg(not(feature = "video"))]
+        {
+            let _ = device;
+            eprintln!("Error: webcam input requires building with `--features video`");
+            std::process::exit(1);
+        }
+    }
+
+    let input = args.common.input.clone().expect("input is required without a subcommand or --webcam");
+
+    // Piped and downloaded images are always single still images
+    let local_file = !image_processor::ImageProcessor::is_stdin_path(&input) && !image_processor::ImageProcessor::is_url(&input);
+    if !local_file && args.watch {
+        eprintln!("Error: --watch needs a local input file");
+        std::process::exit(1);
+    }
+
+    if local_file {
+        if let Some(files) = image_processor::ImageProcessor::batch_inputs(&input)? {
+            if auto_jobs && args.batch_jobs > 1 {
+                let workers = args.batch_jobs.min(files.len());
+                args.jobs = batch_worker_jobs(args.jobs, workers);
+                if !args.searches() {
+                    status!("Using {} thread(s) for each of {} images converted at a time", args.jobs, workers);
+                }
+            }
+            return run_batch(&args, &processor, &files);
+        }
+    }
+
+    if image_processor::ImageProcessor::is_video_path(&input) {
+        if args.mode == Mode::Halfblock {
+            eprintln!("Error: --mode halfblock does not support video input");
+            std::process::exit(1);
+        }
+        if args.fps <= 0.0 {
+            eprintln!("Error: --fps must be greater than 0");
+            std::process::exit(1);
+        }
+
+        #[cfg(feature = "video")]
+        {
+            status!("Decoding video with ffmpeg at {} fps: {:?}", args.fps, input);
+            let frames = asciigen::video::VideoFrames::open(&input, args.fps)?;
+            return run_animation(&args, &input, &processor, frames);
+        }
+        #[cfg(not(feature = "video"))]
+        {
+            eprintln!("Error: video input requires building with `--features video`");
+            std::process::exit(1);
+        }
+    }
+
+    // Animated GIFs are converted frame by frame; half-block mode renders the first frame like any still image
+    if args.mode != Mode::Halfblock && local_file {
+        if let Some(frames) = processor.load_animation_frames(&input)? {
+            status!("Loaded animated GIF with {} frames: {:?}", frames.len(), input);
+            return run_animation(&args, &input, &processor, frames.into_iter().map(Ok));
+        }
+    }
+
+    if args.watch {
+        return watch(&args, &input, &processor);
+    }
+
+    run_single(&args, &input, &processor)
+}
+
+/// Converts one still image with the settings in `args`, showing progress and writing the requested output
+fn run_single(args: &Args, input: &std::path::Path, processor: &image_processor::ImageProcessor) -> Result<(), Box<dyn std::error::Error>> {
+    let original_img = load_still(processor, input)?;
+    let original_img = fit_image(&args.common, processor, &original_img);
+    let ascii_gen = args.common.ascii_generator();
+
+    let seed_art = match &args.seed_art {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)?;
+            let art = genetic_algorithm::Individual::from_art(&text, ascii_gen.charset()).map_err(|e| format!("{:?}: {}", path, e))?;
+            Some(art)
+        }
+        None => None,
+    };
+    let template = match &args.template {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)?;
+            let template = LockMask::from_template(&text, ascii_gen.charset(), args.template_free)
+                .map_err(|e| format!("{:?}: {} (locked characters must be drawable; extend the set with --charset or --charset-preset full)", path, e))?;
+            Some(template)
+        }
+        None => None,
+    };
+
+    // Seed art and templates carry their own grid, which sets the size unless -w/-H is given
+    let grids: Vec<(&str, u32, u32)> = seed_art
+        .as_ref()
+        .map(|(_, w, h)| ("--seed-art", *w, *h))
+        .into_iter()
+        .chain(template.as_ref().map(|(_, w, h)| ("--template", *w, *h)))
+        .collect();
+    let (target_width, target_height) = match grids.first() {
+        Some(&(_, grid_width, grid_height)) if args.common.width.is_none() && args.common.height.is_none() => (grid_width, grid_height),
+        _ => calculate_dimensions(&original_img, args.common.width, args.common.height, args.common.fit, args.common.cell_aspect),
+    };
+    for (flag, grid_width, grid_height) in grids {
+        if (grid_width, grid_height) != (target_width, target_height) {
+            return Err(format!(
+                "{} is {}x{} characters but the target is {}x{}; omit -w/-H to use its size",
+                flag, grid_width, grid_height, target_width, target_height
+            ).into());
+        }
+    }
+    let locks = template.map(|(locks, _, _)| locks);
+    if let Some(locks) = &locks {
+        status!("Template locks {} of {} cells", locks.locked_count(), locks.len());
+    }
+
+    status!("Target ASCII dimensions: {}x{}", target_width, target_height);
+
+    if args.mode == Mode::Halfblock {
+        return run_halfblock(args, input, processor, &original_img, target_width, target_height);
+    }
+
+    let resized_bw = prepare_target(&args.common, processor, &ascii_gen, &original_img, (target_width, target_height))?;
+    let art_colors = art_colors(&args.common, processor, &original_img, (target_width, target_height));
+    let pixel_weights = pixel_weights(args, processor, resized_bw.dimensions())?;
+    let scoring = ScoringOptions::from_args(args, pixel_weights.as_ref());
+
+    let mut stopped_by_user = false;
+    let mut run_seed = None;
+    let mut generations_run = None;
+    let (best_individual, total_elapsed) = if args.searches() {
+        let seed = args.seed.unwrap_or_else(rand::random);
+        if args.traversal == brute_force::TraversalOrder::Random {
+            run_seed = Some(seed);
+            status!("Random seed: {}", seed);
+        }
+        brute_force_with_progress(&args.common, input, art_colors.as_ref(), &ascii_gen, &resized_bw, (target_width, target_height), locks.as_ref(), scoring, SearchOptions::from_args(args, seed))
+    } else {
+        // Use genetic algorithm mode
+        let mut ga = genetic_algorithm::GeneticAlgorithm::new(
+            target_width,
+            target_height,
+            args.population,
+            &ascii_gen,
+            &resized_bw,
+            args.jobs,
+            args.init_char,
+            args.common.white_background,
+        );
+        ga.set_replacement_strategy(args.replacement);
+        ga.set_selection_strategy(args.selection);
+        ga.set_tournament_size(args.tournament_size);
+        ga.set_mutation_rate(args.mutation_rate);
+        ga.set_mutation_operator(args.mutation);
+        ga.set_targeted_mutation(args.targeted_mutation);
+        ga.set_crossover_rate(args.crossover_rate);
+        ga.set_crossover_operator(args.crossover);
+        ga.set_alps(args.alps_layers, args.alps_age_gap);
+        ga.set_islands(args.islands, args.migration_interval, args.migrants);
+        ga.set_local_search(args.local_search);
+        ga.set_stall_generations(args.stall_generations, args.stall_epsilon);
+        ga.set_restart_on_stall(args.restart_on_stall);
+        ga.set_target_fitness(args.target_fitness);
+        if let Some(max_ink) = args.max_ink {
+            ga.set_max_ink(max_ink / 100.0);
+        }
+        if let Some(radius) = args.sharing_radius {
+            ga.set_fitness_sharing(radius);
+        }
+        if let Some(objective) = args.pareto {
+            ga.set_pareto_objective(objective);
+        }
+
+        let seed = args.seed.unwrap_or_else(rand::random);
+        ga.set_seed(seed);
+        run_seed = Some(seed);
+        status!("Random seed: {}", seed);
+        if let (Some((art, _, _)), Some(path)) = (&seed_art, &args.seed_art) {
+            let seeded = ga.seed_population(art, args.seed_art_fraction);
+            status!("Seeded {} of {} individuals from {:?}", seeded, args.population, path);
+        }
+        if let Some(locks) = &locks {
+            ga.set_locks(locks);
+        }
+        scoring.configure_ga(&mut ga);
+        if args.gpu {
+            enable_gpu(&mut ga, true);
+        }
+        let seed_elapsed = seed_initial_population(args, &mut ga, &ascii_gen, &resized_bw, (target_width, target_height), locks.as_ref(), scoring);
+        status!("Background threshold: {}, Total non-background pixels: {}, Background probability: {:.1}%",
+                 ga.background_threshold(), ga.total_non_background_pixels(), ga.background_probability() * 100.0);
+
+        if let Some(path) = &args.resume {
+            let checkpoint = load_checkpoint(path)?;
+            ga.resume(&checkpoint).map_err(|e| format!("Cannot resume from {:?}: {}", path, e))?;
+            status!("Resumed from {:?} after generation {}", path, checkpoint.generations_run);
+        }
+
+        if args.generations == 0 {
+            status!("Running genetic algorithm in continuous mode with population size {} (press 'q' in UI to stop)...", args.population);
+        } else {
+            status!("Running genetic algorithm for {} generations with population size {}...", args.generations, args.population);
+        }
+
+        let mut files = RunFiles::create(args, input)?;
+        let terminate = termination_flag();
+        files.log(export::LogEvent::Start { width: target_width, height: target_height, seed, parameters: Box::new(run_parameters(args, input)) });
+        let result = if args.common.no_ui {
+            // Use console output
+            ga.evolve(args.generations, args.status_interval, &mut Recording { display: &mut ConsoleProgress::new(&args.common), files: &mut files, terminate: &terminate })
+        } else {
+            // Use the interactive terminal UI
+            match terminal_ui::TerminalUI::new() {
+                Ok(mut ui) => {
+                    ui.set_snapshot(|art| save_snapshot(art, input, args.common.debug.then_some(&ascii_gen), args.common.white_background));
+                    ui.set_run_parameters(run_parameters(args, input).entries());
+                    if let Some(colors) = &art_colors {
+                        ui.set_art_colors(colors);
+                    }
+                    let mut result = ga.evolve(args.generations, args.status_interval, &mut Recording { display: &mut ui, files: &mut files, terminate: &terminate });
+                    stopped_by_user = ui.stopped_by_user();
+
+                    if terminate.load(Ordering::Relaxed) {
+                        // The checkpoint is written below, with nobody left to answer a prompt
+                    } else if let Some(objective) = args.pareto {
+                        let front = ga.pareto_front();
+                        let choices: Vec<terminal_ui::Choice> = front
+                            .iter()
+                            .map(|individual| terminal_ui::Choice {
+                                label: pareto_label(individual, objective),
+                                art: ascii_gen.individual_to_string(individual, target_width),
+                            })
+                            .collect();
+                        let chosen = ui.choose_solution(&format!("Pareto front ({} trade-offs):", front.len()), &choices);
+                        result.0 = front[chosen].clone();
+                    } else {
+                        ui.show_message("Evolution complete! Press any key to continue...");
+                        ui.check_input(); // Wait for key press
+                    }
+                    result
+                },
+                Err(e) => {
+                    eprintln!("Failed to initialize terminal UI: {}. Falling back to console output.", e);
+                    ga.evolve(args.generations, args.status_interval, &mut Recording { display: &mut ConsoleProgress::new(&args.common), files: &mut files, terminate: &terminate })
+                }
+            }
+        };
+
+        if terminate.load(Ordering::Relaxed) {
+            let path = args.checkpoint.clone().unwrap_or_else(|| PathBuf::from(format!("checkpoint_{}.json", input_stem(input))));
+            let checkpoint = serde_json::to_vec(&ga.checkpoint()).expect("checkpoint serializes to JSON");
+            export::write_atomic(&path, &checkpoint).map_err(|e| format!("Failed to write checkpoint {:?}: {}", path, e))?;
+            files.log(export::LogEvent::Finish {
+                generations_run: ga.generations_run(),
+                fitness: ga.best().fitness,
+                elapsed_seconds: result.1 + seed_elapsed,
+                stop_reason: ga.stop_reason().to_string(),
+                restarts: ga.restarts(),
+            });
+            files.finish(args)?;
+            eprintln!("Terminated after generation {}: checkpoint saved to {:?}; continue with --resume {:?}",
+                      ga.generations_run(), path, path);
+            std::process::exit(EXIT_CHECKPOINTED);
+        }
+        if stopped_by_user {
+            status!("Evolution stopped by user");
+        }
+        match ga.stop_reason() {
+            genetic_algorithm::StopReason::Stalled => status!("Stopped early: best fitness gained no more than {} in the last {} generations",
+                                                               args.stall_epsilon, args.stall_generations),
+            genetic_algorithm::StopReason::TargetReached => status!("Stopped early: target fitness {:.2}% reached",
+                                                                     args.target_fitness.unwrap_or_default() * 100.0),
+            _ => {}
+        }
+        if ga.restarts() > 0 {
+            status!("Restarted {} time(s) after stalling; keeping the best art of all runs", ga.restarts());
+        }
+        if let (Some(objective), true) = (args.pareto, args.common.no_ui) {
+            let front = ga.pareto_front();
+            status!("Pareto front ({} trade-offs, keeping the fittest; pick another in the interactive UI):", front.len());
+            for (i, individual) in front.iter().enumerate() {
+                status!("  {}. {}", i + 1, pareto_label(individual, objective));
+            }
+        }
+        let result = (result.0, result.1 + seed_elapsed);
+        status!("Final generation {}: Best fitness = {:.2}% (total time: {:.1}s)",
+                 ga.generations_run().saturating_sub(1), result.0.fitness * 100.0, result.1);
+        generations_run = Some(ga.generations_run());
+        let result = refine_result(args, &ascii_gen, &resized_bw, (target_width, target_height), locks.as_ref(), scoring, result);
+
+        if ga.stop_reason() == genetic_algorithm::StopReason::Stalled {
+            let (stall_generations, stall_epsilon) = (args.stall_generations, args.stall_epsilon);
+            files.log(export::LogEvent::Stagnation { generation: ga.generations_run(), best_fitness: ga.best().fitness, stall_generations, stall_epsilon });
+        }
+        files.log(export::LogEvent::Finish {
+            generations_run: ga.generations_run(),
+            fitness: result.0.fitness,
+            elapsed_seconds: result.1,
+            stop_reason: ga.stop_reason().to_string(),
+            restarts: ga.restarts(),
+        });
+        files.finish(args)?;
+        result
+    };
+
+    let report = export::RunReport {
+        art: ascii_gen.individual_to_string(&best_individual, target_width),
+        width: target_width,
+        height: target_height,
+        fitness: best_individual.fitness,
+        generations_run,
+        elapsed_seconds: total_elapsed,
+        mode: match (args.mode, args.brute_force) {
+            (Mode::Beam, _) => "beam",
+            (_, true) => "brute-force",
+            _ => "genetic",
+        }.to_string(),
+        charset: ascii_gen.charset().chars().iter().collect(),
+        seed: run_seed,
+        parameters: Some(run_parameters(args, input)),
+    };
+    save_result(&args.common, input, &ascii_gen, &resized_bw, scoring, &best_individual, &report)
+}
+
+/// Collects the settings a genetic algorithm or search run was started with, for the JSON report and run log
+fn run_parameters(args: &Args, input: &std::path::Path) -> export::RunParameters {
+    export::RunParameters {
+        input: input.display().to_string(),
+        population: args.population,
+        generations: args.generations,
+        jobs: args.jobs,
+        mutation_rate: args.mutation_rate,
+        mutation: args.mutation.to_string(),
+        targeted_mutation: args.targeted_mutation,
+        crossover_rate: args.crossover_rate,
+        crossover: args.crossover.to_string(),
+        replacement: args.replacement.to_string(),
+        selection: args.selection.to_string(),
+        tournament_size: args.tournament_size,
+        fitness_metric: args.fitness_metric.to_string(),
+        pareto: args.pareto.map(|objective| objective.to_string()),
+        max_ink: args.max_ink,
+        ignore_mask: args.ignore_mask.as_ref().map(|path| path.display().to_string()),
+        center_weight: args.center_weight,
+        edge_weight: args.edge_weight,
+        tolerance: args.overlap.tolerance,
+        fp_penalty: args.overlap.fp_penalty,
+        alps_layers: args.alps_layers,
+        alps_age_gap: args.alps_age_gap,
+        local_search: args.local_search,
+        islands: args.islands,
+        migration_interval: args.migration_interval,
+        migrants: args.migrants,
+        block_search: args.block_search,
+        beam_width: (args.mode == Mode::Beam).then_some(args.beam_width),
+        error_diffusion: args.error_diffusion,
+        traversal: args.traversal.to_string(),
+        refine: args.refine,
+        hybrid: args.hybrid,
+        smart_init: args.smart_init,
+        stall_generations: args.stall_generations,
+        stall_epsilon: args.stall_epsilon,
+        restart_on_stall: args.restart_on_stall,
+        target_fitness: args.target_fitness,
+        sharing_radius: args.sharing_radius,
+        init_char: args.init_char,
+        font_size: args.common.glyphs.font_size,
+        white_background: args.common.white_background,
+        invert_source: args.common.invert_source,
+        fit: args.common.fit.to_possible_value().expect("every fit has a name").get_name().to_string(),
+        cell_aspect: args.common.cell_aspect,
+        gamma: args.common.tone.gamma,
+        auto_contrast: args.common.tone.auto_contrast,
+        equalize: args.common.tone.equalize,
+        clahe: args.common.tone.clahe,
+        clahe_tiles: args.common.tone.clahe.map(|_| args.common.tone.clahe_tiles),
+        levels: args.common.tone.levels.map(|levels| format!("{},{},{}", levels.black, levels.white, levels.gamma)),
+        posterize: args.common.tone.posterize,
+    }
+}
+
+/// Describes a Pareto front solution by its fitness and second objective
+fn pareto_label(individual: &genetic_algorithm::Individual, objective: genetic_algorithm::ParetoObjective) -> String {
+    let objective_name = match objective {
+        genetic_algorithm::ParetoObjective::Ink => "ink economy",
+        genetic_algorithm::ParetoObjective::Edge => "edge fidelity",
+    };
+    format!("fitness {:.2}%, {} {:.2}%", individual.fitness * 100.0, objective_name, individual.objective * 100.0)
+}
+
+/// Runs the `convert` command: a single pass over the cells without evolution
+fn run_convert(mut args: ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
+    validate_common(&mut args.common, true, true);
+    let common = &args.common;
+    let input = common.input.clone().expect("input was validated");
+    let processor = common.image_processor();
+
+    let original_img = load_still(&processor, &input)?;
+    let original_img = fit_image(common, &processor, &original_img);
+    let (target_width, target_height) = calculate_dimensions(&original_img, common.width, common.height, common.fit, common.cell_aspect);
+    status!("Target ASCII dimensions: {}x{}", target_width, target_height);
+
+    let ascii_gen = common.ascii_generator();
+    let target = prepare_target(common, &processor, &ascii_gen, &original_img, (target_width, target_height))?;
+    let (best, elapsed) = match args.method {
+        ConvertMethod::Luminance => {
+            let result = luminance::LuminanceMapper::new(target_width, target_height, &ascii_gen, &target, common.white_background).generate();
+            status!("Luminance mapping complete! Fitness: {:.2}% (total time: {:.3}s)", result.0.fitness * 100.0, result.1);
+            result
+        }
+        ConvertMethod::BruteForce => brute_force_with_progress(common, &input, art_colors(common, &processor, &original_img, (target_width, target_height)).as_ref(), &ascii_gen, &target, (target_width, target_height), None, ScoringOptions::default(), SearchOptions::default()),
+    };
+
+    let report = export::RunReport {
+        art: ascii_gen.individual_to_string(&best, target_width),
+        width: target_width,
+        height: target_height,
+        fitness: best.fitness,
+        generations_run: None,
+        elapsed_seconds: elapsed,
+        mode: match args.method {
+            ConvertMethod::Luminance => "luminance",
+            ConvertMethod::BruteForce => "brute-force",
+        }.to_string(),
+        charset: ascii_gen.charset().chars().iter().collect(),
+        seed: None,
+        parameters: None,
+    };
+    save_result(common, &input, &ascii_gen, &target, ScoringOptions::default(), &best, &report)
+}
+
+/// Renders existing art for `score` with the fitness font and compares it with the prepared image, returning the
+/// metrics and the art's size in characters
+fn score_art(args: &ScoreArgs) -> Result<(fitness::ScoreReport, u32, u32), Box<dyn std::error::Error>> {
+    if !(4.0..=64.0).contains(&args.glyphs.font_size) {
+        return Err("font size must be between 4 and 64".into());
+    }
+    args.tone.validate()?;
+
+    let text = std::fs::read_to_string(&args.art).map_err(|e| format!("{:?}: {}", args.art, e))?;
+    if text.lines().all(str::is_empty) {
+        return Err(format!("{:?}: art file is empty", args.art).into());
+    }
+    // Art from other tools may use any characters, so without an explicit set the default set is extended with them
+    let charset = if args.glyphs.charset.is_none() && args.glyphs.charset_preset.is_none() {
+        let spec: String = Charset::default().chars().iter().copied().chain(text.chars().filter(|c| *c != '\n' && *c != '\r')).collect();
+        Charset::parse(&spec).map_err(|e| format!("{:?}: {}", args.art, e))?
+    } else {
+        args.glyphs.charset()
+    };
+    let (individual, width, height) = genetic_algorithm::Individual::from_art(&text, &charset).map_err(|e| format!("{:?}: {}", args.art, e))?;
+
+    let mut ascii_gen = ascii_generator::AsciiGenerator::with_charset(charset);
+    ascii_gen.set_font_size(args.glyphs.font_size);
+    let (char_width, char_height) = ascii_gen.char_dimensions();
+
+    let processor = args.tone.image_processor();
+    let image = processor.load_image(&args.image)?;
+    let target = processor.prepare_target_image_with_inversion(&image, width * char_width, height * char_height, args.invert_source)?;
+    let rendered = ascii_gen.generate_ascii_image(&individual.chars, width, height);
+    let score = asciigen::fitness::score_rendering(&rendered, &target, args.white_background, args.overlap.params()?);
+    Ok((score, width, height))
+}
+
+/// Runs the `score` command: prints the metrics of `score_art`
+fn run_score(args: &ScoreArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let (score, width, height) = score_art(args)?;
+    let match_rate = if score.counts.relevant == 0 { 0.0 } else { score.counts.matches as f64 / score.counts.relevant as f64 };
+    if args.json {
+        let value = serde_json::json!({
+            "art": args.art.display().to_string(),
+            "image": args.image.display().to_string(),
+            "width": width,
+            "height": height,
+            "fitness": score.fitness,
+            "matches": score.counts.matches,
+            "false_positives": score.counts.false_positives,
+            "foreground_pixels": score.non_background_pixels as u64,
+            "match_rate": match_rate,
+            "mean_absolute_error": score.mean_absolute_error,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("Art: {:?} ({}x{} characters), image: {:?}", args.art, width, height, args.image);
+        println!("Fitness: {:.2}%", score.fitness * 100.0);
+        println!("Matched pixels: {} of {} foreground pixels ({:.2}%)", score.counts.matches, score.counts.relevant, match_rate * 100.0);
+        println!("False positives: {} pixels", score.counts.false_positives);
+        println!("Mean absolute error: {:.2} (0-255)", score.mean_absolute_error);
+    }
+    Ok(())
+}
+
+/// Runs the `bench` command: times a fixed-seed genetic algorithm run, a brute-force pass and luminance mapping on one image
+fn run_bench(args: &BenchArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !(20..=1000).contains(&args.population) {
+        return Err("population size must be between 20 and 1000".into());
+    }
+    if args.width == 0 || args.generations == 0 {
+        return Err("--width and --generations must be at least 1".into());
+    }
+    if !(4.0..=64.0).contains(&args.glyphs.font_size) {
+        return Err("font size must be between 4 and 64".into());
+    }
+    if args.gpu && !cfg!(feature = "gpu") {
+        return Err("--gpu requires building with `--features gpu`".into());
+    }
+
+    let processor = image_processor::ImageProcessor::new();
+    let image = processor.load_image(&args.input)?;
+    let (width, height) = calculate_dimensions(&image, Some(args.width), None, Fit::Contain, DEFAULT_CELL_ASPECT);
+    let ascii_gen = args.glyphs.ascii_generator();
+    let (char_width, char_height) = ascii_gen.char_dimensions();
+    let target = processor.prepare_target_image_with_inversion(&image, width * char_width, height * char_height, false)?;
+    let cells = (width * height) as f64;
+
+    println!(
+        "Benchmarking {:?}: {}x{} characters, {} glyphs at {}px, seed {}",
+        args.input, width, height, ascii_gen.charset().len(), args.glyphs.font_size, args.seed
+    );
+
+    let mut ga = genetic_algorithm::GeneticAlgorithm::new(width, height, args.population, &ascii_gen, &target, args.jobs, None, false);
+    ga.set_seed(args.seed);
+    let (best, elapsed) = ga.evolve(args.generations, 1.0, &mut NoProgress);
+    let evaluations = ga.generations_run() as f64 * args.population as f64;
+    println!(
+        "Genetic algorithm: {} generations x {} individuals on {} threads in {:.2}s ({:.1} generations/s, {:.0} evaluations/s, fitness {:.2}%)",
+        args.generations, args.population, ga.thread_count(), elapsed,
+        args.generations as f64 / elapsed, evaluations / elapsed, best.fitness * 100.0
+    );
+
+    #[cfg(feature = "gpu")]
+    if args.gpu {
+        let mut ga = genetic_algorithm::GeneticAlgorithm::new(width, height, args.population, &ascii_gen, &target, args.jobs, None, false);
+        ga.set_seed(args.seed);
+        let adapter = ga.enable_gpu()?.to_string();
+        let (best, elapsed) = ga.evolve(args.generations, 1.0, &mut NoProgress);
+        println!(
+            "Genetic algorithm on {}: {} generations x {} individuals in {:.2}s ({:.1} generations/s, {:.0} evaluations/s, fitness {:.2}%)",
+            adapter, args.generations, args.population, elapsed,
+            args.generations as f64 / elapsed, evaluations / elapsed, best.fitness * 100.0
+        );
+    }
+
+    let (best, elapsed) = brute_force::BruteForceGenerator::new(width, height, &ascii_gen, &target, false)
+        .generate(&mut NoProgress);
+    println!(
+        "Brute force: {} cells in {:.2}s ({:.0} cells/s, fitness {:.2}%)",
+        cells, elapsed, cells / elapsed, best.fitness * 100.0
+    );
+
+    let (best, elapsed) = luminance::LuminanceMapper::new(width, height, &ascii_gen, &target, false).generate();
+    println!(
+        "Luminance mapping: {} cells in {:.4}s ({:.0} cells/s, fitness {:.2}%)",
+        cells, elapsed, cells / elapsed, best.fitness * 100.0
+    );
+
+    Ok(())
+}
+
+/// Runs the `atlas` command: writes the glyph cache as a contact sheet and lists the characters of each row
+fn run_atlas(args: &AtlasArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !(4.0..=64.0).contains(&args.glyphs.font_size) {
+        return Err("font size must be between 4 and 64".into());
+    }
+    if args.columns == 0 {
+        return Err("--columns must be at least 1".into());
+    }
+
+    let ascii_gen = args.glyphs.ascii_generator();
+    ascii_gen.glyph_atlas(args.columns, args.white_background).save(&args.output)?;
+    let (char_width, char_height) = ascii_gen.char_dimensions();
+    println!("Glyph atlas of {} characters ({}x{} pixel cells) saved to {:?}", ascii_gen.charset().len(), char_width, char_height, args.output);
+    for (row, chars) in ascii_gen.charset().chars().chunks(args.columns as usize).enumerate() {
+        println!("  row {:>2}: {:?}", row + 1, chars.iter().collect::<String>());
+    }
+    Ok(())
+}
+
+/// Runs the `play` command: redraws each frame in place at `--fps`
+fn run_play(args: &PlayArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.fps <= 0.0 {
+        return Err("--fps must be greater than 0".into());
+    }
+
+    let frames: Vec<String> = if args.input.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ans")) {
+        export::decode_ans_frames(&std::fs::read(&args.input)?)
+    } else {
+        export::frame_series(&args.input)
+            .iter()
+            .map(std::fs::read_to_string)
+            .collect::<Result<_, _>>()?
+    };
+    if frames.is_empty() {
+        return Err(format!("no frames found for {:?}", args.input).into());
+    }
+
+    let delay = std::time::Duration::from_secs_f64(1.0 / args.fps);
+    match play_frames(&mut std::io::stdout().lock(), &frames, delay, args.repeat) {
+        // A reader such as `head` that closes the pipe early ends playback normally
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Writes each frame `delay` apart, clearing the terminal and redrawing in place, once or until interrupted with `repeat`
+fn play_frames(out: &mut impl std::io::Write, frames: &[String], delay: std::time::Duration, repeat: bool) -> std::io::Result<()> {
+    loop {
+        for (index, frame) in frames.iter().enumerate() {
+            write!(out, "\x1b[H\x1b[2J{}\nFrame {}/{}\n", frame.trim_end_matches('\n'), index + 1, frames.len())?;
+            out.flush()?;
+            std::thread::sleep(delay);
+        }
+        if !repeat {
+            return Ok(());
+        }
+    }
+}
+
+/// Loads a still image from a file, an http(s) URL, or stdin for `-`
+fn load_still(processor: &image_processor::ImageProcessor, input: &std::path::Path) -> Result<image::DynamicImage, Box<dyn std::error::Error>> {
+    status!("Loading image: {}", if image_processor::ImageProcessor::is_stdin_path(input) { "<stdin>".to_string() } else { format!("{:?}", input) });
+    let image = if image_processor::ImageProcessor::is_stdin_path(input) {
+        processor.load_image_from_reader(std::io::stdin().lock())?
+    } else if image_processor::ImageProcessor::is_url(input) {
+        processor.load_image_from_url(&input.to_string_lossy())?
+    } else {
+        processor.load_image(input)?
+    };
+    status!("Input image size: {}x{}", image.width(), image.height());
+    Ok(image)
+}
+
+/// Colors of the source image averaged over each character cell, for the interactive UI to draw the art in
+/// None with `--no-ui`, where nothing would show them
+fn art_colors(
+    common: &CommonArgs,
+    processor: &image_processor::ImageProcessor,
+    original_img: &image::DynamicImage,
+    (target_width, target_height): (u32, u32),
+) -> Option<image::RgbImage> {
+    if common.no_ui {
+        return None;
+    }
+    processor.prepare_color_image(original_img, target_width, target_height, common.invert_source).ok()
+}
+
+/// Scales and converts the image to the grayscale target the optimizers compare rendered glyphs against
+fn prepare_target(
+    common: &CommonArgs,
+    processor: &image_processor::ImageProcessor,
+    ascii_gen: &ascii_generator::AsciiGenerator,
+    original_img: &image::DynamicImage,
+    (target_width, target_height): (u32, u32),
+) -> Result<image::ImageBuffer<image::Luma<u8>, Vec<u8>>, Box<dyn std::error::Error>> {
+    // Calculate actual pixel dimensions needed for ASCII character rendering
+    let (char_width, char_height) = ascii_gen.char_dimensions();
+    let target_pixel_width = target_width * char_width;
+    let target_pixel_height = target_height * char_height;
+
+    status!("Character dimensions: {}x{}", char_width, char_height);
+    status!("Target pixel dimensions: {}x{}", target_pixel_width, target_pixel_height);
+
+    let resized_bw = processor.prepare_target_image_with_inversion(original_img, target_pixel_width, target_pixel_height, common.invert_source)?;
+
+    if common.invert_source {
+        status!("Source image colors inverted");
+    }
+    status!("Post-processed input image size: {}x{}", resized_bw.width(), resized_bw.height());
+    Ok(resized_bw)
+}
+
+/// How the optimizers compare candidates with the target, from `--fitness`, `--tolerance`, `--fp-penalty`,
+/// `--edge-weight` and the pixel weights
+#[derive(Clone, Copy, Default)]
+struct ScoringOptions<'w> {
+    metric: fitness::FitnessMetric,
+    overlap: fitness::OverlapParams,
+    edge_weight: f64,
+    pixel_weights: Option<&'w PixelWeights>,
+}
+
+impl<'w> ScoringOptions<'w> {
+    fn from_args(args: &Args, pixel_weights: Option<&'w PixelWeights>) -> Self {
+        Self {
+            metric: args.fitness_metric,
+            overlap: args.overlap.params().expect("overlap options were validated"),
+            edge_weight: args.edge_weight,
+            pixel_weights,
+        }
+    }
+
+    /// Builds a standalone scorer with these settings, for judging finished art
+    fn scorer<'t>(&self, target: &'t image::ImageBuffer<image::Luma<u8>, Vec<u8>>, white_background: bool) -> fitness::Scorer<'t>
+    where
+        'w: 't,
+    {
+        let mut scorer = fitness::Scorer::new(target, white_background);
+        scorer.set_metric(self.metric);
+        scorer.set_overlap_params(self.overlap);
+        if let Some(pixel_weights) = self.pixel_weights {
+            scorer.set_pixel_weights(pixel_weights);
+        }
+        scorer.set_edge_weight(self.edge_weight);
+        scorer
+    }
+
+    fn configure_ga(&self, ga: &mut genetic_algorithm::GeneticAlgorithm<'w>) {
+        ga.set_fitness_metric(self.metric);
+        ga.set_overlap_params(self.overlap);
+        if let Some(pixel_weights) = self.pixel_weights {
+            ga.set_pixel_weights(pixel_weights);
+        }
+        ga.set_edge_weight(self.edge_weight);
+    }
+
+    fn configure_brute_force(&self, bf_gen: &mut brute_force::BruteForceGenerator<'w>) {
+        bf_gen.set_fitness_metric(self.metric);
+        bf_gen.set_overlap_params(self.overlap);
+        if let Some(pixel_weights) = self.pixel_weights {
+            bf_gen.set_pixel_weights(pixel_weights);
+        }
+        bf_gen.set_edge_weight(self.edge_weight);
+    }
+}
+
+/// How brute force searches the grid, from `--block-search`, `--error-diffusion`, `--traversal` (seeded by `--seed`)
+/// and `--mode beam --beam-width`
+#[derive(Clone, Copy, Default)]
+struct SearchOptions {
+    block_search: Option<usize>,
+    error_diffusion: bool,
+    traversal: brute_force::TraversalOrder,
+    seed: u64,
+    beam_width: Option<usize>,
+}
+
+impl SearchOptions {
+    fn from_args(args: &Args, seed: u64) -> Self {
+        Self {
+            block_search: args.block_search,
+            error_diffusion: args.error_diffusion,
+            traversal: args.traversal,
+            seed,
+            beam_width: (args.mode == Mode::Beam).then_some(args.beam_width),
+        }
+    }
+
+    fn configure(&self, bf_gen: &mut brute_force::BruteForceGenerator) {
+        bf_gen.set_block_search(self.block_search);
+        bf_gen.set_error_diffusion(self.error_diffusion);
+        bf_gen.set_traversal_order(self.traversal);
+        bf_gen.set_seed(self.seed);
+        bf_gen.set_beam_width(self.beam_width);
+    }
+}
+
+/// Builds the fitness weights for a target of the given pixel size from `--center-weight` and `--ignore-mask`
+/// The mask image is scaled to the target; returns None when neither option is set
+fn pixel_weights(
+    args: &Args,
+    processor: &image_processor::ImageProcessor,
+    (pixel_width, pixel_height): (u32, u32),
+) -> Result<Option<PixelWeights>, Box<dyn std::error::Error>> {
+    let mut weights = args.center_weight.map(|strength| PixelWeights::center_weighted(pixel_width, pixel_height, strength));
+
+    if let Some(path) = &args.ignore_mask {
+        let mask_img = processor.load_image(path).map_err(|e| format!("Failed to load --ignore-mask {:?}: {}", path, e))?;
+        // The mask is read as drawn, without the target's tone adjustments
+        let scaled = image_processor::ImageProcessor::new().prepare_target_image_with_inversion(&mask_img, pixel_width, pixel_height, false)?;
+        let mask = PixelWeights::from_ignore_mask(&scaled);
+        status!("Ignore mask excludes {} of {} pixels from scoring", mask.ignored_count(), pixel_width * pixel_height);
+        match &mut weights {
+            Some(weights) => weights.combine(&mask),
+            None => weights = Some(mask),
+        }
+    }
+
+    Ok(weights)
+}
+
+/// Runs the brute-force generator with the terminal UI or console progress, as selected by `--no-ui`
+/// Cells locked by a template keep their characters; `scoring` selects how candidates are compared and `search` how
+/// the grid is searched
+#[allow(clippy::too_many_arguments)]
+fn brute_force_with_progress(
+    common: &CommonArgs,
+    input: &std::path::Path,
+    art_colors: Option<&image::RgbImage>,
+    ascii_gen: &ascii_generator::AsciiGenerator,
+    target: &image::ImageBuffer<image::Luma<u8>, Vec<u8>>,
+    (target_width, target_height): (u32, u32),
+    locks: Option<&LockMask>,
+    scoring: ScoringOptions,
+    search: SearchOptions,
+) -> (genetic_algorithm::Individual, f64) {
+    match search.beam_width {
+        Some(beam_width) => status!("Running beam search (width {}) for {}x{} characters...", beam_width, target_width, target_height),
+        None => status!("Running brute force generation for {}x{} characters...", target_width, target_height),
+    }
+
+    let mut bf_gen = brute_force::BruteForceGenerator::new(
+        target_width,
+        target_height,
+        ascii_gen,
+        target,
+        common.white_background,
+    );
+    if let Some(locks) = locks {
+        bf_gen.set_locks(locks);
+    }
+    scoring.configure_brute_force(&mut bf_gen);
+    search.configure(&mut bf_gen);
+    status!("Brute force - Background threshold: {}, Total non-background pixels: {}",
+             bf_gen.background_threshold(), bf_gen.total_non_background_pixels());
+    status!("Starting brute force generation for {} positions...", target_width * target_height);
+
+    let mut stopped_by_user = false;
+    let result = if common.no_ui {
+        // Use console output for brute force
+        bf_gen.generate(&mut ConsoleProgress::new(common))
+    } else {
+        // Use the interactive terminal UI for brute force
+        match terminal_ui::TerminalUI::new() {
+            Ok(mut ui) => {
+                ui.set_snapshot(|art| save_snapshot(art, input, common.debug.then_some(ascii_gen), common.white_background));
+                if let Some(colors) = art_colors {
+                    ui.set_art_colors(colors);
+                }
+                let result = bf_gen.generate(&mut ui);
+                stopped_by_user = ui.stopped_by_user();
+
+                ui.show_message("Brute force generation complete! Press any key to continue...");
+                ui.check_input(); // Wait for key press
+                result
+            },
+            Err(e) => {
+                eprintln!("Failed to initialize terminal UI: {}. Falling back to console output.", e);
+                bf_gen.generate(&mut ConsoleProgress::new(common))
+            }
+        }
+    };
+
+    if stopped_by_user {
+        status!("Brute force generation stopped by user");
+    }
+    status!("Brute force generation complete! Final fitness: {:.2}% (total time: {:.1}s)",
+             result.0.fitness * 100.0, result.1);
+    result
+}
+
+/// Encodes art for a text output file, or as CP437 with a SAUCE record titled `title` for `--format ans`
+fn encode_art(format: export::OutputFormat, white_background: bool, title: &str, art: &str) -> Result<Vec<u8>, String> {
+    if format == export::OutputFormat::Ans {
+        let sauce = export::SauceInfo {
+            title: title.to_string(),
+            ..Default::default()
+        };
+        export::encode_ans(art, white_background, &sauce)
+    } else {
+        Ok(art.as_bytes().to_vec())
+    }
+}
+
+/// Prints the final art and writes it in the requested format, plus debug images when `--debug` is set
+fn save_result(
+    common: &CommonArgs,
+    input: &std::path::Path,
+    ascii_gen: &ascii_generator::AsciiGenerator,
+    target: &image::ImageBuffer<image::Luma<u8>, Vec<u8>>,
+    scoring: ScoringOptions,
+    best_individual: &genetic_algorithm::Individual,
+    report: &export::RunReport,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Generate output ASCII image buffer to get its dimensions
+    let output_ascii_image = ascii_gen.generate_ascii_image(&best_individual.chars, report.width, report.height);
+    status!("Output ASCII image buffer size: {}x{}", output_ascii_image.width(), output_ascii_image.height());
+
+    let mode_str = if report.mode == "genetic" { "genetic algorithm" } else { report.mode.as_str() };
+    status!("\nBest ASCII art ({}x{} characters, fitness: {:.2}%, mode: {}, elapsed: {:.1}s):\n{}", report.width, report.height, report.fitness * 100.0, mode_str, report.elapsed_seconds, report.art);
+    if common.char_usage {
+        let charset = ascii_gen.charset();
+        for line in char_usage_report(charset, &charset.usage(&best_individual.chars)) {
+            status!("{}", line);
+        }
+    }
+
+    let contents = match common.format {
+        export::OutputFormat::Text | export::OutputFormat::Ans => {
+            encode_art(common.format, common.white_background, &input_stem(input), &report.art)?
+        }
+        export::OutputFormat::Json => {
+            let json = report.to_json();
+            if common.output.is_none() {
+                println!("{}", json);
+            }
+            json.into_bytes()
+        }
+    };
+
+    if let Some(output_path) = &common.output {
+        export::write_atomic(output_path, &contents)?;
+        status!("ASCII art saved to: {:?}", output_path);
+    }
+
+    // Save debug images if requested
+    if common.debug {
+        // Save converted input image
+        let input_debug_path = format!("debug_input_{}.png", input_stem(input));
+        target.save(&input_debug_path)?;
+        status!("Debug input image saved to: {}", input_debug_path);
+
+        // Save final ASCII art as image (same size as fitness comparison buffer)
+        let ascii_image = ascii_gen.generate_ascii_image_with_background(&best_individual.chars, report.width, report.height, common.white_background);
+        let ascii_debug_path = format!("debug_ascii_{}.png", input_stem(input));
+        ascii_image.save(&ascii_debug_path)?;
+        status!("Debug ASCII image saved to: {}", ascii_debug_path);
+
+        // Save how close each cell comes to the best character there, bright where no character would do better
+        let scorer = scoring.scorer(target, common.white_background);
+        let glyphs: Vec<_> = (0..ascii_gen.charset().len()).map(|code| ascii_gen.generate_ascii_image(&[code as u8], 1, 1)).collect();
+        let ceilings = scorer.cell_ceilings(&glyphs, (report.width, report.height));
+        let quality = scorer.cell_quality(&output_ascii_image, ascii_gen.char_dimensions(), &ceilings);
+        let heatmap_debug_path = format!("debug_heatmap_{}.png", input_stem(input));
+        cell_heatmap(&quality, (report.width, report.height), ascii_gen.char_dimensions()).save(&heatmap_debug_path)?;
+        status!("Debug fitness heatmap saved to: {}", heatmap_debug_path);
+    }
+
+    Ok(())
+}
+
+/// Paints each cell of a `grid` (columns, rows) as a `cell_size` block as bright as its quality (0 to 1, row-major)
+fn cell_heatmap(quality: &[f32], grid: (u32, u32), cell_size: (u32, u32)) -> image::GrayImage {
+    image::GrayImage::from_fn(grid.0 * cell_size.0, grid.1 * cell_size.1, |x, y| {
+        let cell = (y / cell_size.1 * grid.0 + x / cell_size.0) as usize;
+        image::Luma([(quality.get(cell).copied().unwrap_or(0.0).clamp(0.0, 1.0) * 255.0).round() as u8])
+    })
+}
+
+/// Runs once, then re-runs whenever the input file changes, rewriting the output in place; stop with Ctrl-C
+/// Failed runs (for example while an editor is still writing the file) are reported and the watch continues
+fn watch(args: &Args, input: &std::path::Path, processor: &image_processor::ImageProcessor) -> Result<(), Box<dyn std::error::Error>> {
+    let mut last_seen = file_signature(input);
+    loop {
+        if let Err(e) = run_single(args, input, processor) {
+            eprintln!("Error: {}", e);
+        }
+        status!("\nWatching {:?} for changes (Ctrl-C to stop)...", input);
+        last_seen = wait_for_change(input, last_seen);
+        status!("\n{:?} changed, regenerating", input);
+    }
+}
+
+/// Modification time and size, which together identify a version of a file
+fn file_signature(path: &std::path::Path) -> Option<(std::time::SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Polls until the file's signature differs from `last_seen` and has stayed the same for one more poll,
+/// so a save that is still in progress is not picked up half-written
+fn wait_for_change(path: &std::path::Path, last_seen: Option<(std::time::SystemTime, u64)>) -> Option<(std::time::SystemTime, u64)> {
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(300);
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = file_signature(path);
+        if current.is_some() && current != last_seen {
+            std::thread::sleep(POLL_INTERVAL);
+            if file_signature(path) == current {
+                return current;
+            }
+        }
+    }
+}
+
+/// Converts the image straight to ANSI half-block art, bypassing character optimization
+fn run_halfblock(
+    args: &Args,
+    input: &std::path::Path,
+    processor: &image_processor::ImageProcessor,
+    original_img: &image::DynamicImage,
+    target_width: u32,
+    target_height: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Each cell shows two vertical pixels, which also cancels the 2:1 character aspect ratio
+    let color_img = processor.prepare_color_image(original_img, target_width, target_height * 2, args.common.invert_source)?;
+    let art = halfblock::render(&color_img);
+
+    status!("\nHalf-block art ({}x{} characters):\n{}", target_width, target_height, art);
+
+    if let Some(output_path) = &args.common.output {
+        std::fs::write(output_path, &art)?;
+        status!("Half-block art saved to: {:?}", output_path);
+    }
+
+    if args.common.debug {
+        let input_debug_path = format!("debug_input_{}.png", input_stem(input));
+        color_img.save(&input_debug_path)?;
+        status!("Debug input image saved to: {}", input_debug_path);
+    }
+
+    Ok(())
+}
+
+/// Result of converting one file in a batch
+struct BatchResult {
+    input: PathBuf,
+    outcome: Result<BatchSuccess, String>,
+}
+
+struct BatchSuccess {
+    output: PathBuf,
+    width: u32,
+    height: u32,
+    fitness: f64,
+    elapsed: f64,
+    early_stop: Option<(genetic_algorithm::StopReason, u32)>,
+}
+
+/// Splits the `threads` of an automatic `--jobs` across `workers` concurrent batch conversions, at least 1 each, so
+/// their genetic algorithm pools don't oversubscribe the cores
+fn batch_worker_jobs(threads: usize, workers: usize) -> usize {
+    (threads / workers.max(1)).max(1)
+}
+
+/// Converts every file of a directory or wildcard input with the same settings, writing `<stem>.txt`
+/// (or `.ans`) next to each input or into the `--output` directory, then prints a summary table
+/// All files share one glyph cache; `--batch-jobs` files are converted at a time
+fn run_batch(args: &Args, processor: &image_processor::ImageProcessor, files: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(flag) = [
+        (args.watch, "--watch"),
+        (args.seed_art.is_some(), "--seed-art"),
+        (args.template.is_some(), "--template"),
+        (args.ignore_mask.is_some(), "--ignore-mask"),
+        (args.stats_csv.is_some(), "--stats-csv"),
+        (args.log_jsonl.is_some(), "--log-jsonl"),
+        (args.autosave.is_some(), "--autosave"),
+        (args.checkpoint.is_some(), "--checkpoint"),
+        (args.resume.is_some(), "--resume"),
+        (args.control_file.is_some(), "--control-file"),
+        (args.frames_dir.is_some(), "--frames-dir"),
+        (args.common.char_usage, "--char-usage"),
+    ]
+        .into_iter()
+        .find_map(|(set, flag)| set.then_some(flag))
+    {
+        return Err(format!("{} is only supported for a single still image", flag).into());
+    }
+    if args.generations == 0 {
+        return Err("batch input needs a fixed number of generations per image (-g greater than 0)".into());
+    }
+    if args.mode == Mode::Halfblock || args.common.format == export::OutputFormat::Json {
+        return Err("batch input supports ascii mode with text or ans output".into());
+    }
+    if args.batch_jobs == 0 {
+        return Err("--batch-jobs must be at least 1".into());
+    }
+    if let Some(dir) = &args.common.output {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let ascii_gen = args.common.ascii_generator();
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let extension = if args.common.format == export::OutputFormat::Ans { "ans" } else { "txt" };
+    status!("Processing {} images ({} at a time)", files.len(), args.batch_jobs);
+    if !args.searches() || args.traversal == brute_force::TraversalOrder::Random {
+        status!("Random seed: {}", seed);
+    }
+
+    let convert = |index: usize, input: &PathBuf| -> Result<BatchSuccess, Box<dyn std::error::Error>> {
+        let image = processor.load_image(input)?;
+        let image = fit_image(&args.common, processor, &image);
+        let (width, height) = calculate_dimensions(&image, args.common.width, args.common.height, args.common.fit, args.common.cell_aspect);
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = processor.prepare_target_image_with_inversion(&image, width * char_width, height * char_height, args.common.invert_source)?;
+
+        let (best, elapsed, early_stop) = optimize_target(args, &ascii_gen, &target, (width, height), seed.wrapping_add(index as u64), None);
+        let art = ascii_gen.individual_to_string(&best, width);
+        let contents = match args.common.format {
+            export::OutputFormat::Ans => {
+                let sauce = export::SauceInfo {
+                    title: input_stem(input),
+                    ..Default::default()
+                };
+                export::encode_ans(&art, args.common.white_background, &sauce)?
+            }
+            _ => art.into_bytes(),
+        };
+
+        let file_name = PathBuf::from(input.file_stem().unwrap_or_default()).with_extension(extension);
+        let output = match &args.common.output {
+            Some(dir) => dir.join(file_name),
+            None => input.with_file_name(file_name),
+        };
+        std::fs::write(&output, contents)?;
+        Ok(BatchSuccess { output, width, height, fitness: best.fitness, elapsed, early_stop })
+    };
+
+    // Workers pull the next file index until every file has been claimed
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let results = std::sync::Mutex::new(Vec::with_capacity(files.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..args.batch_jobs.min(files.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input) = files.get(index) else { break };
+                let outcome = convert(index, input).map_err(|e| e.to_string());
+                match &outcome {
+                    Ok(BatchSuccess { output, fitness, early_stop: Some((reason, generations)), .. }) => {
+                        status!("{:?} -> {:?} ({:.2}%, {} after {} generations)", input, output, fitness * 100.0, reason, generations)
+                    }
+                    Ok(done) => status!("{:?} -> {:?} ({:.2}%)", input, done.output, done.fitness * 100.0),
+                    Err(e) => eprintln!("Error: {:?}: {}", input, e),
+                }
+                results.lock().expect("batch results lock").push(BatchResult { input: input.clone(), outcome });
+            });
+        }
+    });
+
+    let mut results = results.into_inner().expect("batch results lock");
+    results.sort_by(|a, b| a.input.cmp(&b.input));
+    print_batch_summary(&results);
+
+    let failed = results.iter().filter(|r| r.outcome.is_err()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} images failed", failed, results.len()).into());
+    }
+    Ok(())
+}
+
+/// Prints one row per batch input with its size, fitness and time, or the error it failed with
+fn print_batch_summary(results: &[BatchResult]) {
+    let names: Vec<String> = results
+        .iter()
+        .map(|r| r.input.file_name().unwrap_or_default().to_string_lossy().into_owned())
+        .collect();
+    let name_width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0).max(4);
+
+    status!("\n{:<name_width$}  {:>9}  {:>8}  {:>8}", "File", "Size", "Fitness", "Time");
+    for (result, name) in results.iter().zip(&names) {
+        match &result.outcome {
+            Ok(done) => status!(
+                "{:<name_width$}  {:>9}  {:>7.2}%  {:>7.1}s",
+                name,
+                format!("{}x{}", done.width, done.height),
+                done.fitness * 100.0,
+                done.elapsed
+            ),
+            Err(e) => status!("{:<name_width$}  failed: {}", name, e),
+        }
+    }
+}
+
+/// Optimizes one target with a fixed generation budget and no progress display
+/// Animation frames pass the previous frame's best as `previous` to warm-start the genetic algorithm
+/// The third element holds why and after how many generations `--stall-generations` or `--target-fitness` stopped
+/// evolution early
+fn optimize_target(
+    args: &Args,
+    ascii_gen: &ascii_generator::AsciiGenerator,
+    target: &image::ImageBuffer<image::Luma<u8>, Vec<u8>>,
+    (width, height): (u32, u32),
+    seed: u64,
+    previous: Option<&genetic_algorithm::Individual>,
+) -> (genetic_algorithm::Individual, f64, Option<(genetic_algorithm::StopReason, u32)>) {
+    let pixel_weights = args.center_weight.map(|strength| PixelWeights::center_weighted(target.width(), target.height(), strength));
+    let scoring = ScoringOptions::from_args(args, pixel_weights.as_ref());
+
+    if args.searches() {
+        let mut bf_gen = brute_force::BruteForceGenerator::new(width, height, ascii_gen, target, args.common.white_background);
+        scoring.configure_brute_force(&mut bf_gen);
+        SearchOptions::from_args(args, seed).configure(&mut bf_gen);
+        let (best, elapsed) = bf_gen.generate(&mut NoProgress);
+        return (best, elapsed, None);
+    }
+
+    let mut ga = genetic_algorithm::GeneticAlgorithm::new(
+        width,
+        height,
+        args.population,
+        ascii_gen,
+        target,
+        args.jobs,
+        args.init_char,
+        args.common.white_background,
+    );
+    ga.set_replacement_strategy(args.replacement);
+    ga.set_selection_strategy(args.selection);
+    ga.set_tournament_size(args.tournament_size);
+    ga.set_mutation_rate(args.mutation_rate);
+    ga.set_mutation_operator(args.mutation);
+    ga.set_targeted_mutation(args.targeted_mutation);
+    ga.set_crossover_rate(args.crossover_rate);
+    ga.set_crossover_operator(args.crossover);
+    ga.set_alps(args.alps_layers, args.alps_age_gap);
+    ga.set_islands(args.islands, args.migration_interval, args.migrants);
+    ga.set_local_search(args.local_search);
+    ga.set_stall_generations(args.stall_generations, args.stall_epsilon);
+    ga.set_restart_on_stall(args.restart_on_stall);
+    ga.set_target_fitness(args.target_fitness);
+    if let Some(max_ink) = args.max_ink {
+        ga.set_max_ink(max_ink / 100.0);
+    }
+    if let Some(radius) = args.sharing_radius {
+        ga.set_fitness_sharing(radius);
+    }
+    if let Some(objective) = args.pareto {
+        ga.set_pareto_objective(objective);
+    }
+    scoring.configure_ga(&mut ga);
+    if args.gpu {
+        enable_gpu(&mut ga, false);
+    }
+    ga.set_seed(seed);
+    let seed_elapsed = match previous {
+        Some(previous) => {
+            ga.warm_start(previous);
+            0.0
+        }
+        None => seed_initial_population(args, &mut ga, ascii_gen, target, (width, height), None, scoring),
+    };
+    let (best, elapsed) = ga.evolve(args.generations, args.status_interval, &mut NoProgress);
+    let result = (best, elapsed + seed_elapsed);
+    let early_stop = matches!(ga.stop_reason(), genetic_algorithm::StopReason::Stalled | genetic_algorithm::StopReason::TargetReached)
+        .then(|| (ga.stop_reason(), ga.generations_run()));
+    let (best, elapsed) = refine_result(args, ascii_gen, target, (width, height), None, scoring, result);
+    (best, elapsed, early_stop)
+}
+
+/// Moves fitness evaluation to the GPU for `--gpu`; without a usable adapter the run stays on the CPU
+fn enable_gpu(ga: &mut genetic_algorithm::GeneticAlgorithm, announce: bool) {
+    #[cfg(feature = "gpu")]
+    match ga.enable_gpu() {
+        Ok(adapter) if announce => status!("Evaluating fitness on the GPU: {}", adapter),
+        Ok(_) => {}
+        Err(e) if announce => status!("No usable GPU ({}); evaluating fitness on the CPU", e),
+        Err(_) => {}
+    }
+    #[cfg(not(feature = "gpu"))]
+    let _ = (ga, announce);
+}
+
+/// Seeds part of the population from a quick brute-force pass (`--hybrid`) or a luminance mapping of the target
+/// (`--smart-init`), returning the seconds it took
+fn seed_initial_population<'w>(
+    args: &Args,
+    ga: &mut genetic_algorithm::GeneticAlgorithm<'w>,
+    ascii_gen: &'w ascii_generator::AsciiGenerator,
+    target: &'w image::ImageBuffer<image::Luma<u8>, Vec<u8>>,
+    (width, height): (u32, u32),
+    locks: Option<&'w LockMask>,
+    scoring: ScoringOptions<'w>,
+) -> f64 {
+    if let Some(fraction) = args.smart_init {
+        let (seed, elapsed) = luminance::LuminanceMapper::new(width, height, ascii_gen, target, args.common.white_background).generate();
+        let seeded = ga.seed_population(&seed, fraction);
+        status!("Smart init: seeded {} of {} individuals from a luminance mapping of the target", seeded, args.population);
+        return elapsed;
+    }
+    let Some(fraction) = args.hybrid else {
+        return 0.0;
+    };
+
+    let mut bf_gen = brute_force::BruteForceGenerator::new(width, height, ascii_gen, target, args.common.white_background);
+    if let Some(locks) = locks {
+        bf_gen.set_locks(locks);
+    }
+    scoring.configure_brute_force(&mut bf_gen);
+    let (seed, elapsed) = bf_gen.generate(&mut NoProgress);
+    let seeded = ga.seed_population(&seed, fraction);
+    status!("Hybrid: brute force reached {:.2}% in {:.1}s; seeded {} of {} individuals from it",
+             seed.fitness * 100.0, elapsed, seeded, args.population);
+    elapsed
+}
+
+/// Polishes an evolved result with the `--refine` brute-force sweeps, keeping the polished art only if it scores higher
+fn refine_result<'w>(
+    args: &Args,
+    ascii_gen: &'w ascii_generator::AsciiGenerator,
+    target: &'w image::ImageBuffer<image::Luma<u8>, Vec<u8>>,
+    (width, height): (u32, u32),
+    locks: Option<&'w LockMask>,
+    scoring: ScoringOptions<'w>,
+    (best, elapsed): (genetic_algorithm::Individual, f64),
+) -> (genetic_algorithm::Individual, f64) {
+    if args.refine == 0 {
+        return (best, elapsed);
+    }
+
+    let start = std::time::Instant::now();
+    let mut bf_gen = brute_force::BruteForceGenerator::new(width, height, ascii_gen, target, args.common.white_background);
+    if let Some(locks) = locks {
+        bf_gen.set_locks(locks);
+    }
+    scoring.configure_brute_force(&mut bf_gen);
+    let refined = bf_gen.refine(&best, args.refine);
+    let elapsed = elapsed + start.elapsed().as_secs_f64();
+    status!("Refined with up to {} brute-force sweep(s): fitness {:.2}% -> {:.2}%",
+             args.refine, best.fitness * 100.0, refined.fitness.max(best.fitness) * 100.0);
+    (if refined.fitness > best.fitness { refined } else { best }, elapsed)
+}
+
+/// Mirrors a live camera as ASCII art until the device closes or the user quits
+/// Each frame gets one short optimization pass, warm-started from the previous frame, and replaces the display
+#[cfg_attr(not(feature = "video"), allow(dead_code))]
+fn run_webcam<I>(args: &Args, processor: &image_processor::ImageProcessor, frames: I) -> Result<(), Box<dyn std::error::Error>>
+where
+    I: Iterator<Item = Result<image::DynamicImage, Box<dyn std::error::Error>>>,
+{
+    if args.generations == 0 {
+        return Err("--webcam needs a fixed number of generations per frame (-g greater than 0)".into());
+    }
+
+    let ascii_gen = args.common.ascii_generator();
+    let (char_width, char_height) = ascii_gen.char_dimensions();
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let start = std::time::Instant::now();
+
+    let mut ui = if args.common.no_ui {
+        None
+    } else {
+        terminal_ui::TerminalUI::new()
+            .map_err(|e| eprintln!("Failed to initialize terminal UI: {}. Falling back to console output.", e))
+            .ok()
+    };
+
+    let mut dimensions = None;
+    let mut previous_best: Option<genetic_algorithm::Individual> = None;
+    let mut frame_count = 0u32;
+
+    for (index, frame) in frames.enumerate() {
+        let frame = frame?;
+        let frame = fit_image(&args.common, processor, &frame);
+        let (target_width, target_height) = *dimensions.get_or_insert_with(|| calculate_dimensions(&frame, args.common.width, args.common.height, args.common.fit, args.common.cell_aspect));
+        let target = processor.prepare_target_image_with_inversion(
+            &frame,
+            target_width * char_width,
+            target_height * char_height,
+            args.common.invert_source,
+        )?;
+
+        let (best, _, _) = optimize_target(args, &ascii_gen, &target, (target_width, target_height), seed.wrapping_add(index as u64), previous_best.as_ref());
+        let art = ascii_gen.individual_to_string(&best, target_width);
+        frame_count += 1;
+
+        match &mut ui {
+            Some(ui) => {
+                // Frames stand in for generations, so the UI's rate readout shows frames per second
+                ui.update(&terminal_ui::UIStats {
+                    generation: frame_count,
+                    total_generations: 0,
+                    best_fitness: best.fitness,
+                    elapsed_time: start.elapsed().as_secs_f64(),
+                    population_size: if args.searches() { 0 } else { args.population },
+                    thread_count: args.jobs,
+                    width: target_width,
+                    height: target_height,
+                    ascii_art: Some(art),
+                    population: None,
+                    diversity: None,
+                    parameters: None,
+                    cell_quality: None,
+                });
+                if let Some('q' | 'Q') = ui.check_input() {
+                    break;
+                }
+            }
+            None => {
+                // Clear the terminal and redraw in place; stop with Ctrl-C
+                print!("\x1b[H\x1b[2J{}\nFrame {}: fitness {:.2}%\n", art, frame_count, best.fitness * 100.0);
+            }
+        }
+
+        previous_best = Some(best);
+    }
+
+    drop(ui);
+    status!("Processed {} frames in {:.1}s", frame_count, start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Optimizes each frame of an animation in turn, warm-starting the genetic algorithm from the previous frame's best
+/// Text output writes one numbered file per frame; ANSI output writes a single animation file
+fn run_animation<I>(
+    args: &Args,
+    input: &std::path::Path,
+    processor: &image_processor::ImageProcessor,
+    frames: I,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    I: Iterator<Item = Result<image::DynamicImage, Box<dyn std::error::Error>>>,
+{
+    if let Some(flag) = [
+        (args.watch, "--watch"),
+        (args.seed_art.is_some(), "--seed-art"),
+        (args.template.is_some(), "--template"),
+        (args.ignore_mask.is_some(), "--ignore-mask"),
+        (args.stats_csv.is_some(), "--stats-csv"),
+        (args.log_jsonl.is_some(), "--log-jsonl"),
+        (args.autosave.is_some(), "--autosave"),
+        (args.checkpoint.is_some(), "--checkpoint"),
+        (args.resume.is_some(), "--resume"),
+        (args.control_file.is_some(), "--control-file"),
+        (args.frames_dir.is_some(), "--frames-dir"),
+        (args.common.char_usage, "--char-usage"),
+    ]
+        .into_iter()
+        .find_map(|(set, flag)| set.then_some(flag))
+    {
+        return Err(format!("{} is only supported for a single still image", flag).into());
+    }
+    if args.generations == 0 {
+        return Err("animated input needs a fixed number of generations per frame (-g greater than 0)".into());
+    }
+    if args.common.format == export::OutputFormat::Json {
+        return Err("--format json is not supported for animated input".into());
+    }
+
+    let ascii_gen = args.common.ascii_generator();
+    let (char_width, char_height) = ascii_gen.char_dimensions();
+    let seed = args.seed.unwrap_or_else(rand::random);
+    if !args.searches() || args.traversal == brute_force::TraversalOrder::Random {
+        status!("Random seed: {}", seed);
+    }
+
+    let mut dimensions = None;
+    let mut previous_best: Option<genetic_algorithm::Individual> = None;
+    let mut frame_arts = Vec::new();
+
+    for (index, frame) in frames.enumerate() {
+        let frame = frame?;
+        let frame = fit_image(&args.common, processor, &frame);
+        let (target_width, target_height) = *dimensions.get_or_insert_with(|| {
+            let dims = calculate_dimensions(&frame, args.common.width, args.common.height, args.common.fit, args.common.cell_aspect);
+            status!("Target ASCII dimensions: {}x{}", dims.0, dims.1);
+            dims
+        });
+        let target = processor.prepare_target_image_with_inversion(
+            &frame,
+            target_width * char_width,
+            target_height * char_height,
+            args.common.invert_source,
+        )?;
+
+        let (best, elapsed, _) = optimize_target(args, &ascii_gen, &target, (target_width, target_height), seed.wrapping_add(index as u64), previous_best.as_ref());
+
+        let art = ascii_gen.individual_to_string(&best, target_width);
+        status!("Frame {}: fitness {:.2}% ({:.1}s)", index + 1, best.fitness * 100.0, elapsed);
+        if args.common.verbose || args.common.output.is_none() {
+            status!("{}\n", art);
+        }
+
+        if let (export::OutputFormat::Text, Some(output_path)) = (args.common.format, &args.common.output) {
+            let path = export::frame_path(output_path, index);
+            std::fs::write(&path, &art)?;
+            status!("Frame saved to: {:?}", path);
+        }
+
+        frame_arts.push(art);
+        previous_best = Some(best);
+    }
+
+    if frame_arts.is_empty() {
+        return Err(format!("no frames could be decoded from {:?}", input).into());
+    }
+
+    if let (export::OutputFormat::Ans, Some(output_path)) = (args.common.format, &args.common.output) {
+        let sauce = export::SauceInfo {
+            title: input_stem(input),
+            ..Default::default()
+        };
+        std::fs::write(output_path, export::encode_ans_animation(&frame_arts, args.common.white_background, &sauce)?)?;
+        status!("ANSI animation saved to: {:?}", output_path);
+    }
+
+    status!("Processed {} frames", frame_arts.len());
+    Ok(())
+}
+
+/// Exit status of a run stopped by SIGTERM once its checkpoint is written (EX_TEMPFAIL from sysexits.h), telling a
+/// batch script to continue it with `--resume`
+const EXIT_CHECKPOINTED: i32 = 75;
+
+/// Returns a flag set when the process receives SIGTERM, which batch schedulers send before killing a job
+/// Only Unix has the signal; elsewhere the flag is never set
+fn termination_flag() -> std::sync::Arc<AtomicBool> {
+    let flag = std::sync::Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    if let Err(e) = signal_hook::flag::register(signal_hook::consts::SIGTERM, std::sync::Arc::clone(&flag)) {
+        eprintln!("Warning: SIGTERM cannot be caught, so no checkpoint will be written: {}", e);
+    }
+    flag
+}
+
+/// Reads a checkpoint written on SIGTERM
+fn load_checkpoint(path: &std::path::Path) -> Result<genetic_algorithm::Checkpoint, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read checkpoint {:?}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("{:?} is not a checkpoint: {}", path, e))
+}
+
+/// Passes progress on to the console or UI sink showing the run while recording every generation to the run files,
+/// and stops the run on SIGTERM
+struct Recording<'a> {
+    display: &'a mut dyn ProgressSink,
+    files: &'a mut RunFiles,
+    terminate: &'a AtomicBool,
+}
+
+impl ProgressSink for Recording<'_> {
+    fn report(&mut self, event: &ProgressEvent) -> bool {
+        let keep_going = self.display.report(event);
+        if let Some(art) = &event.ascii_art {
+            self.files.autosave(art);
+        }
+        keep_going
+    }
+
+    fn wants_art(&self) -> bool {
+        self.display.wants_art() || self.files.autosave_due()
+    }
+
+    fn wants_cell_quality(&self) -> bool {
+        self.display.wants_cell_quality()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.display.cancelled() || self.terminate.load(Ordering::Relaxed)
+    }
+
+    fn tune(&mut self) -> Option<Tuning> {
+        // Keys pressed in the UI win over a control file changed at the same time
+        match (self.display.tune(), self.files.poll_control()) {
+            (Some(keys), Some(file)) => Some(keys.or(file)),
+            (keys, file) => keys.or(file),
+        }
+    }
 
-    #[arg(short = 'H', long, help = "Height in characters")]
-    height: Option<u32>,
+    fn records(&self) -> bool {
+        self.files.stats_csv.is_some() || self.files.run_log.is_some() || self.files.frames.is_some()
+    }
 
-    #[arg(short, long, default_value = "100", help = "Number of generations (0 = continuous mode)")]
-    generations: u32,
+    fn record(&mut self, event: &ProgressEvent) {
+        self.files.record(event);
+    }
 
-    #[arg(short, long, default_value = "4", help = "Number of threads for parallel fitness evaluation")]
-    jobs: usize,
+    fn records_art(&self, step: u32) -> bool {
+        self.files.frame_due(step)
+    }
+}
 
-    #[arg(short = 'i', long, help = "Character to initialize art buffers with (95% of characters, 5% random)")]
-    init_char: Option<char>,
+/// A file written line by line during a run
+/// A failed write stops further lines and is returned by `finish`, so it cannot interrupt the run
+struct RunFile {
+    writer: std::io::BufWriter<std::fs::File>,
+    error: Option<std::io::Error>,
+}
 
-    #[arg(short, long, help = "Output file path (optional)")]
-    output: Option<PathBuf>,
+impl RunFile {
+    fn create(path: &std::path::Path) -> std::io::Result<Self> {
+        Ok(Self { writer: std::io::BufWriter::new(std::fs::File::create(path)?), error: None })
+    }
 
-    #[arg(short = 'd', long, help = "Save debug images (converted input and final ASCII art as PNG files)")]
-    debug: bool,
+    fn write_line(&mut self, line: &str) {
+        use std::io::Write;
+        if self.error.is_none() {
+            if let Err(e) = writeln!(self.writer, "{}", line) {
+                self.error = Some(e);
+            }
+        }
+    }
 
-    #[arg(short = 'v', long, help = "Verbose output: display fittest ASCII art after each progress update")]
-    verbose: bool,
+    /// Flushes the file, or returns the first error a line failed with
+    fn finish(mut self) -> std::io::Result<()> {
+        use std::io::Write;
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.writer.flush(),
+        }
+    }
+}
 
-    #[arg(short = 'W', long, help = "Use white background (default is black background with white characters)")]
+/// Periodic `--autosave` of the best art to the output file
+struct Autosave {
+    path: PathBuf,
+    interval: std::time::Duration,
+    last_saved: std::time::Instant,
+    format: export::OutputFormat,
     white_background: bool,
+    /// SAUCE title for `--format ans`
+    title: String,
+    /// The first failed save; later saves are still attempted
+    error: Option<String>,
+}
 
-    #[arg(short = 's', long, default_value = "1.0", help = "Status update interval in seconds")]
-    status_interval: f64,
+impl Autosave {
+    fn due(&self) -> bool {
+        self.last_saved.elapsed() >= self.interval
+    }
 
-    #[arg(short = 'p', long, default_value = "80", help = "Population size (20-1000)")]
-    population: usize,
+    fn save(&mut self, art: &str) {
+        self.last_saved = std::time::Instant::now();
+        let saved = encode_art(self.format, self.white_background, &self.title, art)
+            .and_then(|contents| export::write_atomic(&self.path, &contents).map_err(|e| e.to_string()));
+        if let Err(e) = saved {
+            self.error.get_or_insert(e);
+        }
+    }
+}
 
-    #[arg(long, help = "Disable interactive ncurses UI and use console output instead")]
-    no_ui: bool,
+/// `--frames-dir` images of the best art, rendered from the art of recorded generations
+struct Frames {
+    dir: PathBuf,
+    every: u32,
+    /// Renders the frames; built from the glyph options like the run's own generator
+    ascii_gen: ascii_generator::AsciiGenerator,
+    white_background: bool,
+    written: usize,
+    /// The first failed frame, which stops further frames
+    error: Option<String>,
+}
 
-    #[arg(short = 'b', long, help = "Use brute-force mode instead of genetic algorithm")]
-    brute_force: bool,
+impl Frames {
+    fn due(&self, generation: u32) -> bool {
+        self.error.is_none() && generation.is_multiple_of(self.every)
+    }
 
-    #[arg(short = 'I', long, help = "Invert source image colors (useful for negative images)")]
-    invert_source: bool,
+    fn save(&mut self, generation: u32, width: u32, height: u32, art: &str) {
+        let Some(chars) = self.ascii_gen.charset().encode(&art.replace('\n', "")) else { return };
+        let path = self.dir.join(format!("frame_{:06}.png", generation));
+        let image = self.ascii_gen.generate_ascii_image_with_background(&chars, width, height, self.white_background);
+        match image.save(&path) {
+            Ok(()) => self.written += 1,
+            Err(e) => self.error = Some(format!("{:?}: {}", path, e)),
+        }
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+/// A `--control-file` polled during a run for parameter changes
+struct ControlFile {
+    path: PathBuf,
+    /// Modification time of the contents last read
+    modified: Option<std::time::SystemTime>,
+    last_polled: std::time::Instant,
+}
 
-    if args.width.is_none() && args.height.is_none() {
-        eprintln!("Error: Must specify either width or height");
-        std::process::exit(1);
+impl ControlFile {
+    /// Time between checks of the file's modification time
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+    /// Returns the file's settings when it changed since the last read; a malformed file is reported and skipped
+    fn poll(&mut self) -> Option<Tuning> {
+        if self.last_polled.elapsed() < Self::POLL_INTERVAL {
+            return None;
+        }
+        self.last_polled = std::time::Instant::now();
+        let modified = std::fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok()?;
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+        let text = std::fs::read_to_string(&self.path).ok()?;
+        parse_control(&text).map_err(|e| eprintln!("Warning: ignoring --control-file {:?}: {}", self.path, e)).ok()
     }
+}
 
-    if args.width.is_some() && args.height.is_some() {
-        eprintln!("Error: Specify only width OR height, not both");
-        std::process::exit(1);
+/// Parses the `name = value` lines of a control file; blank lines and `#` comments are skipped, and the status
+/// interval is clamped to the range the UI's tuning keys allow
+fn parse_control(text: &str) -> Result<Tuning, String> {
+    let mut tuning = Tuning::default();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let (name, value) = line.split_once('=').ok_or_else(|| format!("expected name = value, got {:?}", line))?;
+        let (name, value) = (name.trim(), value.trim());
+        let number = |max: f64| {
+            value.parse::<f64>().ok().filter(|number| (0.0..=max).contains(number)).ok_or_else(|| format!("invalid {} {:?}", name, value))
+        };
+        match name {
+            "status_interval" => {
+                tuning.status_interval = Some(number(f64::MAX)?.clamp(terminal_ui::MIN_TUNED_INTERVAL, terminal_ui::MAX_TUNED_INTERVAL))
+            }
+            "mutation_rate" => tuning.mutation_rate = Some(number(1.0)?),
+            "crossover_rate" => tuning.crossover_rate = Some(number(1.0)?),
+            "elite_size" => tuning.elite_size = Some(value.parse().map_err(|_| format!("invalid elite_size {:?}", value))?),
+            _ => return Err(format!("unknown setting {:?}", name)),
+        }
     }
+    Ok(tuning)
+}
 
-    if args.population < 20 || args.population > 1000 {
-        eprintln!("Error: Population size must be between 20 and 1000");
-        std::process::exit(1);
+/// The `--stats-csv` and `--log-jsonl` files, `--autosave` output, `--control-file` and `--frames-dir` of a genetic
+/// algorithm run
+struct RunFiles {
+    stats_csv: Option<RunFile>,
+    run_log: Option<RunFile>,
+    autosave: Option<Autosave>,
+    control: Option<ControlFile>,
+    frames: Option<Frames>,
+    /// Stall window and epsilon, for stagnation events
+    stall: (u32, f64),
+    /// Restart count and best fitness of the last recorded generation
+    last: Option<(u32, f64)>,
+}
+
+impl RunFiles {
+    const STATS_CSV_HEADER: &'static str = "generation,best,mean,median,worst,diversity,mutation_rate,elapsed_seconds";
+
+    fn create(args: &Args, input: &std::path::Path) -> Result<Self, String> {
+        let open = |path: &Option<PathBuf>, flag: &str| match path {
+            Some(path) => RunFile::create(path).map(Some).map_err(|e| format!("Failed to create {} {:?}: {}", flag, path, e)),
+            None => Ok(None),
+        };
+        let mut files = Self {
+            stats_csv: open(&args.stats_csv, "--stats-csv")?,
+            run_log: open(&args.log_jsonl, "--log-jsonl")?,
+            autosave: args.autosave.zip(args.common.output.clone()).map(|(seconds, path)| Autosave {
+                path,
+                interval: std::time::Duration::from_secs_f64(seconds),
+                last_saved: std::time::Instant::now(),
+                format: args.common.format,
+                white_background: args.common.white_background,
+                title: input_stem(input),
+                error: None,
+            }),
+            control: args.control_file.clone().map(|path| ControlFile { path, modified: None, last_polled: std::time::Instant::now() }),
+            frames: match &args.frames_dir {
+                Some(dir) => {
+                    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create --frames-dir {:?}: {}", dir, e))?;
+                    Some(Frames {
+                        dir: dir.clone(),
+                        every: args.frame_every,
+                        ascii_gen: args.common.ascii_generator(),
+                        white_background: args.common.white_background,
+                        written: 0,
+                        error: None,
+                    })
+                }
+                None => None,
+            },
+            stall: (args.stall_generations, args.stall_epsilon),
+            last: None,
+        };
+        if let Some(stats_csv) = files.stats_csv.as_mut() {
+            stats_csv.write_line(Self::STATS_CSV_HEADER);
+        }
+        Ok(files)
     }
 
-    println!("Loading image: {:?}", args.input);
-    let processor = image_processor::ImageProcessor::new();
-    let original_img = processor.load_image(&args.input)?;
+    /// Whether the next report should carry the art for `autosave`
+    fn autosave_due(&self) -> bool {
+        self.autosave.as_ref().is_some_and(Autosave::due)
+    }
 
-    println!("Input image size: {}x{}", original_img.width(), original_img.height());
+    /// Writes the art to the output file once the autosave interval has passed
+    fn autosave(&mut self, art: &str) {
+        if let Some(autosave) = self.autosave.as_mut().filter(|autosave| autosave.due()) {
+            autosave.save(art);
+        }
+    }
 
-    let (target_width, target_height) = calculate_dimensions(
-        &original_img,
-        args.width,
-        args.height
-    );
+    /// Whether the event recorded for `generation` should carry the art for a frame
+    fn frame_due(&self, generation: u32) -> bool {
+        self.frames.as_ref().is_some_and(|frames| frames.due(generation))
+    }
 
-    println!("Target ASCII dimensions: {}x{}", target_width, target_height);
+    /// Settings of the control file when it changed
+    fn poll_control(&mut self) -> Option<Tuning> {
+        self.control.as_mut().and_then(ControlFile::poll)
+    }
 
-    let ascii_gen = ascii_generator::AsciiGenerator::new();
+    /// Appends an event to the run log, if there is one
+    fn log(&mut self, event: export::LogEvent) {
+        if let Some(run_log) = self.run_log.as_mut() {
+            run_log.write_line(&event.to_json_line());
+        }
+    }
 
-    // Calculate actual pixel dimensions needed for ASCII character rendering
-    let (char_width, char_height) = ascii_gen.char_dimensions();
-    let target_pixel_width = target_width * char_width;
-    let target_pixel_height = target_height * char_height;
+    /// Writes a generation's statistics row and log event; a grown restart count means the population stagnated
+    /// and was reseeded since the last generation recorded
+    fn record(&mut self, event: &ProgressEvent) {
+        if let (Some(frames), Some(art)) = (self.frames.as_mut(), &event.ascii_art) {
+            frames.save(event.step, event.width, event.height, art);
+        }
+        let stats = event.fitness_stats.unwrap_or(asciigen::FitnessStats { best: 0.0, mean: 0.0, median: 0.0, worst: 0.0 });
+        let (diversity, mutation_rate, restarts) =
+            (event.diversity.unwrap_or_default(), event.mutation_rate.unwrap_or_default(), event.restarts.unwrap_or_default());
 
-    println!("Character dimensions: {}x{}", char_width, char_height);
-    println!("Target pixel dimensions: {}x{}", target_pixel_width, target_pixel_height);
+        if let Some(stats_csv) = self.stats_csv.as_mut() {
+            stats_csv.write_line(&format!(
+                "{},{:.6},{:.6},{:.6},{:.6},{:.6},{},{:.3}",
+                event.step, stats.best, stats.mean, stats.median, stats.worst, diversity, mutation_rate, event.elapsed
+            ));
+        }
 
-    let resized_bw = processor.prepare_target_image_with_inversion(&original_img, target_pixel_width, target_pixel_height, args.invert_source)?;
+        if let Some((last_restarts, last_best)) = self.last {
+            if restarts > last_restarts {
+                let (stall_generations, stall_epsilon) = self.stall;
+                self.log(export::LogEvent::Stagnation { generation: event.step, best_fitness: last_best, stall_generations, stall_epsilon });
+                self.log(export::LogEvent::Restart { generation: event.step, restarts });
+            }
+        }
+        self.last = Some((restarts, stats.best));
+        self.log(export::LogEvent::Generation {
+            generation: event.step,
+            best: stats.best,
+            mean: stats.mean,
+            median: stats.median,
+            worst: stats.worst,
+            diversity,
+            mutation_rate,
+            elapsed_seconds: event.elapsed,
+        });
+    }
 
-    if args.invert_source {
-        println!("Source image colors inverted");
+    /// Flushes both files and names the ones written, warning about a failed autosave or frame
+    fn finish(self, args: &Args) -> Result<(), String> {
+        if let Some(Autosave { path, error: Some(e), .. }) = &self.autosave {
+            eprintln!("Warning: --autosave could not write {:?}: {}", path, e);
+        }
+        if let Some(frames) = &self.frames {
+            if let Some(e) = &frames.error {
+                eprintln!("Warning: --frames-dir stopped after {} frames; could not write {}", frames.written, e);
+            } else {
+                status!("{} frames written to {:?}", frames.written, frames.dir);
+            }
+        }
+        for (file, path, flag, contents) in [
+            (self.stats_csv, &args.stats_csv, "--stats-csv", "Generation statistics"),
+            (self.run_log, &args.log_jsonl, "--log-jsonl", "Run log"),
+        ] {
+            if let (Some(file), Some(path)) = (file, path) {
+                file.finish().map_err(|e| format!("Failed to write {} {:?}: {}", flag, path, e))?;
+                status!("{} written to {:?}", contents, path);
+            }
+        }
+        Ok(())
     }
-    println!("Post-processed input image size: {}x{}", resized_bw.width(), resized_bw.height());
+}
 
-    let (best_individual, total_elapsed) = if args.brute_force {
-        // Use brute force mode
-        println!("Running brute force generation for {}x{} characters...", target_width, target_height);
-        
-        let bf_gen = brute_force::BruteForceGenerator::new(
-            target_width,
-            target_height,
-            &ascii_gen,
-            &resized_bw,
-            args.white_background,
-        );
+/// How `ConsoleProgress` shows progress
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConsoleStyle {
+    /// A progress bar with ETA and best fitness on stderr, redrawn in place
+    Bar,
+    /// One plain status line per update, for logs and scripts
+    Lines,
+    /// Nothing
+    Quiet,
+}
 
-        if args.no_ui {
-            // Use console output for brute force
-            bf_gen.generate(args.verbose, None::<fn(u32, u32, f64, f64, u32, u32, Option<String>) -> bool>)
+impl ConsoleStyle {
+    /// Picks the style for the --quiet and --log flags, falling back to lines when stderr is not a terminal
+    fn select(common: &CommonArgs, stderr_is_terminal: bool) -> Self {
+        if common.quiet {
+            Self::Quiet
+        } else if common.log || !stderr_is_terminal {
+            Self::Lines
         } else {
-            // Use ncurses UI for brute force
-            match ncurses_ui::NcursesUI::new() {
-                Ok(mut ui) => {
-                    let result = bf_gen.generate(args.verbose, Some(|position, total_positions, progress, elapsed_time, width, height, ascii_art| {
-                        let stats = ncurses_ui::UIStats {
-                            generation: position,
-                            total_generations: total_positions,
-                            best_fitness: progress,
-                            elapsed_time,
-                            population_size: 1, // Brute force doesn't use population
-                            thread_count: 1,    // Brute force is single-threaded
-                            width,
-                            height,
-                            ascii_art,
-                        };
-
-                        ui.update(&stats);
-
-                        // Check for user input
-                        if let Some(ch) = ui.check_input() {
-                            match ch {
-                                'q' | 'Q' => return false, // Quit
-                                _ => {}
-                            }
-                        }
-
-                        true // Continue generation
-                    }));
-
-                    ui.show_message("Brute force generation complete! Press any key to continue...");
-                    ui.check_input(); // Wait for key press
-                    result
-                },
-                Err(e) => {
-                    eprintln!("Failed to initialize ncurses UI: {}. Falling back to console output.", e);
-                    bf_gen.generate(args.verbose, None::<fn(u32, u32, f64, f64, u32, u32, Option<String>) -> bool>)
-                }
+            Self::Bar
+        }
+    }
+}
+
+/// Console progress reporter used when the UI is disabled
+/// Draws a progress bar on stderr, or with --log prints every status update of the genetic algorithm and every 10th
+/// position of the brute-force search; the current art is printed in verbose mode
+struct ConsoleProgress {
+    verbose: bool,
+    style: ConsoleStyle,
+    /// Set with --char-usage to summarise the characters of each printed art
+    usage_charset: Option<Charset>,
+    /// Created at the first event, once the kind and length of the run are known
+    bar: Option<indicatif::ProgressBar>,
+}
+
+impl ConsoleProgress {
+    fn new(common: &CommonArgs) -> Self {
+        Self {
+            verbose: common.verbose,
+            style: ConsoleStyle::select(common, std::io::stderr().is_terminal()),
+            usage_charset: common.char_usage.then(|| common.glyphs.charset()),
+            bar: None,
+        }
+    }
+
+    /// Moves the bar to the event, creating it on the first one
+    fn draw_bar(&mut self, event: &ProgressEvent) {
+        let bar = self.bar.get_or_insert_with(|| {
+            let bar = indicatif::ProgressBar::new(event.total_steps as u64);
+            bar.set_style(indicatif::ProgressStyle::with_template(bar_template(event)).expect("valid progress bar template"));
+            if event.total_steps == 0 {
+                bar.enable_steady_tick(std::time::Duration::from_millis(120));
             }
+            bar
+        });
+        bar.set_position(event.step as u64);
+        bar.set_message(bar_message(event));
+    }
+
+    /// Prints the current art in verbose mode, above the bar when one is drawn
+    fn print_art(&self, event: &ProgressEvent) {
+        // Art can also be requested for --autosave
+        let (true, Some(art)) = (self.verbose, &event.ascii_art) else { return };
+        let usage = self.usage_charset.as_ref().and_then(|charset| {
+            let codes = charset.encode(&art.replace('\n', ""))?;
+            Some(char_usage_summary(charset, &charset.usage(&codes)))
+        });
+        let print = || {
+            status!("Current best ASCII art:\n{}\n", art);
+            if let Some(usage) = &usage {
+                status!("{}\n", usage);
+            }
+        };
+        match &self.bar {
+            Some(bar) => bar.suspend(print),
+            None => print(),
         }
-    } else {
-        // Use genetic algorithm mode
-        let mut ga = genetic_algorithm::GeneticAlgorithm::new(
-            target_width,
-            target_height,
-            args.population,
-            &ascii_gen,
-            &resized_bw,
-            args.jobs,
-            args.init_char,
-            args.white_background,
-        );
+    }
+}
 
-        if args.generations == 0 {
-            println!("Running genetic algorithm in continuous mode with population size {} (press 'q' in UI to stop)...", args.population);
-        } else {
-            println!("Running genetic algorithm for {} generations with population size {}...", args.generations, args.population);
+/// Characters of the set that appear in the art with their counts, most frequent first
+fn used_chars(charset: &Charset, counts: &[usize]) -> Vec<(char, usize)> {
+    let mut used: Vec<(char, usize)> = charset.chars().iter().copied().zip(counts.iter().copied()).filter(|&(_, count)| count > 0).collect();
+    used.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    used
+}
+
+/// Used characters of the set with their share of the cells, followed by the unused ones
+fn char_usage_report(charset: &Charset, counts: &[usize]) -> Vec<String> {
+    let total = counts.iter().sum::<usize>().max(1);
+    let used = used_chars(charset, counts);
+    let mut lines = vec![format!("Character usage ({} of {} characters used):", used.len(), charset.len())];
+    lines.extend(used.iter().map(|&(ch, count)| format!("  {:?} {:>6} {:>6.1}%", ch, count, count as f64 * 100.0 / total as f64)));
+    let unused: Vec<String> = charset.chars().iter().zip(counts).filter(|&(_, &count)| count == 0).map(|(ch, _)| format!("{:?}", ch)).collect();
+    if !unused.is_empty() {
+        lines.push(format!("Unused characters ({}): {}", unused.len(), unused.join(" ")));
+    }
+    lines
+}
+
+/// One-line form of the usage report for progress updates, naming only the most frequent characters
+fn char_usage_summary(charset: &Charset, counts: &[usize]) -> String {
+    const SHOWN: usize = 8;
+    let total = counts.iter().sum::<usize>().max(1);
+    let used = used_chars(charset, counts);
+    let top: Vec<String> = used.iter().take(SHOWN).map(|&(ch, count)| format!("{:?} {:.1}%", ch, count as f64 * 100.0 / total as f64)).collect();
+    format!("Character usage ({} of {} used): {}", used.len(), charset.len(), top.join(", "))
+}
+
+/// Progress bar layout for the kind of run: a spinner in continuous mode, otherwise a bar with ETA
+fn bar_template(event: &ProgressEvent) -> &'static str {
+    match (event.kind, event.total_steps) {
+        (ProgressKind::Generation, 0) => "{spinner} [{elapsed_precise}] generation {pos} {msg} - press Ctrl+C to stop",
+        (ProgressKind::Generation, _) => "[{elapsed_precise}] {wide_bar} {pos}/{len} generations {msg} (ETA {eta})",
+        (ProgressKind::Position, _) => "[{elapsed_precise}] {wide_bar} {pos}/{len} positions (ETA {eta})",
+    }
+}
+
+/// Best fitness and population diversity shown after the bar, empty until the search has scored its art
+fn bar_message(event: &ProgressEvent) -> String {
+    match (event.best_fitness, event.diversity) {
+        (Some(fitness), Some(diversity)) => format!("best {:.2}%, diversity {:.1}%", fitness * 100.0, diversity * 100.0),
+        (Some(fitness), None) => format!("best {:.2}%", fitness * 100.0),
+        (None, _) => String::new(),
+    }
+}
+
+impl Drop for ConsoleProgress {
+    fn drop(&mut self) {
+        // The summary printed after the run replaces the bar
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
         }
+    }
+}
 
-        if args.no_ui {
-            // Use console output
-            ga.evolve(args.generations, args.verbose, args.status_interval, None::<fn(u32, u32, f64, f64, usize, usize, u32, u32, Option<String>) -> bool>)
-        } else {
-            // Use ncurses UI
-            match ncurses_ui::NcursesUI::new() {
-                Ok(mut ui) => {
-                    let result = ga.evolve(args.generations, args.verbose, args.status_interval, Some(|generation, total_generations, best_fitness, elapsed_time, population_size, thread_count, width, height, ascii_art| {
-                        let stats = ncurses_ui::UIStats {
-                            generation,
-                            total_generations,
-                            best_fitness,
-                            elapsed_time,
-                            population_size,
-                            thread_count,
-                            width,
-                            height,
-                            ascii_art,
-                        };
-
-                        ui.update(&stats);
-
-                        // Check for user input
-                        if let Some(ch) = ui.check_input() {
-                            match ch {
-                                'q' | 'Q' => return false, // Quit
-                                _ => {}
-                            }
-                        }
-
-                        true // Continue evolution
-                    }));
-
-                    ui.show_message("Evolution complete! Press any key to continue...");
-                    ui.check_input(); // Wait for key press
-                    result
-                },
-                Err(e) => {
-                    eprintln!("Failed to initialize ncurses UI: {}. Falling back to console output.", e);
-                    ga.evolve(args.generations, args.verbose, args.status_interval, None::<fn(u32, u32, f64, f64, usize, usize, u32, u32, Option<String>) -> bool>)
+impl ProgressSink for ConsoleProgress {
+    fn report(&mut self, event: &ProgressEvent) -> bool {
+        match self.style {
+            ConsoleStyle::Quiet => return true,
+            ConsoleStyle::Bar => {
+                self.draw_bar(event);
+                self.print_art(event);
+                return true;
+            }
+            ConsoleStyle::Lines => {}
+        }
+        match event.kind {
+            ProgressKind::Generation => {
+                let best_fitness = event.best_fitness.unwrap_or_default() * 100.0;
+                if event.total_steps == 0 {
+                    status!("Generation {}: Best fitness = {:.2}% (elapsed: {:.1}s) [Continuous mode - press Ctrl+C to stop]",
+                             event.step, best_fitness, event.elapsed);
+                } else {
+                    status!("Generation {}: Best fitness = {:.2}% (elapsed: {:.1}s)",
+                             event.step, best_fitness, event.elapsed);
+                }
+                self.print_art(event);
+            }
+            ProgressKind::Position => {
+                if event.step.is_multiple_of(10) || event.step == event.total_steps {
+                    status!("Progress: {}/{} positions ({:.1}%) - elapsed: {:.1}s",
+                             event.step, event.total_steps, event.progress().unwrap_or_default() * 100.0, event.elapsed);
                 }
             }
         }
-    };
+        true
+    }
 
-    // Generate output ASCII image buffer to get its dimensions
-    let output_ascii_image = ascii_gen.generate_ascii_image(&best_individual.chars, target_width, target_height);
-    println!("Output ASCII image buffer size: {}x{}", output_ascii_image.width(), output_ascii_image.height());
+    fn wants_art(&self) -> bool {
+        self.verbose && self.style != ConsoleStyle::Quiet
+    }
+}
 
-    let ascii_art = ascii_gen.individual_to_string(&best_individual, target_width);
-    let mode_str = if args.brute_force { "brute-force" } else { "genetic algorithm" };
-    println!("\nBest ASCII art ({}x{} characters, fitness: {:.2}%, mode: {}, elapsed: {:.1}s):\n{}", target_width, target_height, best_individual.fitness * 100.0, mode_str, total_elapsed, ascii_art);
+/// Prints each character of the active set with its glyph coverage, lightest first, followed by the ramp
+fn print_charset_info(ascii_gen: &ascii_generator::AsciiGenerator) {
+    let ramp = ascii_gen.density_ramp();
 
-    if let Some(output_path) = args.output {
-        std::fs::write(&output_path, ascii_art)?;
-        println!("ASCII art saved to: {:?}", output_path);
+    println!("Character set ranked by glyph coverage ({} characters):", ramp.len());
+    for &c in ramp {
+        println!("  {:?}  {:6.2}%", ascii_gen.charset().char_at(c), ascii_gen.glyph_coverage(c) * 100.0);
     }
+    let ramp_text: String = ramp.iter().map(|&c| ascii_gen.charset().char_at(c)).collect();
+    println!("Density ramp: \"{}\"", ramp_text);
+}
 
-    // Save debug images if requested
-    if args.debug {
-        // Save converted input image
-        let input_debug_path = format!("debug_input_{}.png",
-            args.input.file_stem().unwrap_or_default().to_string_lossy());
-        resized_bw.save(&input_debug_path)?;
-        println!("Debug input image saved to: {}", input_debug_path);
+/// Writes art shown in the interactive UI to a timestamped snapshot_<input>_<time>.txt, plus the rendered PNG when
+/// `png` carries the glyph renderer, and returns the confirmation for the footer
+fn save_snapshot(
+    art: &str,
+    input: &std::path::Path,
+    png: Option<&ascii_generator::AsciiGenerator>,
+    white_background: bool,
+) -> Result<String, String> {
+    let stem = format!("snapshot_{}_{}", input_stem(input), export::file_timestamp());
+    let text_path = format!("{}.txt", stem);
+    std::fs::write(&text_path, art).map_err(|e| format!("{}: {}", text_path, e))?;
+    let Some(ascii_gen) = png else {
+        return Ok(format!("Snapshot saved to {}", text_path));
+    };
 
-        // Save final ASCII art as image (same size as fitness comparison buffer)
-        let ascii_image = ascii_gen.generate_ascii_image_with_background(&best_individual.chars, target_width, target_height, args.white_background);
-        let ascii_debug_path = format!("debug_ascii_{}.png",
-            args.input.file_stem().unwrap_or_default().to_string_lossy());
-        ascii_image.save(&ascii_debug_path)?;
-        println!("Debug ASCII image saved to: {}", ascii_debug_path);
-    }
+    let png_path = format!("{}.png", stem);
+    let (individual, width, height) = genetic_algorithm::Individual::from_art(art, ascii_gen.charset())?;
+    ascii_gen
+        .generate_ascii_image_with_background(&individual.chars, width, height, white_background)
+        .save(&png_path)
+        .map_err(|e| format!("{}: {}", png_path, e))?;
+    Ok(format!("Snapshot saved to {} and {}", text_path, png_path))
+}
 
-    Ok(())
+/// Returns the input's file stem for titles and debug file names, or "stdin" for piped input
+fn input_stem(input: &std::path::Path) -> String {
+    if image_processor::ImageProcessor::is_stdin_path(input) {
+        "stdin".to_string()
+    } else {
+        input.file_stem().unwrap_or_default().to_string_lossy().into_owned()
+    }
 }
 
+/// Grid size for the image from the given sides; `cell_aspect` is the width:height ratio of a character cell
 fn calculate_dimensions(
     img: &image::DynamicImage,
     width: Option<u32>,
-    height: Option<u32>
+    height: Option<u32>,
+    fit: Fit,
+    cell_aspect: f32,
 ) -> (u32, u32) {
     let (img_width, img_height) = img.dimensions();
     let aspect_ratio = img_width as f32 / img_height as f32;
 
     match (width, height) {
         (Some(w), None) => {
-            let h = (w as f32 / aspect_ratio * 0.5) as u32; // Account for character aspect ratio
+            let h = (w as f32 / aspect_ratio * cell_aspect) as u32; // Account for character aspect ratio
             (w, h.max(1))
         },
         (None, Some(h)) => {
-            let w = (h as f32 * aspect_ratio * 2.0) as u32; // Account for character aspect ratio
+            let w = (h as f32 * aspect_ratio / cell_aspect) as u32; // Account for character aspect ratio
             (w.max(1), h)
         },
-        _ => unreachable!(), // Already validated in main
+        (Some(w), Some(h)) => match fit {
+            // Whichever side would overflow the box sets the size
+            Fit::Contain => {
+                let (from_width, from_height) = calculate_dimensions(img, Some(w), None, fit, cell_aspect);
+                if from_width <= w && from_height <= h {
+                    (from_width, from_height)
+                } else {
+                    calculate_dimensions(img, None, Some(h), fit, cell_aspect)
+                }
+            }
+            Fit::Cover | Fit::Stretch => (w, h),
+        },
+        (None, None) => unreachable!(), // Already validated in main
+    }
+}
+
+/// Crops the image to the proportions of the grid for `--fit cover`; other fits use the whole image
+fn fit_image<'i>(common: &CommonArgs, processor: &image_processor::ImageProcessor, img: &'i image::DynamicImage) -> std::borrow::Cow<'i, image::DynamicImage> {
+    match (common.fit, common.width, common.height) {
+        (Fit::Cover, Some(w), Some(h)) => std::borrow::Cow::Owned(processor.crop_to_aspect(img, w as f32 * common.cell_aspect / h as f32)),
+        _ => std::borrow::Cow::Borrowed(img),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use clap::CommandFactory;
     use image::{DynamicImage, RgbImage};
 
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_no_subcommand_means_generate() {
+        let cli = Cli::try_parse_from(["asciigen", "in.png", "-w", "20", "-g", "5"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.generate.generations, 5);
+        assert_eq!(cli.generate.common.width, Some(20));
+
+        let cli = Cli::try_parse_from(["asciigen", "convert", "in.png", "-w", "20"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Convert(_))));
+        assert!(Cli::try_parse_from(["asciigen", "convert", "in.png", "-g", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_preset_fills_only_unset_options() {
+        let matches = Cli::command().try_get_matches_from(["asciigen", "in.png", "-w", "20", "--preset", "best", "-p", "50"]).unwrap();
+        let mut args = Cli::from_arg_matches(&matches).unwrap().generate;
+        args.apply_preset(&matches);
+        assert_eq!(args.population, 50);
+        assert_eq!(args.generations, 1000);
+        assert_eq!(args.mutation_rate, 0.005);
+        assert_eq!(args.common.glyphs.font_size, 16.0);
+        assert!(!args.brute_force);
+    }
+
+    #[test]
+    fn test_overlap_options() {
+        let args = Cli::try_parse_from(["asciigen", "in.png", "-w", "20", "--tolerance", "60", "--fp-penalty", "0.02"]).unwrap().generate;
+        let params = args.overlap.params().unwrap();
+        assert_eq!((params.tolerance, params.false_positive_penalty), (60, 0.02));
+        let recorded = run_parameters(&args, std::path::Path::new("in.png"));
+        assert_eq!((recorded.tolerance, recorded.fp_penalty), (60, 0.02));
+        assert_eq!(Cli::try_parse_from(["asciigen", "in.png"]).unwrap().generate.overlap.params().unwrap(), fitness::OverlapParams::default());
+
+        assert!(Cli::try_parse_from(["asciigen", "in.png", "--tolerance", "0"]).is_err());
+        let negative = Cli::try_parse_from(["asciigen", "in.png", "--fp-penalty=-1"]).unwrap().generate;
+        assert!(negative.overlap.params().is_err());
+    }
+
+    #[test]
+    fn test_score_takes_the_tone_options() {
+        let parse = |extra: &[&str]| -> Result<ToneArgs, clap::Error> {
+            let cli = Cli::try_parse_from(["asciigen", "score", "art.txt", "in.png"].iter().chain(extra))?;
+            match cli.command {
+                Some(Command::Score(args)) => Ok(args.tone),
+                _ => panic!("expected the score command"),
+            }
+        };
+        let tone = parse(&["--gamma", "1.5", "--equalize"]).unwrap();
+        assert!(tone.adjusts_tones() && tone.equalize && tone.gamma == 1.5);
+        assert!(!parse(&[]).unwrap().adjusts_tones());
+        assert!(parse(&["--auto-contrast", "--equalize"]).is_err());
+        assert!(parse(&["--gamma", "0"]).unwrap().validate().is_err());
+
+        let tone = parse(&["--clahe", "--clahe-tiles", "4"]).unwrap();
+        assert_eq!((tone.clahe, tone.clahe_tiles), (Some(2.0), 4));
+        assert!(parse(&["--clahe", "0.5"]).unwrap().validate().is_err());
+        assert!(parse(&["--clahe", "--equalize"]).is_err());
+
+        let levels = parse(&["--levels", "30,220,1.2"]).unwrap().levels.unwrap();
+        assert_eq!((levels.black, levels.white, levels.gamma), (30, 220, 1.2));
+        assert!(parse(&["--levels", "220,30"]).is_err());
+        assert!(parse(&["--levels", "30,220", "--auto-contrast"]).is_err());
+
+        assert_eq!(parse(&["--posterize", "4"]).unwrap().posterize, Some(4));
+        assert!(parse(&["--posterize", "1"]).unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn test_score_blank_and_unreadable_art() {
+        let dir = std::env::temp_dir().join(format!("asciigen_score_blank_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image_path = dir.join("gray.png");
+        RgbImage::from_pixel(40, 40, image::Rgb([90, 90, 90])).save(&image_path).unwrap();
+        let score = |art: &[u8]| {
+            let art_path = dir.join("art.txt");
+            std::fs::write(&art_path, art).unwrap();
+            let cli = Cli::try_parse_from(["asciigen", "score", art_path.to_str().unwrap(), image_path.to_str().unwrap()]).unwrap();
+            match cli.command {
+                Some(Command::Score(args)) => score_art(&args).map_err(|e| e.to_string()),
+                _ => panic!("expected the score command"),
+            }
+        };
+
+        let (report, width, height) = score(b"    \n    \n").unwrap();
+        assert_eq!((width, height, report.counts.matches), (4, 2, 0));
+        assert!(score(b"\n\n").unwrap_err().contains("art file is empty"));
+        assert!(score(b"\xff\xfe").unwrap_err().contains("art.txt"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_score_reproduces_posterized_run_fitness() {
+        let dir = std::env::temp_dir().join(format!("asciigen_score_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (image_path, art_path) = (dir.join("gradient.png"), dir.join("art.txt"));
+        RgbImage::from_fn(96, 48, |x, y| {
+            let level = ((x * 255 / 95 + y * 2) % 256) as u8;
+            image::Rgb([level, level / 2, 255 - level])
+        })
+        .save(&image_path)
+        .unwrap();
+        let image_arg = image_path.to_str().unwrap();
+        let tone = ["--posterize", "4", "--gamma", "1.4", "--tolerance", "40"];
+
+        let args = Cli::try_parse_from(["asciigen", image_arg, "-w", "12", "-g", "5", "-j", "1", "-p", "20"].iter().chain(&tone))
+            .unwrap()
+            .generate;
+        let processor = args.common.image_processor();
+        let image = processor.load_image(&image_path).unwrap();
+        let (width, height) = calculate_dimensions(&image, args.common.width, None, Fit::Contain, args.common.cell_aspect);
+        let ascii_gen = args.common.ascii_generator();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = processor.prepare_target_image_with_inversion(&image, width * char_width, height * char_height, false).unwrap();
+        let (best, _, _) = optimize_target(&args, &ascii_gen, &target, (width, height), 5, None);
+        std::fs::write(&art_path, ascii_gen.individual_to_string(&best, width)).unwrap();
+
+        let score_args = |extra: &[&str]| {
+            let cli = Cli::try_parse_from(["asciigen", "score", art_path.to_str().unwrap(), image_arg].iter().chain(extra)).unwrap();
+            match cli.command {
+                Some(Command::Score(args)) => score_art(&args).unwrap(),
+                _ => panic!("expected the score command"),
+            }
+        };
+        let (score, score_width, score_height) = score_args(&tone);
+        assert_eq!((score_width, score_height), (width, height));
+        assert!((score.fitness - best.fitness).abs() < 1e-9, "scored {} but the run reported {}", score.fitness, best.fitness);
+        // Without the run's tone flags the art is compared with a different target
+        assert!((score_args(&["--tolerance", "40"]).0.fitness - best.fitness).abs() > 1e-6);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_console_style_flags() {
+        let style = |extra: &[&str], terminal| {
+            let args = Cli::try_parse_from([&["asciigen", "in.png", "--no-ui"], extra].concat()).unwrap().generate;
+            ConsoleStyle::select(&args.common, terminal)
+        };
+        assert_eq!(style(&[], true), ConsoleStyle::Bar);
+        assert_eq!(style(&[], false), ConsoleStyle::Lines);
+        assert_eq!(style(&["--log"], true), ConsoleStyle::Lines);
+        assert_eq!(style(&["-q"], true), ConsoleStyle::Quiet);
+        assert!(Cli::try_parse_from(["asciigen", "in.png", "--quiet", "--log"]).is_err());
+        assert!(Cli::try_parse_from(["asciigen", "in.png", "--quiet", "-v"]).is_err());
+    }
+
+    #[test]
+    fn test_cell_heatmap_paints_cells_by_quality() {
+        let heatmap = cell_heatmap(&[1.0, 0.0, 0.5, 0.25], (2, 2), (3, 2));
+        assert_eq!(heatmap.dimensions(), (6, 4));
+        assert_eq!([(0, 0), (5, 1), (2, 3), (3, 2)].map(|(x, y)| heatmap.get_pixel(x, y)[0]), [255, 0, 128, 64]);
+    }
+
+    #[test]
+    fn test_char_usage_report() {
+        let charset = Charset::parse(" .:#").unwrap();
+        let counts = charset.usage(&charset.encode("##. #").unwrap());
+        assert_eq!(
+            char_usage_report(&charset, &counts),
+            vec![
+                "Character usage (3 of 4 characters used):".to_string(),
+                "  '#'      3   60.0%".to_string(),
+                "  ' '      1   20.0%".to_string(),
+                "  '.'      1   20.0%".to_string(),
+                "Unused characters (1): ':'".to_string(),
+            ]
+        );
+        assert_eq!(char_usage_summary(&charset, &counts), "Character usage (3 of 4 used): '#' 60.0%, ' ' 20.0%, '.' 20.0%");
+    }
+
+    #[test]
+    fn test_parse_control() {
+        let tuning = parse_control("# faster updates\nstatus_interval = 0.5\n\n  mutation_rate=0.02\nelite_size = 3\n").unwrap();
+        assert_eq!(tuning, Tuning { mutation_rate: Some(0.02), crossover_rate: None, elite_size: Some(3), status_interval: Some(0.5) });
+        assert_eq!(parse_control("").unwrap(), Tuning::default());
+
+        assert!(parse_control("crossover_rate = 1.5").is_err());
+        assert!(parse_control("status_interval = -1").is_err());
+        // The interval is kept to the same range as the UI's '-'/'+' keys
+        assert_eq!(parse_control("status_interval = 0").unwrap().status_interval, Some(terminal_ui::MIN_TUNED_INTERVAL));
+        assert_eq!(parse_control("status_interval = 60").unwrap().status_interval, Some(terminal_ui::MAX_TUNED_INTERVAL));
+        assert!(parse_control("elite_size = many").is_err());
+        assert!(parse_control("population = 50").is_err());
+        assert!(parse_control("mutation_rate 0.1").is_err());
+    }
+
+    #[test]
+    fn test_autosave_replaces_output_when_due() {
+        let path = std::env::temp_dir().join(format!("asciigen_autosave_{}.ans", std::process::id()));
+        let mut autosave = Autosave {
+            path: path.clone(),
+            interval: std::time::Duration::from_secs(3600),
+            last_saved: std::time::Instant::now(),
+            format: export::OutputFormat::Ans,
+            white_background: false,
+            title: "duck".to_string(),
+            error: None,
+        };
+        assert!(!autosave.due());
+
+        autosave.last_saved -= autosave.interval;
+        assert!(autosave.due());
+        autosave.save("ab\ncd");
+        assert!(!autosave.due() && autosave.error.is_none());
+        assert_eq!(export::decode_ans_frames(&std::fs::read(&path).unwrap()), ["ab\ncd"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_frames_saved_every_n_generations() {
+        let dir = std::env::temp_dir().join(format!("asciigen_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ascii_gen = ascii_generator::AsciiGenerator::new();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let mut frames = Frames { dir: dir.clone(), every: 5, ascii_gen, white_background: false, written: 0, error: None };
+        assert_eq!((0..=10).filter(|&generation| frames.due(generation)).collect::<Vec<_>>(), vec![0, 5, 10]);
+
+        frames.save(5, 2, 2, "@#\n. ");
+        assert_eq!((frames.written, frames.error.as_ref()), (1, None));
+        assert_eq!(image::image_dimensions(dir.join("frame_000005.png")).unwrap(), (2 * char_width, 2 * char_height));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_calculate_dimensions_from_width() {
         let img = DynamicImage::ImageRgb8(RgbImage::new(100, 50));
-        let (w, h) = calculate_dimensions(&img, Some(80), None);
+        let (w, h) = calculate_dimensions(&img, Some(80), None, Fit::Contain, DEFAULT_CELL_ASPECT);
         assert_eq!(w, 80);
         assert!(h > 0);
         assert!(h < 80); // Should be less due to aspect ratio adjustment
@@ -296,8 +3453,91 @@ mod tests {
     #[test]
     fn test_calculate_dimensions_from_height() {
         let img = DynamicImage::ImageRgb8(RgbImage::new(100, 50));
-        let (w, h) = calculate_dimensions(&img, None, Some(40));
+        let (w, h) = calculate_dimensions(&img, None, Some(40), Fit::Contain, DEFAULT_CELL_ASPECT);
         assert_eq!(h, 40);
         assert!(w > 40); // Should be more due to aspect ratio
     }
+
+    #[test]
+    fn test_parse_levels() {
+        assert_eq!(parse_levels("30,220,1.2"), Ok(image_processor::Levels { black: 30, white: 220, gamma: 1.2 }));
+        assert_eq!(parse_levels("0, 128"), Ok(image_processor::Levels { black: 0, white: 128, gamma: 1.0 }));
+        assert!(parse_levels("30").is_err());
+        assert!(parse_levels("200,100").is_err());
+        assert!(parse_levels("0,256").is_err());
+        assert!(parse_levels("0,255,0").is_err());
+        assert!(parse_levels("0,255,1,2").is_err());
+
+        assert!(Cli::try_parse_from(["asciigen", "in.png", "--levels", "30,220", "--auto-contrast"]).is_err());
+    }
+
+    #[test]
+    fn test_batch_worker_jobs() {
+        assert_eq!(batch_worker_jobs(8, 1), 8);
+        assert_eq!(batch_worker_jobs(8, 4), 2);
+        // Leftover cores stay idle rather than giving some workers more threads than others
+        assert_eq!(batch_worker_jobs(8, 3), 2);
+        assert_eq!(batch_worker_jobs(2, 4), 1);
+        assert_eq!(batch_worker_jobs(1, 0), 1);
+    }
+
+    #[test]
+    fn test_play_frames() {
+        let frames = ["ab\n".to_string(), "cd".to_string()];
+        let mut out = Vec::new();
+        play_frames(&mut out, &frames, std::time::Duration::ZERO, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[H\x1b[2Jab\nFrame 1/2\n\x1b[H\x1b[2Jcd\nFrame 2/2\n");
+
+        // A closed pipe surfaces as an error instead of a panic, even while repeating
+        struct ClosedPipe;
+        impl std::io::Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let error = play_frames(&mut ClosedPipe, &frames, std::time::Duration::ZERO, true).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("120x40"), Ok((120, 40)));
+        assert_eq!(parse_size("80X24"), Ok((80, 24)));
+        assert!(parse_size("120").is_err());
+        assert!(parse_size("0x40").is_err());
+        assert!(parse_size("120x").is_err());
+
+        let cli = Cli::try_parse_from(["asciigen", "in.png", "--size", "120x40", "--fit", "cover"]).unwrap();
+        assert_eq!(cli.generate.common.size, Some((120, 40)));
+        assert!(Cli::try_parse_from(["asciigen", "in.png", "--size", "120x40", "-w", "80"]).is_err());
+    }
+
+    #[test]
+    fn test_calculate_dimensions_follows_cell_aspect() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(100, 50));
+        // Square cells keep the image's own proportions; narrower cells need more rows
+        assert_eq!(calculate_dimensions(&img, Some(80), None, Fit::Contain, 1.0), (80, 40));
+        assert_eq!(calculate_dimensions(&img, Some(80), None, Fit::Contain, 0.4), (80, 16));
+        assert_eq!(calculate_dimensions(&img, None, Some(16), Fit::Contain, 0.4), (80, 16));
+    }
+
+    #[test]
+    fn test_calculate_dimensions_with_both_sides() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(100, 50));
+        // Width binds in a tall box, height in a flat one
+        assert_eq!(calculate_dimensions(&img, Some(80), Some(24), Fit::Contain, DEFAULT_CELL_ASPECT), (80, 20));
+        assert_eq!(calculate_dimensions(&img, Some(80), Some(10), Fit::Contain, DEFAULT_CELL_ASPECT), (40, 10));
+        assert_eq!(calculate_dimensions(&img, Some(80), Some(10), Fit::Cover, DEFAULT_CELL_ASPECT), (80, 10));
+        assert_eq!(calculate_dimensions(&img, Some(80), Some(10), Fit::Stretch, DEFAULT_CELL_ASPECT), (80, 10));
+
+        // Cover crops the image to the box so the cells keep their proportions
+        let mut common = Cli::try_parse_from(["asciigen", "in.png", "-w", "80", "-H", "10", "--fit", "cover"]).unwrap().generate.common;
+        let processor = image_processor::ImageProcessor::new();
+        assert_eq!(fit_image(&common, &processor, &img).dimensions(), (100, 25));
+        common.fit = Fit::Stretch;
+        assert_eq!(fit_image(&common, &processor, &img).dimensions(), (100, 50));
+    }
 }
@@ -0,0 +1,338 @@
+//! Progress reporting shared by the search engines
+//!
+//! `GeneticAlgorithm::evolve` and `BruteForceGenerator::generate` both describe their progress with a
+//! [`ProgressEvent`] and hand it to a [`ProgressSink`], which can show it and stop the run early.
+//!
+//! A [`ChannelSink`] forwards events as [`GenerationUpdate`]s over a channel instead, so a GUI or service can run the
+//! search on a worker thread and stop it through a [`CancelHandle`] without a callback borrowing the engine.
+//! Every update carries the art, so a receiver that drains more slowly than reports arrive should use
+//! [`ChannelSink::bounded`], which holds the search back while the channel is full:
+//!
+//! ```
+//! use asciigen::{AsciiGenerator, CancelHandle, ChannelSink, GeneticAlgorithm};
+//! use image::{ImageBuffer, Luma};
+//! use std::sync::mpsc;
+//!
+//! let ascii_gen = AsciiGenerator::new();
+//! let (char_width, char_height) = ascii_gen.char_dimensions();
+//! let target = ImageBuffer::from_fn(8 * char_width, 4 * char_height, |x, _| Luma([(x * 4 % 256) as u8]));
+//!
+//! let (sender, updates) = mpsc::sync_channel(2);
+//! let cancel = CancelHandle::new();
+//! let mut sink = ChannelSink::bounded(sender, cancel.clone());
+//! std::thread::scope(|scope| {
+//!     // Continuous mode, reporting every 0.1 seconds: runs until cancelled
+//!     scope.spawn(|| GeneticAlgorithm::new(8, 4, 20, &ascii_gen, &target, 1, None, false).evolve(0, 0.1, &mut sink));
+//!     for update in updates.iter().take(3) {
+//!         println!("generation {}: {:.2}%", update.generation, update.fitness.map_or(0.0, |stats| stats.best) * 100.0);
+//!     }
+//!     cancel.cancel();
+//!     // Dropping the receiver also releases a search waiting on the full channel
+//!     drop(updates);
+//! });
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Sender, SyncSender};
+use std::sync::Arc;
+
+/// What the steps of a `ProgressEvent` count
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressKind {
+    /// Generations of the genetic algorithm
+    Generation,
+    /// Positions filled by the brute-force or beam search
+    Position,
+}
+
+/// Fitness spread of a population at one generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FitnessStats {
+    pub best: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub worst: f64,
+}
+
+/// Parameter changes a sink asks a running genetic algorithm for; unset fields keep their value
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tuning {
+    pub mutation_rate: Option<f64>,
+    pub crossover_rate: Option<f64>,
+    pub elite_size: Option<usize>,
+    /// Seconds between progress reports
+    pub status_interval: Option<f64>,
+}
+
+impl Tuning {
+    /// Combines two requests, preferring the fields set in `self`
+    pub fn or(self, other: Tuning) -> Tuning {
+        Tuning {
+            mutation_rate: self.mutation_rate.or(other.mutation_rate),
+            crossover_rate: self.crossover_rate.or(other.crossover_rate),
+            elite_size: self.elite_size.or(other.elite_size),
+            status_interval: self.status_interval.or(other.status_interval),
+        }
+    }
+}
+
+/// One progress report from a running search
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressEvent {
+    pub kind: ProgressKind,
+    /// Generations run or positions filled so far
+    pub step: u32,
+    /// Steps in the whole run, 0 when the genetic algorithm runs continuously
+    pub total_steps: u32,
+    /// Fitness of the best art so far; the brute-force search only scores its art once it is complete
+    pub best_fitness: Option<f64>,
+    /// Fitness spread of the current population, for the genetic algorithm
+    pub fitness_stats: Option<FitnessStats>,
+    /// Mean fraction of cells in which two individuals differ, for the genetic algorithm
+    pub diversity: Option<f64>,
+    /// Per-character mutation probability, for the genetic algorithm
+    pub mutation_rate: Option<f64>,
+    /// Restarts after stalling so far, for the genetic algorithm
+    pub restarts: Option<u32>,
+    /// Crossover rate, for the genetic algorithm
+    pub crossover_rate: Option<f64>,
+    /// Individuals copied unchanged into each generation, for the genetic algorithm
+    pub elite_size: Option<usize>,
+    /// Seconds between reports, for the genetic algorithm
+    pub status_interval: Option<f64>,
+    /// Seconds since the run started
+    pub elapsed: f64,
+    /// Individuals per generation, 1 for the brute-force search
+    pub population_size: usize,
+    /// Threads the engine runs on
+    pub thread_count: usize,
+    /// Art width in characters
+    pub width: u32,
+    /// Art height in characters
+    pub height: u32,
+    /// The best art so far, only filled in when the sink `wants_art`
+    pub ascii_art: Option<String>,
+    /// How well each cell of the best art does, row-major, from 0 for the worst cell to 1 for cells holding the best
+    /// character there; only filled in by the genetic algorithm when the sink `wants_cell_quality`
+    pub cell_quality: Option<Vec<f32>>,
+}
+
+impl ProgressEvent {
+    /// Fraction of the run completed (0-1), or None in continuous mode
+    pub fn progress(&self) -> Option<f64> {
+        (self.total_steps > 0).then(|| self.step as f64 / self.total_steps as f64)
+    }
+}
+
+/// Receives progress events from a search
+pub trait ProgressSink {
+    /// Handles one event; returning false stops the search early
+    fn report(&mut self, event: &ProgressEvent) -> bool;
+
+    /// Whether events should carry the current art, which costs a render of the grid per event
+    fn wants_art(&self) -> bool {
+        true
+    }
+
+    /// Whether events should carry `cell_quality`, which costs an extra render and per-cell scoring per event
+    fn wants_cell_quality(&self) -> bool {
+        false
+    }
+
+    /// Polled after every generation or position, between reports; returning true stops the search
+    fn cancelled(&self) -> bool {
+        false
+    }
+
+    /// Polled every generation of the genetic algorithm for parameter changes to apply before breeding the next one
+    fn tune(&mut self) -> Option<Tuning> {
+        None
+    }
+
+    /// Whether `record` should receive every generation of the genetic algorithm
+    fn records(&self) -> bool {
+        false
+    }
+
+    /// Receives every evaluated generation, whatever the status interval, without the art unless `records_art` asks
+    /// for it; the final population is recorded once the run stops
+    fn record(&mut self, _event: &ProgressEvent) {}
+
+    /// Whether the event `record` receives for generation `step` should carry the art, which costs a render of the grid
+    fn records_art(&self, _step: u32) -> bool {
+        false
+    }
+}
+
+/// A sink that ignores every event and never stops the search
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn report(&mut self, _event: &ProgressEvent) -> bool {
+        true
+    }
+
+    fn wants_art(&self) -> bool {
+        false
+    }
+}
+
+impl<F: FnMut(&ProgressEvent) -> bool> ProgressSink for F {
+    fn report(&mut self, event: &ProgressEvent) -> bool {
+        self(event)
+    }
+}
+
+/// A shared flag that stops a search from another thread
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the search to stop; it finishes the generation or position in progress and returns its best art
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A progress report as sent over a channel by `ChannelSink`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationUpdate {
+    /// Generations run (positions filled by the brute-force search) so far
+    pub generation: u32,
+    /// Generations in the whole run, 0 in continuous mode
+    pub total_generations: u32,
+    /// Fitness spread of the population; None for the brute-force search
+    pub fitness: Option<FitnessStats>,
+    /// Seconds since the run started
+    pub elapsed: f64,
+    /// Text of the best art so far
+    pub art: String,
+}
+
+impl From<&ProgressEvent> for GenerationUpdate {
+    fn from(event: &ProgressEvent) -> Self {
+        Self {
+            generation: event.step,
+            total_generations: event.total_steps,
+            fitness: event.fitness_stats,
+            elapsed: event.elapsed,
+            art: event.ascii_art.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// A sink that sends every event over a channel and stops the search once its `CancelHandle` is cancelled or the
+/// receiver is dropped
+#[derive(Clone, Debug)]
+pub struct ChannelSink {
+    sender: UpdateSender,
+    cancel: CancelHandle,
+}
+
+/// Sending half of the channel a `ChannelSink` reports to
+#[derive(Clone, Debug)]
+enum UpdateSender {
+    Unbounded(Sender<GenerationUpdate>),
+    Bounded(SyncSender<GenerationUpdate>),
+}
+
+impl ChannelSink {
+    /// Sends over an unbounded channel: the search never waits, so updates the receiver hasn't taken yet pile up in
+    /// memory, one art `String` each
+    pub fn new(sender: Sender<GenerationUpdate>, cancel: CancelHandle) -> Self {
+        Self { sender: UpdateSender::Unbounded(sender), cancel }
+    }
+
+    /// Sends over a `sync_channel`: a report waits while the channel is full, so a slow receiver slows the search
+    /// down instead of letting updates pile up; cancel and drop the receiver together to stop a search that is waiting
+    pub fn bounded(sender: SyncSender<GenerationUpdate>, cancel: CancelHandle) -> Self {
+        Self { sender: UpdateSender::Bounded(sender), cancel }
+    }
+}
+
+impl ProgressSink for ChannelSink {
+    fn report(&mut self, event: &ProgressEvent) -> bool {
+        let update = GenerationUpdate::from(event);
+        let sent = match &self.sender {
+            UpdateSender::Unbounded(sender) => sender.send(update).is_ok(),
+            UpdateSender::Bounded(sender) => sender.send(update).is_ok(),
+        };
+        sent && !self.cancel.is_cancelled()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsciiGenerator, GeneticAlgorithm, StopReason};
+    use image::{ImageBuffer, Luma};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_channel_sink_streams_until_cancelled() {
+        let ascii_gen = AsciiGenerator::new();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = ImageBuffer::from_fn(4 * char_width, 2 * char_height, |x, _| Luma([(x * 8 % 256) as u8]));
+
+        let (sender, updates) = mpsc::channel();
+        let cancel = CancelHandle::new();
+        let mut sink = ChannelSink::new(sender, cancel.clone());
+        let mut ga = GeneticAlgorithm::new(4, 2, 20, &ascii_gen, &target, 1, None, false);
+        ga.set_seed(5);
+
+        let received: Vec<GenerationUpdate> = std::thread::scope(|scope| {
+            scope.spawn(|| ga.evolve(0, 0.0, &mut sink));
+            let received = updates.iter().take(3).collect();
+            cancel.cancel();
+            received
+        });
+
+        assert_eq!(received.iter().map(|update| update.generation).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(received.iter().all(|update| update.fitness.is_some() && update.art.lines().count() == 2));
+        assert_eq!(ga.stop_reason(), StopReason::Interrupted);
+
+        // A dropped receiver stops the run at the next report
+        drop(updates);
+        let (sender, _) = mpsc::channel();
+        ga.evolve(0, 0.0, &mut ChannelSink::new(sender, CancelHandle::new()));
+        assert_eq!((ga.generations_run(), ga.stop_reason()), (0, StopReason::Interrupted));
+    }
+
+    #[test]
+    fn test_bounded_channel_sink_waits_for_the_receiver() {
+        let ascii_gen = AsciiGenerator::new();
+        let (char_width, char_height) = ascii_gen.char_dimensions();
+        let target = ImageBuffer::from_fn(4 * char_width, 2 * char_height, |x, _| Luma([(x * 8 % 256) as u8]));
+
+        let (sender, updates) = mpsc::sync_channel(1);
+        let cancel = CancelHandle::new();
+        let mut sink = ChannelSink::bounded(sender, cancel.clone());
+        let mut ga = GeneticAlgorithm::new(4, 2, 20, &ascii_gen, &target, 1, None, false);
+        ga.set_seed(5);
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| ga.evolve(0, 0.0, &mut sink));
+            assert_eq!(updates.recv().unwrap().generation, 0);
+            // Reporting every generation, an unbounded channel would take hundreds of updates meanwhile
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            cancel.cancel();
+            drop(updates);
+        });
+        // One update was taken, one fills the slot and the search stopped waiting to send the next
+        assert_eq!(ga.stop_reason(), StopReason::Interrupted);
+        assert!(ga.generations_run() <= 3, "{} generations run", ga.generations_run());
+    }
+}
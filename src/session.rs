@@ -0,0 +1,196 @@
+use crate::{AsciiGenerator, Charset, GeneticAlgorithm, ImageProcessor, Individual};
+use image::{DynamicImage, ImageBuffer, Luma};
+use std::mem::ManuallyDrop;
+
+/// Settings for an `EvolutionSession`
+#[derive(Clone, Debug)]
+pub struct SessionConfig {
+    /// Width of the art in characters; the height follows the image's aspect ratio
+    pub width: u32,
+    /// Population size (20-1000)
+    pub population: usize,
+    /// Characters the genetic algorithm may place
+    pub charset: Charset,
+    /// Font size in pixels used for fitness rendering (4-64)
+    pub font_size: f32,
+    /// Optimize for dark characters on a white background
+    pub white_background: bool,
+    /// Invert the source image before optimizing
+    pub invert_source: bool,
+    /// Seed for reproducible runs; a random seed is used if None
+    pub seed: Option<u64>,
+    /// Threads used for fitness evaluation, 0 for one per logical core (ignored on WebAssembly, which runs single-threaded)
+    pub threads: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            width: 40,
+            population: 80,
+            charset: Charset::default(),
+            font_size: crate::ascii_generator::DEFAULT_FONT_SIZE,
+            white_background: false,
+            invert_source: false,
+            seed: None,
+            threads: 4,
+        }
+    }
+}
+
+/// A genetic algorithm run that owns its glyph renderer and target image
+/// Unlike `GeneticAlgorithm`, which borrows both, a session can be stored in long-lived handles
+/// (JavaScript objects, C pointers) and advanced a few generations at a time between snapshots
+pub struct EvolutionSession {
+    ga: ManuallyDrop<GeneticAlgorithm<'static>>,
+    ascii_generator: *mut AsciiGenerator,
+    target_image: *mut ImageBuffer<Luma<u8>, Vec<u8>>,
+    width: u32,
+    height: u32,
+    white_background: bool,
+}
+
+impl EvolutionSession {
+    /// Prepares a run that converts `image` into art `config.width` characters wide
+    pub fn new(image: &DynamicImage, config: &SessionConfig) -> Result<Self, String> {
+        if config.width == 0 {
+            return Err("width must be at least 1 character".to_string());
+        }
+        if !(20..=1000).contains(&config.population) {
+            return Err("population size must be between 20 and 1000".to_string());
+        }
+        if !(4.0..=64.0).contains(&config.font_size) {
+            return Err("font size must be between 4 and 64".to_string());
+        }
+
+        let mut ascii_generator = AsciiGenerator::with_charset(config.charset.clone());
+        ascii_generator.set_font_size(config.font_size);
+        let (char_width, char_height) = ascii_generator.char_dimensions();
+
+        // Same character aspect correction as the command line: cells are about twice as tall as wide
+        let aspect_ratio = image.width() as f32 / image.height() as f32;
+        let (width, height) = (config.width, ((config.width as f32 / aspect_ratio * 0.5) as u32).max(1));
+
+        let target_image = ImageProcessor::new()
+            .prepare_target_image_with_inversion(image, width * char_width, height * char_height, config.invert_source)
+            .map_err(|e| e.to_string())?;
+
+        // The renderer and target live on the heap for the whole session and are only freed in Drop,
+        // after the genetic algorithm borrowing them has been dropped
+        let ascii_generator = Box::into_raw(Box::new(ascii_generator));
+        let target_image = Box::into_raw(Box::new(target_image));
+        // SAFETY: both pointers come from Box::into_raw above, are never mutated, and outlive `ga` (see Drop)
+        let (ga_generator, ga_target) = unsafe { (&*ascii_generator, &*target_image) };
+
+        let mut ga = GeneticAlgorithm::new(
+            width,
+            height,
+            config.population,
+            ga_generator,
+            ga_target,
+            config.threads,
+            None,
+            config.white_background,
+        );
+        if let Some(seed) = config.seed {
+            ga.set_seed(seed);
+        }
+
+        Ok(Self {
+            ga: ManuallyDrop::new(ga),
+            ascii_generator,
+            target_image,
+            width,
+            height,
+            white_background: config.white_background,
+        })
+    }
+
+    /// Runs the given number of generations and returns the best fitness afterwards (0.0 to 1.0)
+    pub fn step(&mut self, generations: u32) -> f64 {
+        for _ in 0..generations {
+            self.ga.step();
+        }
+        self.fitness()
+    }
+
+    /// Returns the fitness of the best individual so far; 0.0 before the first step
+    pub fn fitness(&self) -> f64 {
+        self.ga.best().fitness
+    }
+
+    /// Returns the number of generations run so far
+    pub fn generation(&self) -> u32 {
+        self.ga.generations_run()
+    }
+
+    /// Returns the art size in characters as (width, height)
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the size in pixels of the target image and of `render_best()`
+    pub fn pixel_dimensions(&self) -> (u32, u32) {
+        // SAFETY: the pointer is valid until Drop
+        unsafe { (*self.target_image).dimensions() }
+    }
+
+    /// Returns the best individual so far
+    pub fn best(&self) -> &Individual {
+        self.ga.best()
+    }
+
+    /// Returns the best art so far as text, rows separated by newlines
+    pub fn best_art(&self) -> String {
+        self.ascii_generator().individual_to_string(self.ga.best(), self.width)
+    }
+
+    /// Renders the best art so far with the fitness font, at the resolution of the target image
+    pub fn render_best(&self) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        self.ascii_generator()
+            .generate_ascii_image_with_background(&self.ga.best().chars, self.width, self.height, self.white_background)
+    }
+
+    fn ascii_generator(&self) -> &AsciiGenerator {
+        // SAFETY: the pointer is valid until Drop
+        unsafe { &*self.ascii_generator }
+    }
+}
+
+impl Drop for EvolutionSession {
+    fn drop(&mut self) {
+        // SAFETY: the genetic algorithm is dropped first, so nothing borrows the boxes when they are freed
+        unsafe {
+            ManuallyDrop::drop(&mut self.ga);
+            drop(Box::from_raw(self.ascii_generator));
+            drop(Box::from_raw(self.target_image));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_session_steps_and_renders() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(40, 20, |x, _| {
+            if x < 20 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        }));
+        let config = SessionConfig { width: 8, population: 20, seed: Some(5), ..Default::default() };
+        let mut session = EvolutionSession::new(&image, &config).unwrap();
+        assert_eq!(session.dimensions(), (8, 2));
+
+        let fitness = session.step(3);
+        assert_eq!(session.generation(), 3);
+        assert_eq!(fitness, session.fitness());
+        assert_eq!(session.best_art().lines().count(), 2);
+
+        let (char_width, char_height) = AsciiGenerator::new().char_dimensions();
+        assert_eq!(session.render_best().dimensions(), (8 * char_width, 2 * char_height));
+        assert_eq!(session.pixel_dimensions(), session.render_best().dimensions());
+
+        assert!(EvolutionSession::new(&image, &SessionConfig { population: 5, ..config }).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::charset::Charset;
+use crate::genetic_algorithm::Individual;
+
+/// Grid cells frozen to fixed characters, such as a caption or logo, so the optimizers only fill in the rest
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockMask {
+    cells: Vec<Option<u8>>,
+}
+
+impl LockMask {
+    /// Creates a mask from one entry per cell: the locked character code, or None for a free cell
+    pub fn new(cells: Vec<Option<u8>>) -> Self {
+        Self { cells }
+    }
+
+    /// Parses a template text file, returning the mask with the template's width and height in characters
+    /// Every character other than `free_char` is locked; cells past the end of a short line are free
+    pub fn from_template(text: &str, charset: &Charset, free_char: char) -> Result<(Self, u32, u32), String> {
+        let mut lines: Vec<&str> = text.lines().collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        if width == 0 {
+            return Err("the template is empty".to_string());
+        }
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            for ch in line.chars() {
+                if ch == free_char {
+                    cells.push(None);
+                } else {
+                    let code = charset
+                        .index_of(ch)
+                        .ok_or_else(|| format!("character {:?} on line {} is not in the character set", ch, row + 1))?;
+                    cells.push(Some(code));
+                }
+            }
+            cells.extend(std::iter::repeat_n(None, width - line.chars().count()));
+        }
+
+        Ok((Self::new(cells), width as u32, lines.len() as u32))
+    }
+
+    /// Returns the number of cells the mask covers
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if the mask covers no cells
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the locked character code at a cell, or None if the cell is free
+    pub fn locked(&self, position: usize) -> Option<u8> {
+        self.cells.get(position).copied().flatten()
+    }
+
+    /// Returns the number of locked cells
+    pub fn locked_count(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    /// Overwrites the locked cells of an individual with their fixed characters
+    pub fn apply(&self, individual: &mut Individual) {
+        for (char, cell) in individual.chars.iter_mut().zip(&self.cells) {
+            if let Some(code) = cell {
+                *char = *code;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_template() {
+        let charset = Charset::parse("AB ").unwrap();
+        let (mask, width, height) = LockMask::from_template("A~B\n~\n", &charset, '~').unwrap();
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(mask.locked_count(), 2);
+        assert_eq!(mask.locked(0), charset.index_of('A'));
+        assert_eq!(mask.locked(1), None);
+        assert_eq!(mask.locked(5), None);
+
+        let mut individual = Individual::new(charset.encode("      ").unwrap());
+        mask.apply(&mut individual);
+        assert_eq!(individual.chars, charset.encode("A B   ").unwrap());
+
+        assert!(LockMask::from_template("AxB", &charset, ' ').is_err());
+    }
+}
@@ -0,0 +1,1166 @@
+use asciigen::{FitnessStats, ProgressEvent, ProgressSink, Tuning};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use image::{Rgb, RgbImage};
+use std::collections::VecDeque;
+use std::io::{BufWriter, Stdout, Write};
+use std::time::{Duration, Instant};
+
+/// Best-fitness readings kept for the history chart, one column each
+const FITNESS_HISTORY_LEN: usize = 60;
+
+/// Gap between the best and worst fitness below which the population is shown as collapsed to near-clones
+const COLLAPSED_SPREAD: f64 = 0.001;
+
+/// Diversity below which the population is shown as nearly uniform (red) or converging (yellow)
+const LOW_DIVERSITY: f64 = 0.01;
+const MEDIUM_DIVERSITY: f64 = 0.05;
+
+/// Bar heights of the history chart, lowest first
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Factor the 'm'/'M' keys divide or multiply the mutation rate by
+const MUTATION_STEP: f64 = 1.5;
+
+/// Mutation rate 'M' raises a rate of zero to
+const MIN_TUNED_MUTATION: f64 = 0.001;
+
+/// Amount the 'c'/'C' keys lower or raise the crossover rate by
+const CROSSOVER_STEP: f64 = 0.1;
+
+/// Factor the '-'/'+' keys divide or multiply the status interval by, and its range in seconds once tuned; keys are
+/// read at each update, so longer intervals would make the UI sluggish
+const INTERVAL_STEP: f64 = 2.0;
+pub const MIN_TUNED_INTERVAL: f64 = 0.1;
+pub const MAX_TUNED_INTERVAL: f64 = 10.0;
+
+/// Smallest terminal the fixed-position statistics fit in; smaller terminals show a notice until resized
+const MIN_ROWS: i32 = 15;
+const MIN_COLUMNS: i32 = 70;
+
+/// Row the art's title is drawn on; the art starts two rows below it
+const ART_TITLE_ROW: i32 = 12;
+
+/// Rows under the art kept free for the footer
+const FOOTER_ROWS: i32 = 3;
+
+/// Widest the progress bars are drawn, in characters between the brackets
+const MAX_BAR_WIDTH: usize = 60;
+
+/// Columns the left/right arrow keys scroll the art by
+const SCROLL_COLUMNS: usize = 4;
+
+/// Keys listed by the help overlay, with what they do
+const KEY_HELP: [(&str, &str); 10] = [
+    ("q, Ctrl-C", "stop the run and keep the best art so far"),
+    ("s", "save a snapshot of the art"),
+    ("h", "toggle the per-cell error heatmap"),
+    ("m / M", "lower / raise the mutation rate (genetic algorithm)"),
+    ("c / C", "lower / raise the crossover rate (genetic algorithm)"),
+    ("e / E", "remove / add an elite (genetic algorithm)"),
+    ("- / +", "shorten / lengthen the status interval (genetic algorithm)"),
+    ("arrows, PgUp/PgDn", "scroll art larger than the terminal; Home returns to the top left"),
+    ("z", "toggle the overview of art larger than the terminal"),
+    ("?", "show this help; any key returns"),
+];
+
+/// Width of each column of parameters in the help overlay
+const PARAMETER_COLUMN_WIDTH: usize = 34;
+
+/// Text colors: good values, medium values, bad values, headers and labels
+const GOOD: Color = Color::Green;
+const MEDIUM: Color = Color::Yellow;
+const BAD: Color = Color::Red;
+const HEADING: Color = Color::Cyan;
+const LABEL: Color = Color::White;
+
+/// Which part of art too large for the terminal is shown: a scrolled window at full size, or a downsampled overview
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct ArtView {
+    /// First line shown
+    top: usize,
+    /// First column shown
+    left: usize,
+    /// Whether the whole art is shown, keeping every n-th line and column
+    zoomed: bool,
+    /// Lines shown at the last draw, which the page keys scroll by
+    page: usize,
+    /// Art line and column of the top left character shown at the last draw
+    origin: (usize, usize),
+    /// Art lines and columns per character shown at the last draw: 1 at full size, more in the overview
+    step: usize,
+}
+
+impl ArtView {
+    /// Scrolls with the arrow, page and Home keys and toggles the overview with 'z'; returns false for other keys
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Up => self.top = self.top.saturating_sub(1),
+            KeyCode::Down => self.top += 1,
+            KeyCode::Left => self.left = self.left.saturating_sub(SCROLL_COLUMNS),
+            KeyCode::Right => self.left += SCROLL_COLUMNS,
+            KeyCode::PageUp => self.top = self.top.saturating_sub(self.page.max(1)),
+            KeyCode::PageDown => self.top += self.page.max(1),
+            KeyCode::Home => (self.top, self.left) = (0, 0),
+            KeyCode::Char('z' | 'Z') => self.zoomed = !self.zoomed,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns the lines of the art that fit in `rows` x `columns`, and a note on which part they are when the art
+    /// does not fit; the scroll position is clamped so the window stays within the art
+    fn visible(&mut self, art: &str, rows: usize, columns: usize) -> (Vec<String>, Option<String>) {
+        let lines: Vec<&str> = art.lines().collect();
+        let height = lines.len();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        self.page = rows;
+        (self.origin, self.step) = ((0, 0), 1);
+        if rows == 0 || columns == 0 || (height <= rows && width <= columns) {
+            return (lines.iter().map(|line| line.to_string()).collect(), None);
+        }
+
+        if self.zoomed {
+            // One factor for both axes keeps the proportions
+            let step = height.div_ceil(rows).max(width.div_ceil(columns));
+            self.step = step;
+            let shown = lines.iter().step_by(step).map(|line| line.chars().step_by(step).collect()).collect();
+            return (shown, Some(format!("overview 1:{}, 'z' to zoom in", step)));
+        }
+
+        self.top = self.top.min(height.saturating_sub(rows));
+        self.left = self.left.min(width.saturating_sub(columns));
+        self.origin = (self.top, self.left);
+        let shown = lines[self.top..height.min(self.top + rows)]
+            .iter()
+            .map(|line| line.chars().skip(self.left).take(columns).collect())
+            .collect();
+        let note = format!(
+            "lines {}-{} of {}, columns {}-{} of {}; arrows/PgUp/PgDn to scroll, 'z' for overview",
+            self.top + 1,
+            height.min(self.top + rows),
+            height,
+            self.left + 1,
+            width.min(self.left + columns),
+            width
+        );
+        (shown, Some(note))
+    }
+}
+
+/// Buffered drawing on the terminal; text is clipped to the screen, and nothing shows until `flush`
+struct Screen {
+    out: BufWriter<Stdout>,
+    rows: i32,
+    columns: i32,
+}
+
+impl Screen {
+    fn new() -> Self {
+        Self { out: BufWriter::with_capacity(1 << 16, std::io::stdout()), rows: 24, columns: 80 }
+    }
+
+    /// Starts a frame: picks up the terminal's current size and clears the screen
+    /// Terminals that report no size (such as some pseudo-terminals) keep the last known one
+    fn clear(&mut self) {
+        if let Ok((columns @ 1.., rows @ 1..)) = terminal::size() {
+            (self.columns, self.rows) = (columns as i32, rows as i32);
+        }
+        let _ = queue!(self.out, Clear(ClearType::All));
+    }
+
+    /// Draws text at a row and column, cut off before the last column (by characters, since art may be Unicode)
+    fn put(&mut self, y: i32, x: i32, color: Color, text: &str) {
+        if y < 0 || y >= self.rows || x < 0 || x >= self.columns - 1 {
+            return;
+        }
+        let text: String = text.chars().take((self.columns - 1 - x) as usize).collect();
+        let _ = queue!(self.out, MoveTo(x as u16, y as u16), SetForegroundColor(color), Print(text), ResetColor);
+    }
+
+    fn flush(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+/// Saves a snapshot of the art shown in the UI, returning a confirmation to show in the footer
+pub type SnapshotSaver<'a> = Box<dyn FnMut(&str) -> Result<String, String> + 'a>;
+
+/// Interactive terminal UI for displaying genetic algorithm progress, drawn with crossterm so it runs on Windows,
+/// macOS and Linux alike
+pub struct TerminalUI<'a> {
+    screen: Screen,
+    start_time: Instant,
+    last_generation: u32,
+    last_update_time: Instant,
+    /// Set once the user pressed 'q' while the UI was reporting progress
+    stopped_by_user: bool,
+    /// Best fitness at the last `FITNESS_HISTORY_LEN` updates, oldest first
+    fitness_history: VecDeque<f64>,
+    /// Parameter changes asked for with the tuning keys since the genetic algorithm last polled
+    pending_tuning: Option<Tuning>,
+    /// Called with the current art when the user presses 's'
+    snapshot: Option<SnapshotSaver<'a>>,
+    /// Outcome of the last snapshot, shown in the footer
+    notice: Option<String>,
+    /// Scroll position and zoom of the art preview
+    art_view: ArtView,
+    /// Whether the art is colored by how well each cell does, toggled with 'h'
+    heatmap: bool,
+    /// Whether the help overlay is shown instead of the statistics, opened with '?'
+    help: bool,
+    /// Settings of the run, as (name, value) pairs, listed by the help overlay
+    run_parameters: Vec<(String, String)>,
+    /// Color of the source image under each cell, row-major, and cells per row, to draw the art in
+    art_colors: Option<(Vec<Color>, usize)>,
+    /// Keys read between reports, while watching for resizes, handled at the next report
+    pending_keys: VecDeque<KeyCode>,
+    /// Statistics of the last update, drawn again when the terminal is resized
+    last_stats: Option<UIStats>,
+}
+
+/// Terminal input the UI acts on
+enum Input {
+    Key(KeyCode),
+    Resize,
+}
+
+/// Draws values as a row of bars from the lowest to the highest value in the slice; equal values draw the lowest bar
+fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+    values
+        .iter()
+        .map(|&value| {
+            let level = if max > min { ((value - min) / (max - min) * top).round() as usize } else { 0 };
+            SPARK_LEVELS[level]
+        })
+        .collect()
+}
+
+/// Statistics to display in the UI
+#[derive(Clone)]
+pub struct UIStats {
+    pub generation: u32,
+    pub total_generations: u32,
+    pub best_fitness: f64,
+    pub elapsed_time: f64,
+    pub population_size: usize,
+    pub thread_count: usize,
+    pub width: u32,
+    pub height: u32,
+    pub ascii_art: Option<String>,
+    /// Fitness spread of the genetic algorithm's population, shown when set
+    pub population: Option<FitnessStats>,
+    /// Mean fraction of cells in which two individuals of the population differ, shown when set
+    pub diversity: Option<f64>,
+    /// Genetic algorithm parameters the tuning keys adjust, shown when set
+    pub parameters: Option<Tuning>,
+    /// Per-cell quality of the art (0 worst to 1 best, row-major, `width` cells per row) to color it by, when set
+    pub cell_quality: Option<Vec<f32>>,
+}
+
+/// One solution offered by `choose_solution`, such as a point on the Pareto front
+pub struct Choice {
+    pub label: String,
+    pub art: String,
+}
+
+impl<'a> TerminalUI<'a> {
+    /// Switch the terminal to raw mode on the alternate screen and create a new UI instance
+    pub fn new() -> Result<Self, String> {
+        // Raw mode delivers keys without Enter or echo
+        terminal::enable_raw_mode().map_err(|e| format!("Failed to enable raw mode: {}", e))?;
+
+        let mut screen = Screen::new();
+        if let Err(e) = execute!(screen.out, EnterAlternateScreen, Hide, Clear(ClearType::All)) {
+            let _ = terminal::disable_raw_mode();
+            return Err(format!("Failed to set up the terminal: {}", e));
+        }
+
+        Ok(Self {
+            screen,
+            start_time: Instant::now(),
+            last_generation: 0,
+            last_update_time: Instant::now(),
+            stopped_by_user: false,
+            fitness_history: VecDeque::with_capacity(FITNESS_HISTORY_LEN),
+            pending_tuning: None,
+            snapshot: None,
+            notice: None,
+            art_view: ArtView::default(),
+            heatmap: false,
+            help: false,
+            run_parameters: Vec::new(),
+            art_colors: None,
+            pending_keys: VecDeque::new(),
+            last_stats: None,
+        })
+    }
+
+    /// Lets the user save the current art with 's' while a run is reported, without stopping it
+    pub fn set_snapshot(&mut self, save: impl FnMut(&str) -> Result<String, String> + 'a) {
+        self.snapshot = Some(Box::new(save));
+    }
+
+    /// Sets the run settings the help overlay lists, as (name, value) pairs
+    pub fn set_run_parameters(&mut self, parameters: Vec<(String, String)>) {
+        self.run_parameters = parameters;
+    }
+
+    /// Draws the art in the colors of the source image, one pixel per cell, previewing color output; ignored when
+    /// `NO_COLOR` is set. Truecolor is used when `COLORTERM` announces it, the nearest of 256 colors otherwise
+    pub fn set_art_colors(&mut self, colors: &RgbImage) {
+        if std::env::var_os("NO_COLOR").is_some() {
+            return;
+        }
+        let truecolor = std::env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit");
+        let cells = colors
+            .pixels()
+            .map(|&Rgb([r, g, b])| if truecolor { Color::Rgb { r, g, b } } else { Color::AnsiValue(ansi256([r, g, b])) })
+            .collect();
+        self.art_colors = Some((cells, colors.width() as usize));
+    }
+
+    /// Whether the user stopped a run by pressing 'q'
+    pub fn stopped_by_user(&self) -> bool {
+        self.stopped_by_user
+    }
+
+    /// Update the display with current statistics, laid out for the terminal's current size
+    pub fn update(&mut self, stats: &UIStats) {
+        // Update timing information
+        self.last_generation = stats.generation;
+        self.last_update_time = Instant::now();
+        if self.fitness_history.len() == FITNESS_HISTORY_LEN {
+            self.fitness_history.pop_front();
+        }
+        self.fitness_history.push_back(stats.best_fitness);
+        self.last_stats = Some(stats.clone());
+        self.draw(stats);
+    }
+
+    /// Draws the statistics, laid out for the terminal's current size
+    fn draw(&mut self, stats: &UIStats) {
+        // Clear screen
+        self.screen.clear();
+
+        if self.screen.rows < MIN_ROWS || self.screen.columns < MIN_COLUMNS {
+            let size = format!("Terminal too small ({}x{}), need {}x{}", self.screen.columns, self.screen.rows, MIN_COLUMNS, MIN_ROWS);
+            self.screen.put(0, 0, LABEL, &size);
+            self.screen.put(1, 0, LABEL, "Resize it or press 'q' to quit");
+            self.screen.flush();
+            return;
+        }
+
+        // Draw header
+        self.draw_header();
+
+        if self.help {
+            self.draw_help(stats);
+            self.screen.flush();
+            return;
+        }
+
+        // Draw main statistics
+        self.draw_stats(stats);
+        if let Some(population) = stats.population {
+            self.draw_population(&population);
+        }
+        self.draw_fitness_history();
+        if let Some(parameters) = stats.parameters {
+            self.draw_parameters(&parameters);
+        }
+
+        // Draw progress bar
+        if stats.total_generations == 0 {
+            self.draw_fitness_progress_bar(stats.best_fitness);
+        } else {
+            self.draw_progress_bar(stats.generation, stats.total_generations);
+        }
+
+        // Draw ASCII art if provided, colored by cell quality in heatmap mode and by the source image otherwise
+        if let Some(ref art) = stats.ascii_art {
+            let heatmap = stats.cell_quality.as_deref().filter(|_| self.heatmap);
+            let heatmap_colors: Option<Vec<Color>> = heatmap.map(|quality| quality.iter().map(|&q| quality_color(q)).collect());
+            let (title, colors) = match &heatmap_colors {
+                Some(colors) => ("Current Best ASCII Art (heatmap: green good, red poor):", Some((colors.as_slice(), stats.width as usize))),
+                None => ("Current Best ASCII Art:", self.art_colors.as_ref().map(|(colors, columns)| (colors.as_slice(), *columns))),
+            };
+            Self::draw_ascii_art(&mut self.screen, ART_TITLE_ROW, title, art, &mut self.art_view, colors);
+        }
+
+        // Draw footer with controls
+        self.draw_footer();
+
+        // Show the frame
+        self.screen.flush();
+    }
+
+    /// Draw the header section
+    fn draw_header(&mut self) {
+        self.screen.put(0, 0, HEADING, "ASCIIGen - Genetic Algorithm ASCII Art Generator");
+        self.screen.put(1, 0, HEADING, "================================================");
+    }
+
+    /// Draw the main statistics section
+    fn draw_stats(&mut self, stats: &UIStats) {
+        let y_start = 3;
+        let continuous_mode = stats.total_generations == 0;
+        let screen = &mut self.screen;
+
+        // Generation info
+        screen.put(y_start, 0, LABEL, "Generation:");
+        if continuous_mode {
+            screen.put(y_start, 15, GOOD, &format!("{} (continuous)", stats.generation));
+        } else {
+            screen.put(y_start, 15, GOOD, &format!("{}/{}", stats.generation, stats.total_generations));
+        }
+
+        // Progress percentage (fitness-based in continuous mode, generation-based otherwise)
+        let progress = if continuous_mode {
+            stats.best_fitness * 100.0 // Fitness as percentage
+        } else {
+            (stats.generation as f64 / stats.total_generations as f64) * 100.0
+        };
+
+        if continuous_mode {
+            screen.put(y_start, 35, LABEL, "Fitness:");
+        } else {
+            screen.put(y_start, 35, LABEL, "Progress:");
+        }
+        let color = if progress < 25.0 { BAD } else if progress < 75.0 { MEDIUM } else { GOOD };
+        screen.put(y_start, 45, color, &format!("{:.1}%", progress));
+
+        // Best fitness
+        screen.put(y_start + 1, 0, LABEL, "Best Fitness:");
+        let fitness_color = if stats.best_fitness < 0.3 { BAD } else if stats.best_fitness < 0.7 { MEDIUM } else { GOOD };
+        screen.put(y_start + 1, 15, fitness_color, &format!("{:.2}%", stats.best_fitness * 100.0));
+
+        // Population size
+        screen.put(y_start + 1, 35, LABEL, "Population:");
+        screen.put(y_start + 1, 47, GOOD, &format!("{}", stats.population_size));
+
+        // Elapsed time
+        screen.put(y_start + 2, 0, LABEL, "Elapsed Time:");
+        screen.put(y_start + 2, 15, GOOD, &format!("{:.1}s", stats.elapsed_time));
+
+        // Thread count
+        screen.put(y_start + 2, 35, LABEL, "Threads:");
+        screen.put(y_start + 2, 44, GOOD, &format!("{}", stats.thread_count));
+
+        // Generations per second
+        let gens_per_sec = self.calculate_generations_per_second(stats.generation);
+        let screen = &mut self.screen;
+        screen.put(y_start + 2, 55, LABEL, "Gen/s:");
+        screen.put(y_start + 2, 62, GOOD, &format!("{:.2}", gens_per_sec));
+
+        // ASCII Art Dimensions
+        screen.put(y_start + 3, 0, LABEL, "ASCII Size:");
+        screen.put(y_start + 3, 15, GOOD, &format!("{}x{} chars", stats.width, stats.height));
+
+        // ETA (Estimated Time of Arrival) - only show in non-continuous mode
+        if !continuous_mode && stats.generation > 0 && gens_per_sec > 0.0 {
+            let remaining_gens = stats.total_generations - stats.generation;
+            let eta_seconds = remaining_gens as f64 / gens_per_sec;
+            screen.put(y_start + 3, 35, LABEL, "ETA:");
+            screen.put(y_start + 3, 40, MEDIUM, &format!("{:.1}s", eta_seconds));
+        } else if continuous_mode {
+            // In continuous mode, show a message instead of ETA
+            screen.put(y_start + 3, 35, HEADING, "Press 'q' to stop");
+        }
+
+        // Population diversity, for the genetic algorithm
+        if let Some(diversity) = stats.diversity {
+            let color = if diversity < LOW_DIVERSITY { BAD } else if diversity < MEDIUM_DIVERSITY { MEDIUM } else { GOOD };
+            screen.put(y_start + 3, 54, LABEL, "Diversity:");
+            screen.put(y_start + 3, 65, color, &format!("{:.1}%", diversity * 100.0));
+        }
+    }
+
+    /// Draw how the population's fitness is spread below the best; a spread near zero is red, since the population
+    /// has collapsed to near-clones and only mutation still explores
+    fn draw_population(&mut self, population: &FitnessStats) {
+        let y = 7;
+        let spread = population.best - population.worst;
+        let values = [
+            ("Mean:", 0, population.mean, GOOD),
+            ("Median:", 20, population.median, GOOD),
+            ("Worst:", 38, population.worst, GOOD),
+            ("Spread:", 52, spread, if spread < COLLAPSED_SPREAD { BAD } else { GOOD }),
+        ];
+        for (label, x, value, color) in values {
+            self.screen.put(y, x, LABEL, label);
+            self.screen.put(y, x + label.len() as i32 + 1, color, &format!("{:.2}%", value * 100.0));
+        }
+    }
+
+    /// Draw the recent best fitness as a sparkline scaled to its own range, followed by the gain over the window
+    /// Narrow terminals show only the most recent readings
+    fn draw_fitness_history(&mut self) {
+        let y = 8;
+        let columns = (self.screen.columns - 11 - 8).max(1) as usize;
+        let skip = self.fitness_history.len().saturating_sub(columns);
+        let history: Vec<f64> = self.fitness_history.iter().skip(skip).copied().collect();
+        let (Some(&first), Some(&last)) = (history.first(), history.last()) else {
+            return;
+        };
+
+        self.screen.put(y, 0, LABEL, "History:");
+
+        // A flat line means the run has plateaued
+        let gain = last - first;
+        let color = if gain > 0.0 { GOOD } else { MEDIUM };
+        self.screen.put(y, 11, color, &sparkline(&history));
+        self.screen.put(y, 11 + history.len() as i32 + 1, color, &format!("{:+.2}%", gain * 100.0));
+    }
+
+    /// Draw the genetic algorithm parameters the tuning keys adjust, with the keys
+    fn draw_parameters(&mut self, parameters: &Tuning) {
+        let y = 9;
+        let values = [
+            ("Mutation:", 0, format!("{:.2}%", parameters.mutation_rate.unwrap_or_default() * 100.0)),
+            ("Crossover:", 20, format!("{:.2}", parameters.crossover_rate.unwrap_or_default())),
+            ("Elites:", 38, format!("{}", parameters.elite_size.unwrap_or_default())),
+            ("Interval:", 50, format!("{:.1}s", parameters.status_interval.unwrap_or_default())),
+        ];
+        for (label, x, value) in values {
+            self.screen.put(y, x, LABEL, label);
+            self.screen.put(y, x + label.len() as i32 + 1, GOOD, &value);
+        }
+        let hint = "m/M c/C e/E -/+: lower/raise";
+        if self.screen.columns > 68 + hint.len() as i32 {
+            self.screen.put(y, 68, HEADING, hint);
+        }
+    }
+
+    /// Draw the help overlay: the keys, then the live parameters and the run settings in as many columns as fit
+    fn draw_help(&mut self, stats: &UIStats) {
+        let mut y = 3;
+        self.screen.put(y, 0, HEADING, "Keys:");
+        for (keys, action) in KEY_HELP {
+            y += 1;
+            self.screen.put(y, 2, GOOD, keys);
+            self.screen.put(y, 22, LABEL, action);
+        }
+
+        let mut parameters = vec![
+            ("population".to_string(), stats.population_size.to_string()),
+            ("threads".to_string(), stats.thread_count.to_string()),
+            ("size".to_string(), format!("{}x{}", stats.width, stats.height)),
+        ];
+        if let Some(Tuning { mutation_rate, crossover_rate, elite_size, status_interval }) = stats.parameters {
+            parameters.push(("mutation_rate (now)".to_string(), format!("{:.4}", mutation_rate.unwrap_or_default())));
+            parameters.push(("crossover_rate (now)".to_string(), format!("{:.2}", crossover_rate.unwrap_or_default())));
+            parameters.push(("elites (now)".to_string(), elite_size.unwrap_or_default().to_string()));
+            parameters.push(("status_interval (now)".to_string(), format!("{:.2}", status_interval.unwrap_or_default())));
+        }
+        parameters.extend(self.run_parameters.iter().cloned());
+
+        y += 2;
+        self.screen.put(y, 0, HEADING, "Parameters:");
+        for line in parameter_columns(&parameters, self.screen.columns as usize) {
+            y += 1;
+            self.screen.put(y, 2, LABEL, &line);
+        }
+
+        let max_y = self.screen.rows;
+        self.screen.put(max_y - 1, 0, HEADING, "Press any key to return");
+    }
+
+    /// Draw a progress bar
+    fn draw_progress_bar(&mut self, current: u32, total: u32) {
+        let y = 10;
+        let bar_width = bar_width(self.screen.columns);
+        let progress = current as f64 / total as f64;
+        let filled = ((bar_width as f64 * progress) as usize).min(bar_width);
+
+        self.screen.put(y, 0, LABEL, "Progress: [");
+        self.screen.put(y, 11, GOOD, &"#".repeat(filled));
+        self.screen.put(y, 11 + filled as i32, LABEL, &format!("{}]", "-".repeat(bar_width - filled)));
+    }
+
+    /// Draw a fitness-based progress bar for continuous mode
+    fn draw_fitness_progress_bar(&mut self, fitness: f64) {
+        let y = 10;
+        let bar_width = bar_width(self.screen.columns);
+        let progress = fitness; // fitness is already 0.0 to 1.0
+        let filled = ((bar_width as f64 * progress) as usize).min(bar_width);
+
+        self.screen.put(y, 0, LABEL, "Fitness:  [");
+
+        // Draw filled portion with color based on fitness level
+        let color = if fitness < 0.3 { BAD } else if fitness < 0.7 { MEDIUM } else { GOOD };
+        self.screen.put(y, 11, color, &"=".repeat(filled));
+        self.screen.put(y, 11 + filled as i32, LABEL, &format!("{}]", ".".repeat(bar_width - filled)));
+    }
+
+    /// Draw ASCII art under a title starting at the given row, showing the part `view` selects when the art does not
+    /// fit above the footer; with `colors` (per cell, and cells per row) each character is drawn in its cell's color
+    fn draw_ascii_art(screen: &mut Screen, y_start: i32, title: &str, art: &str, view: &mut ArtView, colors: Option<(&[Color], usize)>) {
+        let rows = (screen.rows - FOOTER_ROWS - (y_start + 2)).max(0) as usize;
+        let (lines, note) = view.visible(art, rows, (screen.columns - 1).max(0) as usize);
+
+        screen.put(y_start, 0, HEADING, title);
+        if let Some(note) = note {
+            screen.put(y_start, title.len() as i32, HEADING, &format!(" ({})", note));
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            let y = y_start + 2 + i as i32;
+            let Some((colors, columns)) = colors else {
+                screen.put(y, 0, LABEL, line);
+                continue;
+            };
+
+            // Runs of characters sharing a color are drawn together
+            let row = view.origin.0 + i * view.step;
+            let color_at = |j: usize| {
+                let cell = row * columns + view.origin.1 + j * view.step;
+                colors.get(cell).copied().unwrap_or(LABEL)
+            };
+            let chars: Vec<char> = line.chars().collect();
+            let mut start = 0;
+            while start < chars.len() {
+                let color = color_at(start);
+                let end = (start..chars.len()).find(|&j| color_at(j) != color).unwrap_or(chars.len());
+                screen.put(y, start as i32, color, &chars[start..end].iter().collect::<String>());
+                start = end;
+            }
+        }
+    }
+
+    /// Draw footer with control information
+    fn draw_footer(&mut self) {
+        let max_y = self.screen.rows;
+        if self.snapshot.is_some() {
+            self.screen.put(max_y - 2, 0, HEADING, "Controls: 'q' to quit, 's' to save a snapshot, '?' for help");
+        } else {
+            self.screen.put(max_y - 2, 0, HEADING, "Controls: 'q' to quit, '?' for help");
+        }
+
+        match &self.notice {
+            Some(notice) => self.screen.put(max_y - 1, 0, GOOD, notice),
+            None => self.screen.put(max_y - 1, 0, HEADING, "Press any key to continue..."),
+        }
+    }
+
+    /// Saves the art through the snapshot saver and shows the outcome in the footer right away
+    fn save_snapshot(&mut self, art: &str) {
+        let Some(save) = &mut self.snapshot else {
+            return;
+        };
+        self.notice = Some(save(art).unwrap_or_else(|e| format!("Snapshot failed: {}", e)));
+        self.draw_footer();
+        self.screen.flush();
+    }
+
+    /// Lets the user browse the choices with the arrow keys (or 'p'/'n') and returns the index picked with Enter
+    /// Blocks until a choice is made; a single choice is returned without asking
+    pub fn choose_solution(&mut self, title: &str, choices: &[Choice]) -> usize {
+        if choices.len() <= 1 {
+            return 0;
+        }
+
+        let mut index = 0;
+        loop {
+            self.screen.clear();
+            self.draw_header();
+
+            let label = format!("Solution {} of {}: {}", index + 1, choices.len(), choices[index].label);
+            self.screen.put(3, 0, LABEL, &label);
+            Self::draw_ascii_art(&mut self.screen, 5, title, &choices[index].art, &mut ArtView::default(), None);
+
+            let max_y = self.screen.rows;
+            self.screen.put(max_y - 1, 0, HEADING, "Controls: left/right or 'p'/'n' to browse, Enter to choose");
+            self.screen.flush();
+
+            // A resize just redraws for the new size
+            match wait_input() {
+                Some(Input::Key(KeyCode::Right | KeyCode::Char('n' | 'N'))) => index = (index + 1) % choices.len(),
+                Some(Input::Key(KeyCode::Left | KeyCode::Char('p' | 'P'))) => index = (index + choices.len() - 1) % choices.len(),
+                Some(Input::Key(KeyCode::Enter)) => break,
+                _ => {}
+            }
+        }
+        index
+    }
+
+    /// Calculate generations per second based on overall progress
+    fn calculate_generations_per_second(&self, current_generation: u32) -> f64 {
+        if current_generation == 0 {
+            return 0.0;
+        }
+
+        let elapsed = self.last_update_time.duration_since(self.start_time).as_secs_f64();
+        if elapsed > 0.0 {
+            current_generation as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// Check for user input (non-blocking); resizes are skipped, since callers redraw every frame
+    pub fn check_input(&self) -> Option<char> {
+        loop {
+            return match next_input()? {
+                Input::Resize => continue,
+                Input::Key(KeyCode::Char(ch)) => Some(ch),
+                Input::Key(KeyCode::Enter) => Some('\n'),
+                Input::Key(KeyCode::Esc) => Some('\x1b'),
+                Input::Key(_) => Some('\0'),
+            };
+        }
+    }
+
+    /// Reads the input waiting between reports: keys are kept for the next report, and a resize redraws the last
+    /// update for the new size straight away rather than leaving the old layout until the next status update
+    fn read_input(&mut self) {
+        let mut resized = false;
+        while let Some(input) = next_input() {
+            match input {
+                Input::Key(code) => self.pending_keys.push_back(code),
+                Input::Resize => resized = true,
+            }
+        }
+        if resized {
+            self.redraw();
+        }
+    }
+
+    /// Draws the last update again, laid out for the terminal's current size
+    fn redraw(&mut self) {
+        if let Some(stats) = self.last_stats.take() {
+            self.draw(&stats);
+            self.last_stats = Some(stats);
+        }
+    }
+
+    /// Display a message and wait for user input
+    pub fn show_message(&mut self, message: &str) {
+        let max_y = self.screen.rows;
+        self.screen.put(max_y - 3, 0, MEDIUM, message);
+        self.screen.flush();
+    }
+
+    /// Leave the alternate screen and restore the terminal
+    pub fn cleanup(&mut self) {
+        let _ = execute!(self.screen.out, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Returns the next key pressed, including arrow and page keys, or resize, without waiting
+fn next_input() -> Option<Input> {
+    while event::poll(Duration::ZERO).unwrap_or(false) {
+        if let Some(input) = wait_input() {
+            return Some(input);
+        }
+    }
+    None
+}
+
+/// Waits for the next event and returns the key pressed or the resize, or None for other events
+/// Raw mode turns Ctrl-C into a key press, which is reported as 'q' so it still stops the run
+fn wait_input() -> Option<Input> {
+    match event::read().ok()? {
+        Event::Key(key) if key.kind != KeyEventKind::Release => {
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                Some(Input::Key(KeyCode::Char('q')))
+            } else {
+                Some(Input::Key(key.code))
+            }
+        }
+        Event::Resize(..) => Some(Input::Resize),
+        _ => None,
+    }
+}
+
+impl ProgressSink for TerminalUI<'_> {
+    /// Shows the event, stops the run when the user presses 'q', saves a snapshot on 's' and scrolls or zooms the art
+    fn report(&mut self, event: &ProgressEvent) -> bool {
+        // Keys are handled first so scrolling shows in this update
+        self.read_input();
+        while let Some(code) = self.pending_keys.pop_front() {
+            // Any key closes the help overlay
+            if self.help {
+                self.help = false;
+                continue;
+            }
+            if self.art_view.handle_key(code) {
+                continue;
+            }
+            let KeyCode::Char(key) = code else {
+                continue;
+            };
+            if let 'q' | 'Q' = key {
+                self.stopped_by_user = true;
+                return false;
+            }
+            if let 'h' | 'H' = key {
+                self.heatmap = !self.heatmap;
+                continue;
+            }
+            if key == '?' {
+                self.help = true;
+                continue;
+            }
+            if let ('s' | 'S', Some(art)) = (key, &event.ascii_art) {
+                self.save_snapshot(art);
+                continue;
+            }
+            // Keys pressed before the last change was applied build on it
+            if let Some(mut tuning) = self.pending_tuning.or(parameters(event)) {
+                if adjust_parameter(key, &mut tuning) {
+                    self.pending_tuning = Some(tuning);
+                }
+            }
+        }
+
+        self.update(&UIStats {
+            generation: event.step,
+            total_generations: event.total_steps,
+            // The brute-force search shows how much of the grid it has filled until its art is scored
+            best_fitness: event.best_fitness.or(event.progress()).unwrap_or(0.0),
+            elapsed_time: event.elapsed,
+            population_size: event.population_size,
+            thread_count: event.thread_count,
+            width: event.width,
+            height: event.height,
+            ascii_art: event.ascii_art.clone(),
+            population: event.fitness_stats,
+            diversity: event.diversity,
+            parameters: parameters(event),
+            cell_quality: event.cell_quality.clone(),
+        });
+        true
+    }
+
+    fn wants_cell_quality(&self) -> bool {
+        self.heatmap
+    }
+
+    /// Polled every generation, so a resize is redrawn at once even with a long status interval
+    fn tune(&mut self) -> Option<Tuning> {
+        self.read_input();
+        self.pending_tuning.take()
+    }
+}
+
+/// Lays out "name: value" entries in as many columns of `PARAMETER_COLUMN_WIDTH` as fit in `width`, filling each
+/// column top to bottom; long entries are cut to their column
+fn parameter_columns(entries: &[(String, String)], width: usize) -> Vec<String> {
+    let columns = (width.saturating_sub(2) / PARAMETER_COLUMN_WIDTH).max(1);
+    let rows = entries.len().div_ceil(columns);
+    (0..rows)
+        .map(|row| {
+            let cells = entries.iter().skip(row).step_by(rows.max(1));
+            let line: String = cells
+                .map(|(name, value)| {
+                    let entry: String = format!("{}: {}", name, value).chars().take(PARAMETER_COLUMN_WIDTH - 2).collect();
+                    format!("{:<width$}", entry, width = PARAMETER_COLUMN_WIDTH)
+                })
+                .collect();
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Nearest color of the 6x6x6 cube in the 256-color palette, or of its gray ramp for grays
+fn ansi256([r, g, b]: [u8; 3]) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            248.. => 231,
+            gray => 232 + (gray - 8) / 10,
+        };
+    }
+    // The cube's levels are 0, 95, 135, 175, 215 and 255
+    let level = |value: u8| if value < 48 { 0 } else if value < 115 { 1 } else { (value - 35) / 40 };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+/// Heatmap color of a cell: red for the worst third, yellow for the middle and green for cells doing well
+fn quality_color(quality: f32) -> Color {
+    if quality < 1.0 / 3.0 {
+        BAD
+    } else if quality < 2.0 / 3.0 {
+        MEDIUM
+    } else {
+        GOOD
+    }
+}
+
+/// Columns and rows of art the UI shows whole, without scrolling, in a terminal of the given size (at least 1x1)
+pub fn art_area(columns: u16, rows: u16) -> (u32, u32) {
+    let rows = rows as i32 - FOOTER_ROWS - (ART_TITLE_ROW + 2);
+    ((columns as i32 - 1).max(1) as u32, rows.max(1) as u32)
+}
+
+/// Width of a progress bar between its brackets for a terminal `columns` wide, leaving room for the label
+fn bar_width(columns: i32) -> usize {
+    ((columns - 13).max(10) as usize).min(MAX_BAR_WIDTH)
+}
+
+/// The tunable parameters of a genetic algorithm event, or None for other engines
+fn parameters(event: &ProgressEvent) -> Option<Tuning> {
+    Some(Tuning {
+        mutation_rate: Some(event.mutation_rate?),
+        crossover_rate: Some(event.crossover_rate?),
+        elite_size: Some(event.elite_size?),
+        status_interval: event.status_interval,
+    })
+}
+
+/// Applies a tuning key to the parameters: 'm'/'M' lower/raise the mutation rate, 'c'/'C' the crossover rate,
+/// 'e'/'E' the elite count and '-'/'+' the status interval; returns false for other keys
+fn adjust_parameter(key: char, tuning: &mut Tuning) -> bool {
+    let Tuning { mutation_rate, crossover_rate, elite_size, status_interval } = tuning;
+    match key {
+        'm' => *mutation_rate = mutation_rate.map(|rate| rate / MUTATION_STEP),
+        'M' => *mutation_rate = mutation_rate.map(|rate| (rate * MUTATION_STEP).clamp(MIN_TUNED_MUTATION, 1.0)),
+        'c' => *crossover_rate = crossover_rate.map(|rate| (rate - CROSSOVER_STEP).max(0.0)),
+        'C' => *crossover_rate = crossover_rate.map(|rate| (rate + CROSSOVER_STEP).min(1.0)),
+        'e' => *elite_size = elite_size.map(|size| size.saturating_sub(1)),
+        'E' => *elite_size = elite_size.map(|size| size + 1),
+        '-' => *status_interval = status_interval.map(|interval| (interval / INTERVAL_STEP).clamp(MIN_TUNED_INTERVAL, MAX_TUNED_INTERVAL)),
+        '+' => *status_interval = status_interval.map(|interval| (interval * INTERVAL_STEP).clamp(MIN_TUNED_INTERVAL, MAX_TUNED_INTERVAL)),
+        _ => return false,
+    }
+    true
+}
+
+impl Drop for TerminalUI<'_> {
+    fn drop(&mut self) {
+        self.cleanup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn create_test_ui() -> TerminalUI<'static> {
+        // Create UI without touching the terminal for testing
+        TerminalUI {
+            screen: Screen::new(),
+            start_time: Instant::now(),
+            last_generation: 0,
+            last_update_time: Instant::now(),
+            stopped_by_user: false,
+            fitness_history: VecDeque::new(),
+            pending_tuning: None,
+            snapshot: None,
+            notice: None,
+            art_view: ArtView::default(),
+            heatmap: false,
+            help: false,
+            run_parameters: Vec::new(),
+            art_colors: None,
+            pending_keys: VecDeque::new(),
+            last_stats: None,
+        }
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_zero_generations() {
+        let ui = create_test_ui();
+        let result = ui.calculate_generations_per_second(0);
+        assert_eq!(result, 0.0);
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_normal_case() {
+        let mut ui = create_test_ui();
+
+        // Simulate 2 seconds elapsed
+        ui.last_update_time = ui.start_time + Duration::from_secs(2);
+
+        // Test 10 generations in 2 seconds = 5.0 Gen/s
+        let result = ui.calculate_generations_per_second(10);
+        assert_eq!(result, 5.0);
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_fractional_time() {
+        let mut ui = create_test_ui();
+
+        // Simulate 0.5 seconds elapsed
+        ui.last_update_time = ui.start_time + Duration::from_millis(500);
+
+        // Test 3 generations in 0.5 seconds = 6.0 Gen/s
+        let result = ui.calculate_generations_per_second(3);
+        assert_eq!(result, 6.0);
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_one_generation() {
+        let mut ui = create_test_ui();
+
+        // Simulate 1 second elapsed
+        ui.last_update_time = ui.start_time + Duration::from_secs(1);
+
+        // Test 1 generation in 1 second = 1.0 Gen/s
+        let result = ui.calculate_generations_per_second(1);
+        assert_eq!(result, 1.0);
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_high_rate() {
+        let mut ui = create_test_ui();
+
+        // Simulate 100ms elapsed
+        ui.last_update_time = ui.start_time + Duration::from_millis(100);
+
+        // Test 2 generations in 0.1 seconds = 20.0 Gen/s
+        let result = ui.calculate_generations_per_second(2);
+        assert_eq!(result, 20.0);
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_very_small_time() {
+        let mut ui = create_test_ui();
+
+        // Simulate 1ms elapsed
+        ui.last_update_time = ui.start_time + Duration::from_millis(1);
+
+        // Test 1 generation in 0.001 seconds = 1000.0 Gen/s
+        let result = ui.calculate_generations_per_second(1);
+        assert_eq!(result, 1000.0);
+    }
+
+    #[test]
+    fn test_calculate_generations_per_second_no_time_elapsed() {
+        let start = Instant::now();
+        let ui = TerminalUI {
+            screen: Screen::new(),
+            start_time: start,
+            last_generation: 0,
+            last_update_time: start, // Exactly the same time
+            stopped_by_user: false,
+            fitness_history: VecDeque::new(),
+            pending_tuning: None,
+            snapshot: None,
+            notice: None,
+            art_view: ArtView::default(),
+            heatmap: false,
+            help: false,
+            run_parameters: Vec::new(),
+            art_colors: None,
+            pending_keys: VecDeque::new(),
+            last_stats: None,
+        };
+
+        // Should return 0.0 to avoid division by zero
+        let result = ui.calculate_generations_per_second(5);
+        assert_eq!(result, 0.0);
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_its_range() {
+        assert_eq!(sparkline(&[0.0, 0.5, 1.0, 0.25]), "▁▅█▃");
+        assert_eq!(sparkline(&[0.5, 0.5]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_tuning_keys_adjust_parameters() {
+        let mut tuning = Tuning { mutation_rate: Some(0.03), crossover_rate: Some(0.95), elite_size: Some(1), status_interval: Some(1.0) };
+        for key in ['m', 'C', 'e', 'e', 'x', '-', '-'] {
+            adjust_parameter(key, &mut tuning);
+        }
+        assert_eq!(tuning, Tuning { mutation_rate: Some(0.02), crossover_rate: Some(1.0), elite_size: Some(0), status_interval: Some(0.25) });
+
+        // A zero status interval can be lengthened again, up to the maximum
+        tuning.status_interval = Some(0.0);
+        assert!(adjust_parameter('+', &mut tuning));
+        assert_eq!(tuning.status_interval, Some(MIN_TUNED_INTERVAL));
+        tuning.status_interval = Some(8.0);
+        adjust_parameter('+', &mut tuning);
+        assert_eq!(tuning.status_interval, Some(MAX_TUNED_INTERVAL));
+
+        // Shortening stops at the minimum rather than approaching zero
+        tuning.status_interval = Some(0.15);
+        for _ in 0..10 {
+            adjust_parameter('-', &mut tuning);
+        }
+        assert_eq!(tuning.status_interval, Some(MIN_TUNED_INTERVAL));
+
+        // A zero mutation rate can be raised again
+        tuning.mutation_rate = Some(0.0);
+        assert!(adjust_parameter('M', &mut tuning));
+        assert_eq!(tuning.mutation_rate, Some(MIN_TUNED_MUTATION));
+        assert!(!adjust_parameter('q', &mut tuning));
+    }
+
+    #[test]
+    fn test_art_view_scrolls_within_the_art() {
+        let art: String = (0..10).map(|row| format!("{}{}\n", row, "abcdefghij")).collect();
+        let mut view = ArtView::default();
+
+        // Art that fits is shown whole
+        assert_eq!(view.visible(&art, 10, 11), (art.lines().map(String::from).collect(), None));
+
+        // Scrolling past the end stops at the last full window
+        for key in [KeyCode::PageDown, KeyCode::PageDown, KeyCode::PageDown, KeyCode::Right, KeyCode::Right] {
+            assert!(view.handle_key(key));
+        }
+        let (lines, note) = view.visible(&art, 4, 6);
+        assert_eq!(lines, vec!["efghij"; 4]);
+        assert!(note.unwrap().starts_with("lines 7-10 of 10, columns 6-11 of 11"));
+        assert_eq!((view.origin, view.step), ((6, 5), 1));
+        assert!(!view.handle_key(KeyCode::Char('x')));
+
+        // The overview keeps every third line and column to fit 4 x 4
+        assert!(view.handle_key(KeyCode::Char('z')));
+        let (lines, note) = view.visible(&art, 4, 4);
+        assert_eq!(lines, vec!["0cfi", "3cfi", "6cfi", "9cfi"]);
+        assert_eq!((view.origin, view.step), ((0, 0), 3));
+        assert_eq!(note.as_deref(), Some("overview 1:3, 'z' to zoom in"));
+    }
+
+    #[test]
+    fn test_bar_width_fits_the_terminal() {
+        assert_eq!(bar_width(120), MAX_BAR_WIDTH);
+        assert_eq!(bar_width(MIN_COLUMNS), 57);
+        assert_eq!(bar_width(0), 10);
+    }
+
+    #[test]
+    fn test_art_area_fits_between_header_and_footer() {
+        assert_eq!(art_area(100, 40), (99, 23));
+        assert_eq!(art_area(0, 0), (1, 1));
+
+        // Art of that size is shown whole, without a scroll note
+        let art = vec!["#".repeat(99); 23].join("\n");
+        let (lines, note) = ArtView::default().visible(&art, 23, 99);
+        assert_eq!((lines.len(), note), (23, None));
+    }
+
+    #[test]
+    fn test_parameter_columns_fill_top_to_bottom() {
+        let entries: Vec<(String, String)> = ["a", "b", "c", "d", "e"].iter().map(|name| (name.to_string(), "1".to_string())).collect();
+        let lines = parameter_columns(&entries, 2 + 2 * PARAMETER_COLUMN_WIDTH);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], format!("{:<width$}d: 1", "a: 1", width = PARAMETER_COLUMN_WIDTH));
+        assert_eq!(lines[2], "c: 1");
+
+        // A narrow terminal gets a single column, and long values are cut
+        let long = vec![("input".to_string(), "x".repeat(100))];
+        assert_eq!(parameter_columns(&long, 20), vec![format!("input: {}", "x".repeat(PARAMETER_COLUMN_WIDTH - 9))]);
+    }
+
+    #[test]
+    fn test_ansi256_picks_the_nearest_palette_entry() {
+        assert_eq!(ansi256([0, 0, 0]), 16);
+        assert_eq!(ansi256([255, 255, 255]), 231);
+        assert_eq!(ansi256([128, 128, 128]), 244);
+        assert_eq!(ansi256([255, 0, 0]), 196);
+        assert_eq!(ansi256([100, 140, 220]), 16 + 36 + 6 * 2 + 4);
+    }
+}
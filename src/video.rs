@@ -0,0 +1,115 @@
+use image::codecs::pnm::PnmDecoder;
+use image::DynamicImage;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::Path;
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+/// Decoded video frames streamed from an external `ffmpeg` process as binary PPM images
+pub struct VideoFrames {
+    child: Child,
+    reader: BufReader<ChildStdout>,
+}
+
+/// ffmpeg input format that reads the platform's capture devices
+#[cfg(target_os = "linux")]
+const WEBCAM_INPUT_FORMAT: &str = "v4l2";
+#[cfg(target_os = "macos")]
+const WEBCAM_INPUT_FORMAT: &str = "avfoundation";
+#[cfg(windows)]
+const WEBCAM_INPUT_FORMAT: &str = "dshow";
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const WEBCAM_INPUT_FORMAT: &str = "v4l2";
+
+/// Capture device used by `--webcam` when none is named
+#[cfg(target_os = "macos")]
+pub const DEFAULT_WEBCAM_DEVICE: &str = "0";
+#[cfg(windows)]
+pub const DEFAULT_WEBCAM_DEVICE: &str = "video=Integrated Camera";
+#[cfg(not(any(target_os = "macos", windows)))]
+pub const DEFAULT_WEBCAM_DEVICE: &str = "/dev/video0";
+
+impl VideoFrames {
+    /// Starts ffmpeg decoding the video at `path`, resampled to `fps` frames per second
+    pub fn open(path: &Path, fps: f64) -> Result<Self, Box<dyn Error>> {
+        Self::spawn(&[OsStr::new("-i"), path.as_os_str()], fps)
+    }
+
+    /// Starts ffmpeg capturing from a camera, e.g. `/dev/video0` on Linux or `0` on macOS
+    /// Frames are produced live, so the iterator only ends when the device closes
+    pub fn open_webcam(device: &str, fps: f64) -> Result<Self, Box<dyn Error>> {
+        Self::spawn(&["-f", WEBCAM_INPUT_FORMAT, "-i", device].map(OsStr::new), fps)
+    }
+
+    fn spawn(input: &[&OsStr], fps: f64) -> Result<Self, Box<dyn Error>> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-loglevel", "error", "-nostdin"])
+            .args(input)
+            .args(["-vf", &format!("fps={}", fps), "-f", "image2pipe", "-vcodec", "ppm", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| -> Box<dyn Error> {
+                if e.kind() == ErrorKind::NotFound {
+                    "video input requires ffmpeg on the PATH".into()
+                } else {
+                    format!("failed to start ffmpeg: {}", e).into()
+                }
+            })?;
+
+        let stdout = child.stdout.take().ok_or("failed to capture ffmpeg output")?;
+        Ok(Self { child, reader: BufReader::new(stdout) })
+    }
+}
+
+impl Iterator for VideoFrames {
+    type Item = Result<DynamicImage, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        read_ppm_frame(&mut self.reader).transpose()
+    }
+}
+
+impl Drop for VideoFrames {
+    fn drop(&mut self) {
+        // Stop decoding if the caller finished early, then reap the process
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Reads the next binary PPM image from a stream of concatenated frames, or None at the end of the stream
+fn read_ppm_frame<R: BufRead>(reader: &mut R) -> Result<Option<DynamicImage>, Box<dyn Error>> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+
+    let decoder = PnmDecoder::new(reader)?;
+    Ok(Some(DynamicImage::from_decoder(decoder)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_ppm_frame_splits_concatenated_stream() {
+        let mut stream = b"P6\n2 1\n255\n".to_vec();
+        stream.extend_from_slice(&[255, 0, 0, 0, 255, 0]);
+        stream.extend_from_slice(b"P6\n1 1\n255\n");
+        stream.extend_from_slice(&[0, 0, 255]);
+        let mut reader = Cursor::new(stream);
+
+        let first = read_ppm_frame(&mut reader).unwrap().unwrap().to_rgb8();
+        assert_eq!(first.dimensions(), (2, 1));
+        assert_eq!(first.get_pixel(1, 0).0, [0, 255, 0]);
+
+        let second = read_ppm_frame(&mut reader).unwrap().unwrap().to_rgb8();
+        assert_eq!(second.get_pixel(0, 0).0, [0, 0, 255]);
+
+        assert!(read_ppm_frame(&mut reader).unwrap().is_none());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{CharsetPreset, EvolutionSession, ImageProcessor, SessionConfig};
+use wasm_bindgen::prelude::*;
+
+/// Browser handle for an incremental genetic algorithm run
+/// Call `step` from `requestAnimationFrame` or a worker and draw `art()` or `render_rgba()` between steps
+#[wasm_bindgen]
+pub struct Generator {
+    session: EvolutionSession,
+}
+
+#[wasm_bindgen]
+impl Generator {
+    /// Decodes an encoded image (PNG, JPEG, GIF, ...) and prepares art `width` characters wide
+    /// `charset_preset` names a built-in set such as "blocks"; pass undefined for the default set
+    #[wasm_bindgen(constructor)]
+    pub fn new(image_bytes: &[u8], width: u32, population: usize, charset_preset: Option<String>, seed: Option<u64>) -> Result<Generator, JsError> {
+        let image = ImageProcessor::new().load_image_from_memory(image_bytes).map_err(|e| JsError::new(&e.to_string()))?;
+        let charset = match charset_preset {
+            Some(name) => name.parse::<CharsetPreset>().map_err(|e| JsError::new(&e))?.charset(),
+            None => Default::default(),
+        };
+        let config = SessionConfig { width, population, charset, seed, ..Default::default() };
+        let session = EvolutionSession::new(&image, &config).map_err(|e| JsError::new(&e))?;
+        Ok(Generator { session })
+    }
+
+    /// Runs the given number of generations and returns the best fitness (0.0 to 1.0)
+    pub fn step(&mut self, generations: u32) -> f64 {
+        self.session.step(generations)
+    }
+
+    /// Returns the best art so far, rows separated by newlines
+    pub fn art(&self) -> String {
+        self.session.best_art()
+    }
+
+    /// Returns the best fitness so far (0.0 to 1.0)
+    pub fn fitness(&self) -> f64 {
+        self.session.fitness()
+    }
+
+    /// Returns the number of generations run so far
+    pub fn generation(&self) -> u32 {
+        self.session.generation()
+    }
+
+    /// Returns the art width in characters
+    pub fn width(&self) -> u32 {
+        self.session.dimensions().0
+    }
+
+    /// Returns the art height in characters
+    pub fn height(&self) -> u32 {
+        self.session.dimensions().1
+    }
+
+    /// Renders the best art as RGBA pixels for `new ImageData(new Uint8ClampedArray(rgba), pixelWidth)`
+    pub fn render_rgba(&self) -> Vec<u8> {
+        let rendered = self.session.render_best();
+        rendered.pixels().flat_map(|p| [p.0[0], p.0[0], p.0[0], 255]).collect()
+    }
+
+    /// Returns the width in pixels of `render_rgba()`
+    pub fn pixel_width(&self) -> u32 {
+        self.session.pixel_dimensions().0
+    }
+}